@@ -0,0 +1,380 @@
+//! エージェント管理モジュール
+//!
+//! `/v1/agents` のエージェント一覧・詳細・稼働サービスを表示し、
+//! オフライン/警告状態やサーバーより古いバージョンのエージェントを強調する
+
+pub mod stale;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use colored::*;
+use tabled::{Table, Tabled};
+
+use crate::alerts::parse_timestamp;
+use crate::util::{format_duration, parse_version};
+use crate::{AgentInfo, ApiClient, HealthResponse, ServiceInfo};
+
+/// エージェントの状態（重要度の高い順）
+pub const STATUSES: [&str; 3] = ["offline", "warning", "online"];
+
+/// 状態の並び順（未知の値は最後）
+fn status_rank(status: &str) -> usize {
+    STATUSES
+        .iter()
+        .position(|s| *s == status)
+        .unwrap_or(STATUSES.len())
+}
+
+pub fn status_colored(status: &str) -> ColoredString {
+    match status {
+        "online" => status.green(),
+        "warning" => status.yellow().bold(),
+        "offline" => status.red().bold(),
+        _ => status.dimmed(),
+    }
+}
+
+/// サーバーと比べたエージェントのバージョン
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionLag {
+    Current,
+    /// サーバーより古い
+    Behind,
+    Unknown,
+}
+
+pub fn version_lag(agent: Option<&str>, server: Option<&str>) -> VersionLag {
+    match (
+        agent.and_then(parse_version),
+        server.and_then(parse_version),
+    ) {
+        (Some(a), Some(s)) if a < s => VersionLag::Behind,
+        (Some(_), Some(_)) => VersionLag::Current,
+        _ => VersionLag::Unknown,
+    }
+}
+
+fn version_colored(version: Option<&str>, lag: VersionLag) -> String {
+    match (version, lag) {
+        (None, _) => "-".dimmed().to_string(),
+        (Some(v), VersionLag::Behind) => format!("{} (旧)", v).yellow().to_string(),
+        (Some(v), _) => v.to_string(),
+    }
+}
+
+/// 最終確認からの経過時間（例: 「5分前」）
+pub fn since_label(timestamp: &str, now: DateTime<Utc>) -> String {
+    match parse_timestamp(timestamp) {
+        Some(at) => format!(
+            "{}前",
+            format_duration((now - at).max(chrono::Duration::zero()))
+        ),
+        None => timestamp.to_string(),
+    }
+}
+
+/// ID・ID前方一致・ホスト名でエージェントを特定
+pub fn find_agent<'a>(agents: &'a [AgentInfo], key: &str) -> Result<&'a AgentInfo> {
+    if let Some(agent) = agents
+        .iter()
+        .find(|a| a.agent_id == key || a.hostname.eq_ignore_ascii_case(key))
+    {
+        return Ok(agent);
+    }
+
+    let candidates: Vec<&AgentInfo> = agents
+        .iter()
+        .filter(|a| a.agent_id.starts_with(key))
+        .collect();
+    match candidates.as_slice() {
+        [agent] => Ok(agent),
+        [] => anyhow::bail!("エージェントが見つかりません: {}", key),
+        _ => anyhow::bail!(
+            "複数のエージェントに一致します: {} ({})",
+            key,
+            candidates
+                .iter()
+                .map(|a| a.agent_id.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// 一覧の絞り込み条件
+#[derive(Debug, Default)]
+pub struct Filter {
+    pub statuses: Vec<String>,
+    /// サーバーより古いバージョンのみ
+    pub outdated: bool,
+}
+
+impl Filter {
+    pub fn matches(&self, agent: &AgentInfo, server_version: Option<&str>) -> bool {
+        (self.statuses.is_empty() || self.statuses.contains(&agent.status))
+            && (!self.outdated
+                || version_lag(agent.version.as_deref(), server_version) == VersionLag::Behind)
+    }
+}
+
+#[derive(Tabled)]
+struct AgentRow {
+    #[tabled(rename = "ホスト名")]
+    hostname: String,
+    #[tabled(rename = "ID")]
+    id: String,
+    #[tabled(rename = "IPアドレス")]
+    ip_address: String,
+    #[tabled(rename = "状態")]
+    status: String,
+    #[tabled(rename = "最終確認")]
+    last_seen: String,
+    #[tabled(rename = "バージョン")]
+    version: String,
+}
+
+#[derive(Tabled)]
+struct ServiceRow {
+    #[tabled(rename = "サービス")]
+    name: String,
+    #[tabled(rename = "ポート")]
+    port: String,
+    #[tabled(rename = "状態")]
+    status: String,
+    #[tabled(rename = "バインド")]
+    bind_address: String,
+    #[tabled(rename = "最終確認")]
+    last_checked: String,
+}
+
+/// 全インターフェースで待ち受けているか
+pub fn is_wildcard_bind(address: &str) -> bool {
+    matches!(address, "0.0.0.0" | "::" | "[::]" | "*")
+}
+
+pub async fn cmd_agents_list(client: &ApiClient, filter: Filter) -> Result<()> {
+    let (agents, health) = tokio::join!(
+        client.get::<Vec<AgentInfo>>("/v1/agents"),
+        client.get::<HealthResponse>("/health"),
+    );
+    let agents = agents?;
+    // サーバーのバージョンが取得できない場合はバージョン比較を省略
+    let server_version = health.ok().map(|h| h.version);
+    let server_version = server_version.as_deref();
+    let now = Utc::now();
+
+    let total = agents.len();
+    let mut agents: Vec<AgentInfo> = agents
+        .into_iter()
+        .filter(|a| filter.matches(a, server_version))
+        .collect();
+    agents.sort_by(|a, b| {
+        status_rank(&a.status)
+            .cmp(&status_rank(&b.status))
+            .then_with(|| a.hostname.cmp(&b.hostname))
+    });
+
+    println!("\n{}", "🖥️ エージェント一覧".bold());
+
+    if agents.is_empty() {
+        println!("条件に一致するエージェントがありません");
+        return Ok(());
+    }
+
+    let count = |status: &str| agents.iter().filter(|a| a.status == status).count();
+    let (online, warning, offline) = (count("online"), count("warning"), count("offline"));
+    let outdated = agents
+        .iter()
+        .filter(|a| version_lag(a.version.as_deref(), server_version) == VersionLag::Behind)
+        .count();
+
+    let shown = agents.len();
+    let rows: Vec<AgentRow> = agents
+        .into_iter()
+        .map(|a| {
+            let lag = version_lag(a.version.as_deref(), server_version);
+            let last_seen = since_label(&a.last_seen, now);
+            AgentRow {
+                id: a.agent_id,
+                ip_address: a.ip_address.unwrap_or_else(|| "-".to_string()),
+                last_seen: if a.status == "online" {
+                    last_seen
+                } else {
+                    last_seen.red().to_string()
+                },
+                status: status_colored(&a.status).to_string(),
+                version: version_colored(a.version.as_deref(), lag),
+                hostname: a.hostname,
+            }
+        })
+        .collect();
+
+    println!("{}", Table::new(rows));
+    println!(
+        "{}件 / 全{}件 (オンライン: {} / 警告: {} / オフライン: {})",
+        shown,
+        total,
+        online.to_string().green(),
+        if warning > 0 {
+            warning.to_string().yellow()
+        } else {
+            warning.to_string().normal()
+        },
+        if offline > 0 {
+            offline.to_string().red()
+        } else {
+            offline.to_string().normal()
+        }
+    );
+    if let (Some(server), true) = (server_version, outdated > 0) {
+        println!(
+            "{} {}件のエージェントがサーバー ({}) より古いバージョンです",
+            "⚠".yellow(),
+            outdated,
+            server
+        );
+    }
+
+    Ok(())
+}
+
+pub async fn cmd_agents_show(client: &ApiClient, key: &str) -> Result<()> {
+    let agents: Vec<AgentInfo> = client.get("/v1/agents").await?;
+    let id = find_agent(&agents, key)?.agent_id.clone();
+    let agent_path = format!("/v1/agents/{}", id);
+    let services_path = format!("{}/services", agent_path);
+
+    let (agent, services, health) = tokio::join!(
+        client.get::<AgentInfo>(&agent_path),
+        client.get::<Vec<ServiceInfo>>(&services_path),
+        client.get::<HealthResponse>("/health"),
+    );
+    let agent = agent?;
+    let server_version = health.ok().map(|h| h.version);
+    let lag = version_lag(agent.version.as_deref(), server_version.as_deref());
+    let now = Utc::now();
+
+    println!("\n{}", format!("🖥️ {}", agent.hostname).bold());
+    println!("{}", "=".repeat(40));
+    println!("ID:         {}", agent.agent_id);
+    println!("IPアドレス: {}", agent.ip_address.as_deref().unwrap_or("-"));
+    println!("状態:       {}", status_colored(&agent.status));
+    println!(
+        "最終確認:   {} ({})",
+        agent.last_seen,
+        since_label(&agent.last_seen, now)
+    );
+    match (&agent.version, lag, &server_version) {
+        (Some(version), VersionLag::Behind, Some(server)) => println!(
+            "バージョン: {} {}",
+            version.yellow(),
+            format!("(サーバー: {} より古いバージョン)", server).yellow()
+        ),
+        (version, _, _) => println!("バージョン: {}", version.as_deref().unwrap_or("-")),
+    }
+
+    println!("\n--- サービス ---");
+    match services {
+        Ok(services) if services.is_empty() => println!("サービスはありません"),
+        Ok(mut services) => {
+            services.sort_by_key(|s| s.port);
+            let rows: Vec<ServiceRow> = services
+                .into_iter()
+                .map(|s| {
+                    let bind = s.bind_address.unwrap_or_else(|| "-".to_string());
+                    ServiceRow {
+                        name: s.name,
+                        port: format!("{}/{}", s.port, s.protocol),
+                        status: match s.status.as_str() {
+                            "running" => s.status.green().to_string(),
+                            "stopped" => s.status.red().to_string(),
+                            _ => s.status.dimmed().to_string(),
+                        },
+                        bind_address: if is_wildcard_bind(&bind) {
+                            bind.yellow().to_string()
+                        } else {
+                            bind
+                        },
+                        last_checked: since_label(&s.last_checked, now),
+                    }
+                })
+                .collect();
+            println!("{}", Table::new(rows));
+        }
+        Err(e) => println!("{} サービス一覧を取得できません: {:#}", "✗".red(), e),
+    }
+    println!();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agent(id: &str, hostname: &str, status: &str, version: Option<&str>) -> AgentInfo {
+        AgentInfo {
+            agent_id: id.to_string(),
+            hostname: hostname.to_string(),
+            ip_address: None,
+            status: status.to_string(),
+            last_seen: "2026-10-01T00:00:00Z".to_string(),
+            version: version.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_version_lag() {
+        assert_eq!(
+            version_lag(Some("0.0.9"), Some("0.1.2")),
+            VersionLag::Behind
+        );
+        assert_eq!(
+            version_lag(Some("0.1.1"), Some("v0.1.2")),
+            VersionLag::Behind
+        );
+        assert_eq!(
+            version_lag(Some("0.1.2"), Some("0.1.2")),
+            VersionLag::Current
+        );
+        assert_eq!(
+            version_lag(Some("0.2.0"), Some("0.1.2")),
+            VersionLag::Current
+        );
+        assert_eq!(version_lag(None, Some("0.1.2")), VersionLag::Unknown);
+        assert_eq!(version_lag(Some("0.1.0"), None), VersionLag::Unknown);
+    }
+
+    #[test]
+    fn test_find_and_filter() {
+        let agents = vec![
+            agent("ag-1001", "web-01", "online", Some("0.1.2")),
+            agent("ag-1002", "web-02", "offline", Some("0.0.9")),
+        ];
+
+        assert_eq!(find_agent(&agents, "WEB-02").unwrap().agent_id, "ag-1002");
+        assert_eq!(find_agent(&agents, "ag-1001").unwrap().hostname, "web-01");
+        assert!(find_agent(&agents, "ag-10").is_err());
+        assert!(find_agent(&agents, "db-01").is_err());
+
+        let filter = Filter {
+            statuses: vec!["offline".to_string(), "warning".to_string()],
+            outdated: false,
+        };
+        assert!(!filter.matches(&agents[0], None));
+        assert!(filter.matches(&agents[1], None));
+
+        let outdated = Filter {
+            outdated: true,
+            ..Default::default()
+        };
+        assert!(!outdated.matches(&agents[0], Some("0.1.2")));
+        assert!(outdated.matches(&agents[1], Some("0.1.2")));
+        assert!(!outdated.matches(&agents[1], None));
+
+        let now = parse_timestamp("2026-10-01T00:05:00Z").unwrap();
+        assert_eq!(since_label(&agents[0].last_seen, now), "5分前");
+        assert!(is_wildcard_bind("::"));
+        assert!(!is_wildcard_bind("127.0.0.1"));
+    }
+}
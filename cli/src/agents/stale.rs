@@ -0,0 +1,371 @@
+//! 無応答エージェントの検出
+//!
+//! `last_seen` がしきい値より古い、またはサーバーがオフラインと判定したエージェントを列挙する。
+//! 監視モードではオフライン化と復旧を通知する
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Local, Utc};
+use colored::*;
+use tabled::{Table, Tabled};
+
+use super::status_colored;
+use crate::alerts::parse_timestamp;
+use crate::util::format_duration;
+use crate::{AgentInfo, ApiClient};
+
+/// 最終確認からの経過時間（`last_seen` を解釈できない場合はなし）
+fn silence(agent: &AgentInfo, now: DateTime<Utc>) -> Option<Duration> {
+    parse_timestamp(&agent.last_seen).map(|at| (now - at).max(Duration::zero()))
+}
+
+/// 無応答とみなすか（最終確認時刻が不明な場合も無応答とする）
+pub fn is_stale(agent: &AgentInfo, threshold: Duration, now: DateTime<Utc>) -> bool {
+    agent.status == "offline" || silence(agent, now).is_none_or(|d| d > threshold)
+}
+
+/// 監視モードで通知する変化
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// 応答が途絶えた
+    Offline {
+        hostname: String,
+        agent_id: String,
+        silence: Option<Duration>,
+    },
+    /// 応答が戻った（無応答を検出してからの時間）
+    Recovered {
+        hostname: String,
+        agent_id: String,
+        downtime: Duration,
+    },
+}
+
+/// 監視モードの状態（無応答のエージェントIDと検出時刻）
+#[derive(Debug, Default)]
+pub struct WatchState {
+    stale: BTreeMap<String, DateTime<Utc>>,
+    initialized: bool,
+}
+
+impl WatchState {
+    /// 最新の一覧を取り込み、前回からの変化を返す（初回は基準として記録のみ）
+    pub fn update(
+        &mut self,
+        agents: &[AgentInfo],
+        threshold: Duration,
+        now: DateTime<Utc>,
+    ) -> Vec<Event> {
+        let mut events = Vec::new();
+        let mut stale = BTreeMap::new();
+
+        for agent in agents {
+            let since = self.stale.get(&agent.agent_id).copied();
+            match (is_stale(agent, threshold, now), since) {
+                (true, Some(since)) => {
+                    stale.insert(agent.agent_id.clone(), since);
+                }
+                (true, None) => {
+                    stale.insert(agent.agent_id.clone(), now);
+                    if self.initialized {
+                        events.push(Event::Offline {
+                            hostname: agent.hostname.clone(),
+                            agent_id: agent.agent_id.clone(),
+                            silence: silence(agent, now),
+                        });
+                    }
+                }
+                (false, Some(since)) => events.push(Event::Recovered {
+                    hostname: agent.hostname.clone(),
+                    agent_id: agent.agent_id.clone(),
+                    downtime: now - since,
+                }),
+                (false, None) => {}
+            }
+        }
+
+        // 一覧から消えたエージェントは追跡をやめる
+        self.stale = stale;
+        self.initialized = true;
+        events
+    }
+
+    pub fn stale_count(&self) -> usize {
+        self.stale.len()
+    }
+}
+
+#[derive(Tabled)]
+struct StaleRow {
+    #[tabled(rename = "ホスト名")]
+    hostname: String,
+    #[tabled(rename = "ID")]
+    id: String,
+    #[tabled(rename = "状態")]
+    status: String,
+    #[tabled(rename = "最終確認")]
+    last_seen: String,
+    #[tabled(rename = "無応答")]
+    silence: String,
+}
+
+/// 無応答のエージェントを表示し、該当があれば終了コード1
+pub async fn cmd_agents_stale(client: &ApiClient, threshold: Duration) -> Result<()> {
+    let agents: Vec<AgentInfo> = client.get("/v1/agents").await?;
+    let now = Utc::now();
+
+    let mut stale: Vec<&AgentInfo> = agents
+        .iter()
+        .filter(|a| is_stale(a, threshold, now))
+        .collect();
+    // 無応答の長い順（不明なものを先頭）
+    stale.sort_by_key(|a| std::cmp::Reverse(silence(a, now).unwrap_or(Duration::MAX)));
+
+    println!(
+        "\n{} (しきい値 {})",
+        "🔕 無応答エージェント".bold(),
+        format_duration(threshold)
+    );
+
+    if stale.is_empty() {
+        println!(
+            "{} すべてのエージェント ({}件) が{}以内に応答しています",
+            "✓".green(),
+            agents.len(),
+            format_duration(threshold)
+        );
+        println!();
+        return Ok(());
+    }
+
+    let rows: Vec<StaleRow> = stale
+        .iter()
+        .map(|a| StaleRow {
+            hostname: a.hostname.clone(),
+            id: a.agent_id.clone(),
+            status: status_colored(&a.status).to_string(),
+            last_seen: a.last_seen.clone(),
+            silence: match silence(a, now) {
+                Some(d) => format_duration(d).red().to_string(),
+                None => "不明".red().to_string(),
+            },
+        })
+        .collect();
+    println!("{}", Table::new(rows));
+    println!(
+        "{}",
+        format!(
+            "{}件 / 全{}件のエージェントが応答していません",
+            stale.len(),
+            agents.len()
+        )
+        .red()
+    );
+    println!();
+
+    std::process::exit(1);
+}
+
+fn print_event(event: &Event) {
+    let time = Local::now().format("%H:%M:%S");
+    match event {
+        Event::Offline {
+            hostname,
+            agent_id,
+            silence,
+        } => println!(
+            "[{}] {} {} ({}) が応答していません{}",
+            time,
+            "🔴 オフライン".red().bold(),
+            hostname.bold(),
+            agent_id,
+            silence
+                .map(|d| format!(" (最終確認 {}前)", format_duration(d)))
+                .unwrap_or_default()
+        ),
+        Event::Recovered {
+            hostname,
+            agent_id,
+            downtime,
+        } => println!(
+            "[{}] {} {} ({}) が応答を再開しました (検出から {})",
+            time,
+            "🟢 復旧".green().bold(),
+            hostname.bold(),
+            agent_id,
+            format_duration(*downtime)
+        ),
+    }
+}
+
+/// 一定間隔で確認し、オフライン化と復旧を通知する
+pub async fn cmd_agents_stale_watch(
+    client: &ApiClient,
+    threshold: Duration,
+    interval: Duration,
+) -> Result<()> {
+    let mut state = WatchState::default();
+    let mut ticker = tokio::time::interval(interval.to_std()?);
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+
+    println!(
+        "\n{} (しきい値 {} / {}ごと / Ctrl+Cで終了)",
+        "🔕 無応答エージェントの監視".bold(),
+        format_duration(threshold),
+        format_duration(interval)
+    );
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = &mut ctrl_c => {
+                println!();
+                return Ok(());
+            }
+        }
+
+        let result = tokio::select! {
+            result = client.get::<Vec<AgentInfo>>("/v1/agents") => result,
+            _ = &mut ctrl_c => {
+                println!();
+                return Ok(());
+            }
+        };
+        let agents = match result {
+            Ok(agents) => agents,
+            Err(e) => {
+                println!(
+                    "[{}] {} {:#}",
+                    Local::now().format("%H:%M:%S"),
+                    "APIリクエスト失敗:".red(),
+                    e
+                );
+                continue;
+            }
+        };
+
+        let first = !state.initialized;
+        let now = Utc::now();
+        let events = state.update(&agents, threshold, now);
+
+        if first {
+            println!(
+                "[{}] {}件を監視中 / 無応答 {}件",
+                Local::now().format("%H:%M:%S"),
+                agents.len(),
+                state.stale_count()
+            );
+            for agent in agents.iter().filter(|a| is_stale(a, threshold, now)) {
+                println!(
+                    "    {} {} ({}) 最終確認 {}",
+                    "·".red(),
+                    agent.hostname,
+                    agent.agent_id,
+                    agent.last_seen
+                );
+            }
+        }
+        for event in &events {
+            print_event(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agent(id: &str, status: &str, last_seen: &str) -> AgentInfo {
+        AgentInfo {
+            agent_id: id.to_string(),
+            hostname: format!("host-{}", id),
+            ip_address: None,
+            status: status.to_string(),
+            last_seen: last_seen.to_string(),
+            version: None,
+        }
+    }
+
+    #[test]
+    fn test_is_stale() {
+        let now = parse_timestamp("2026-10-01T01:00:00Z").unwrap();
+        let threshold = Duration::minutes(15);
+
+        assert!(!is_stale(
+            &agent("a", "online", "2026-10-01T00:50:00Z"),
+            threshold,
+            now
+        ));
+        assert!(is_stale(
+            &agent("a", "online", "2026-10-01T00:40:00Z"),
+            threshold,
+            now
+        ));
+        assert!(is_stale(
+            &agent("a", "offline", "2026-10-01T00:59:00Z"),
+            threshold,
+            now
+        ));
+        assert!(is_stale(&agent("a", "online", "never"), threshold, now));
+    }
+
+    #[test]
+    fn test_watch_transitions() {
+        let threshold = Duration::minutes(15);
+        let t0 = parse_timestamp("2026-10-01T01:00:00Z").unwrap();
+        let mut state = WatchState::default();
+
+        // 初回は基準として記録のみ
+        let events = state.update(
+            &[
+                agent("a", "online", "2026-10-01T00:59:00Z"),
+                agent("b", "offline", "2026-10-01T00:00:00Z"),
+            ],
+            threshold,
+            t0,
+        );
+        assert!(events.is_empty());
+        assert_eq!(state.stale_count(), 1);
+
+        // a が途絶え、b が復旧
+        let t1 = t0 + Duration::minutes(20);
+        let events = state.update(
+            &[
+                agent("a", "online", "2026-10-01T00:59:00Z"),
+                agent("b", "online", "2026-10-01T01:19:00Z"),
+            ],
+            threshold,
+            t1,
+        );
+        assert_eq!(
+            events,
+            vec![
+                Event::Offline {
+                    hostname: "host-a".to_string(),
+                    agent_id: "a".to_string(),
+                    silence: Some(Duration::minutes(21)),
+                },
+                Event::Recovered {
+                    hostname: "host-b".to_string(),
+                    agent_id: "b".to_string(),
+                    downtime: Duration::minutes(20),
+                },
+            ]
+        );
+
+        // 状態が変わらなければ通知しない
+        let t2 = t1 + Duration::minutes(1);
+        let events = state.update(
+            &[
+                agent("a", "online", "2026-10-01T00:59:00Z"),
+                agent("b", "online", "2026-10-01T01:20:00Z"),
+            ],
+            threshold,
+            t2,
+        );
+        assert!(events.is_empty());
+        assert_eq!(state.stale_count(), 1);
+    }
+}
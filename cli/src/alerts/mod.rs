@@ -0,0 +1,325 @@
+//! アラート統計モジュール
+//!
+//! アラートのタイムスタンプから時間帯別の件数・上位タイトル/ソース・平均確認時間を集計
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use colored::*;
+use tabled::{Table, Tabled};
+
+use crate::util::{bar, format_duration, sparkline};
+use crate::{Alert, ApiClient};
+
+/// 集計期間あたりのバケット数の上限
+pub const MAX_BUCKETS: i64 = 1000;
+
+/// 時間帯ごとのレベル別件数
+#[derive(Debug, Clone, PartialEq)]
+pub struct BucketCount {
+    pub start: DateTime<Utc>,
+    pub info: u64,
+    pub warning: u64,
+    pub critical: u64,
+}
+
+impl BucketCount {
+    pub fn total(&self) -> u64 {
+        self.info + self.warning + self.critical
+    }
+}
+
+/// アラート統計
+#[derive(Debug)]
+pub struct AlertStats {
+    pub buckets: Vec<BucketCount>,
+    pub top_titles: Vec<(String, usize)>,
+    pub top_sources: Vec<(String, usize)>,
+    /// 平均確認時間（確認日時のあるアラートがなければなし）
+    pub mean_time_to_ack: Option<Duration>,
+    /// 確認済みだが確認日時のないアラートの件数
+    pub acked_without_time: usize,
+    pub total: usize,
+}
+
+/// アラートの作成日時をパース
+pub fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok()
+}
+
+/// 期間内のアラートを集計（バケット数が [`MAX_BUCKETS`] を超える場合はエラー）
+pub fn compute(
+    alerts: &[Alert],
+    now: DateTime<Utc>,
+    since: Duration,
+    bucket: Duration,
+    top: usize,
+) -> Result<AlertStats> {
+    // バケット境界をエポック基準に揃える
+    let bucket_secs = bucket.num_seconds().max(1);
+    anyhow::ensure!(
+        since.num_seconds() / bucket_secs < MAX_BUCKETS,
+        "集計単位が細かすぎます ({} / {} で{}区間を超えます)",
+        format_duration(since),
+        format_duration(bucket),
+        MAX_BUCKETS
+    );
+    let from = now - since;
+    let start = DateTime::from_timestamp(from.timestamp().div_euclid(bucket_secs) * bucket_secs, 0)
+        .unwrap_or(from);
+    let bucket_count = ((now - start).num_seconds() / bucket_secs + 1) as usize;
+
+    let mut buckets: Vec<BucketCount> = (0..bucket_count)
+        .map(|i| BucketCount {
+            start: start + Duration::seconds(bucket_secs * i as i64),
+            info: 0,
+            warning: 0,
+            critical: 0,
+        })
+        .collect();
+
+    let mut titles: HashMap<&str, usize> = HashMap::new();
+    let mut sources: HashMap<&str, usize> = HashMap::new();
+    let mut ack_total = Duration::zero();
+    let mut ack_count = 0;
+    let mut acked_without_time = 0;
+    let mut total = 0;
+
+    for alert in alerts {
+        let Some(created) = parse_timestamp(&alert.created_at) else {
+            continue;
+        };
+        if created < from || created > now {
+            continue;
+        }
+
+        let index = ((created - start).num_seconds() / bucket_secs) as usize;
+        if let Some(b) = buckets.get_mut(index) {
+            match alert.level.as_str() {
+                "critical" => b.critical += 1,
+                "warning" => b.warning += 1,
+                _ => b.info += 1,
+            }
+        }
+
+        *titles.entry(alert.title.as_str()).or_default() += 1;
+        if let Some(source) = alert.source.as_deref() {
+            *sources.entry(source).or_default() += 1;
+        }
+
+        match alert.acknowledged_at.as_deref().and_then(parse_timestamp) {
+            Some(acked) if acked >= created => {
+                ack_total += acked - created;
+                ack_count += 1;
+            }
+            Some(_) => {}
+            None if alert.acknowledged => acked_without_time += 1,
+            None => {}
+        }
+
+        total += 1;
+    }
+
+    Ok(AlertStats {
+        buckets,
+        top_titles: top_n(titles, top),
+        top_sources: top_n(sources, top),
+        mean_time_to_ack: (ack_count > 0).then(|| ack_total / ack_count),
+        acked_without_time,
+        total,
+    })
+}
+
+/// 件数の多い順に上位N件を取得
+fn top_n(counts: HashMap<&str, usize>, n: usize) -> Vec<(String, usize)> {
+    let mut entries: Vec<(String, usize)> = counts
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect();
+    entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    entries.truncate(n);
+    entries
+}
+
+// ==================== 表示 ====================
+
+#[derive(Tabled)]
+struct BucketRow {
+    #[tabled(rename = "期間")]
+    period: String,
+    #[tabled(rename = "情報")]
+    info: u64,
+    #[tabled(rename = "警告")]
+    warning: u64,
+    #[tabled(rename = "重大")]
+    critical: u64,
+    #[tabled(rename = "合計")]
+    total: u64,
+    #[tabled(rename = "グラフ")]
+    graph: String,
+}
+
+pub async fn cmd_alerts_stats(
+    client: &ApiClient,
+    since: Duration,
+    bucket: Duration,
+    top: usize,
+) -> Result<()> {
+    let alerts: Vec<Alert> = client.get("/alerts").await?;
+    let stats = compute(&alerts, Utc::now(), since, bucket, top)?;
+
+    println!(
+        "\n{}",
+        format!(
+            "📈 アラート推移 (過去{} / {}毎)",
+            format_duration(since),
+            format_duration(bucket)
+        )
+        .bold()
+    );
+    println!("{}", "=".repeat(50));
+
+    if stats.total == 0 {
+        println!("期間内のアラートはありません");
+        println!();
+        return Ok(());
+    }
+
+    let time_format = if bucket >= Duration::days(1) {
+        "%Y-%m-%d"
+    } else {
+        "%m-%d %H:%M"
+    };
+    let max = stats
+        .buckets
+        .iter()
+        .map(BucketCount::total)
+        .max()
+        .unwrap_or(0);
+
+    let rows: Vec<BucketRow> = stats
+        .buckets
+        .iter()
+        .map(|b| BucketRow {
+            period: b.start.format(time_format).to_string(),
+            info: b.info,
+            warning: b.warning,
+            critical: b.critical,
+            total: b.total(),
+            graph: bar(b.total(), max, 30),
+        })
+        .collect();
+    println!("{}", Table::new(rows));
+
+    let series = |f: fn(&BucketCount) -> u64| -> Vec<u64> { stats.buckets.iter().map(f).collect() };
+    println!("\n--- 推移 ---");
+    println!("情報: {}", sparkline(&series(|b| b.info)).green());
+    println!("警告: {}", sparkline(&series(|b| b.warning)).yellow());
+    println!("重大: {}", sparkline(&series(|b| b.critical)).red());
+
+    print_ranking("上位タイトル", &stats.top_titles);
+    print_ranking("上位ソース", &stats.top_sources);
+
+    println!();
+    println!("合計: {}件", stats.total);
+    match stats.mean_time_to_ack {
+        Some(mtta) => println!("平均確認時間: {}", format_duration(mtta)),
+        None if stats.acked_without_time > 0 => println!(
+            "平均確認時間: {}",
+            "算出できません (サーバーが確認日時を返していません)".dimmed()
+        ),
+        None => println!("平均確認時間: -"),
+    }
+    println!();
+
+    Ok(())
+}
+
+fn print_ranking(label: &str, entries: &[(String, usize)]) {
+    if entries.is_empty() {
+        return;
+    }
+    println!("\n--- {} ---", label);
+    for (i, (name, count)) in entries.iter().enumerate() {
+        println!("  {}. {} ({}件)", i + 1, name, count);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alert(level: &str, title: &str, created_at: &str, acknowledged_at: Option<&str>) -> Alert {
+        Alert {
+            id: "1".to_string(),
+            level: level.to_string(),
+            title: title.to_string(),
+            message: String::new(),
+            source: Some("agent-1".to_string()),
+            created_at: created_at.to_string(),
+            acknowledged: acknowledged_at.is_some(),
+            acknowledged_at: acknowledged_at.map(str::to_string),
+            tactics: None,
+            techniques: None,
+        }
+    }
+
+    #[test]
+    fn test_compute_buckets() {
+        let now = parse_timestamp("2026-10-03T12:00:00Z").unwrap();
+        let alerts = vec![
+            alert("critical", "SSH brute force", "2026-10-01T01:00:00Z", None),
+            alert("warning", "SSH brute force", "2026-10-01T02:00:00Z", None),
+            alert("info", "Port scan", "2026-10-03T11:00:00Z", None),
+            alert("info", "Too old", "2026-09-01T00:00:00Z", None),
+        ];
+
+        let stats = compute(&alerts, now, Duration::days(3), Duration::days(1), 5).unwrap();
+
+        assert_eq!(stats.total, 3);
+        assert_eq!(stats.buckets.len(), 4);
+        assert_eq!(stats.buckets[1].critical, 1);
+        assert_eq!(stats.buckets[1].warning, 1);
+        assert_eq!(stats.buckets[3].info, 1);
+        assert_eq!(stats.top_titles[0], ("SSH brute force".to_string(), 2));
+        assert_eq!(stats.top_sources[0], ("agent-1".to_string(), 3));
+
+        // バケット数が多すぎる場合はエラー
+        assert!(compute(&alerts, now, Duration::days(36500), Duration::seconds(1), 5).is_err());
+        assert!(compute(&alerts, now, Duration::days(999), Duration::days(1), 5).is_ok());
+    }
+
+    #[test]
+    fn test_mean_time_to_ack() {
+        let now = parse_timestamp("2026-10-03T12:00:00Z").unwrap();
+        let mut alerts = vec![
+            alert(
+                "info",
+                "a",
+                "2026-10-03T10:00:00Z",
+                Some("2026-10-03T10:30:00Z"),
+            ),
+            alert(
+                "info",
+                "b",
+                "2026-10-03T10:00:00Z",
+                Some("2026-10-03T11:30:00Z"),
+            ),
+            alert("info", "c", "2026-10-03T10:00:00Z", None),
+        ];
+
+        let stats = compute(&alerts, now, Duration::days(1), Duration::hours(1), 5).unwrap();
+        assert_eq!(stats.mean_time_to_ack, Some(Duration::hours(1)));
+
+        // 確認日時を返さないサーバー
+        for alert in &mut alerts {
+            alert.acknowledged_at = None;
+        }
+        let stats = compute(&alerts, now, Duration::days(1), Duration::hours(1), 5).unwrap();
+        assert_eq!(stats.mean_time_to_ack, None);
+        assert_eq!(stats.acked_without_time, 2);
+    }
+}
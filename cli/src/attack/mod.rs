@@ -0,0 +1,472 @@
+//! MITRE ATT&CK マッピング
+//!
+//! 検出ルール・アラートのタクティク/テクニックのタグを正規化し、
+//! 有効なルールのカバレッジをマトリクス表示またはATT&CK Navigatorのレイヤーとして出力する
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use colored::*;
+use tabled::builder::Builder;
+
+use crate::{ApiClient, DetectionRule};
+
+/// Enterprise ATT&CK のタクティク
+#[derive(Debug, PartialEq, Eq)]
+pub struct Tactic {
+    pub id: &'static str,
+    /// Navigator で使用する短縮名
+    pub shortname: &'static str,
+    pub name: &'static str,
+}
+
+/// マトリクスの列順に並べたタクティク一覧
+pub const TACTICS: [Tactic; 14] = [
+    Tactic {
+        id: "TA0043",
+        shortname: "reconnaissance",
+        name: "Reconnaissance",
+    },
+    Tactic {
+        id: "TA0042",
+        shortname: "resource-development",
+        name: "Resource Development",
+    },
+    Tactic {
+        id: "TA0001",
+        shortname: "initial-access",
+        name: "Initial Access",
+    },
+    Tactic {
+        id: "TA0002",
+        shortname: "execution",
+        name: "Execution",
+    },
+    Tactic {
+        id: "TA0003",
+        shortname: "persistence",
+        name: "Persistence",
+    },
+    Tactic {
+        id: "TA0004",
+        shortname: "privilege-escalation",
+        name: "Privilege Escalation",
+    },
+    Tactic {
+        id: "TA0005",
+        shortname: "defense-evasion",
+        name: "Defense Evasion",
+    },
+    Tactic {
+        id: "TA0006",
+        shortname: "credential-access",
+        name: "Credential Access",
+    },
+    Tactic {
+        id: "TA0007",
+        shortname: "discovery",
+        name: "Discovery",
+    },
+    Tactic {
+        id: "TA0008",
+        shortname: "lateral-movement",
+        name: "Lateral Movement",
+    },
+    Tactic {
+        id: "TA0009",
+        shortname: "collection",
+        name: "Collection",
+    },
+    Tactic {
+        id: "TA0011",
+        shortname: "command-and-control",
+        name: "Command and Control",
+    },
+    Tactic {
+        id: "TA0010",
+        shortname: "exfiltration",
+        name: "Exfiltration",
+    },
+    Tactic {
+        id: "TA0040",
+        shortname: "impact",
+        name: "Impact",
+    },
+];
+
+/// タクティクをID・短縮名・名前のいずれかで検索（`credential_access` 形式も可）
+pub fn find_tactic(value: &str) -> Option<&'static Tactic> {
+    let key = value.trim().to_lowercase().replace(['_', ' '], "-");
+    TACTICS.iter().find(|t| {
+        t.id.eq_ignore_ascii_case(&key)
+            || t.shortname == key
+            || t.name.to_lowercase().replace(' ', "-") == key
+    })
+}
+
+/// テクニックIDを正規化（`T1110` / `T1110.001` 形式、大文字小文字は問わない）
+pub fn parse_technique(value: &str) -> Option<String> {
+    let id = value.trim().to_ascii_uppercase();
+    let rest = id.strip_prefix('T')?;
+    let (base, sub) = match rest.split_once('.') {
+        Some((base, sub)) => (base, Some(sub)),
+        None => (rest, None),
+    };
+    let digits = |s: &str, n: usize| s.len() == n && s.chars().all(|c| c.is_ascii_digit());
+    if digits(base, 4) && sub.is_none_or(|s| digits(s, 3)) {
+        Some(id)
+    } else {
+        None
+    }
+}
+
+/// clap 用のテクニックIDパーサ
+pub fn technique_arg(value: &str) -> Result<String, String> {
+    parse_technique(value).ok_or_else(|| {
+        format!(
+            "テクニックIDの形式が不正です: {} (例: T1110, T1110.001)",
+            value
+        )
+    })
+}
+
+/// テクニックが条件に一致するか（親テクニック指定時はサブテクニックも一致）
+pub fn technique_matches(filter: &str, technique: &str) -> bool {
+    let Some(technique) = parse_technique(technique) else {
+        return false;
+    };
+    technique == filter
+        || (!filter.contains('.')
+            && technique
+                .strip_prefix(filter)
+                .is_some_and(|rest| rest.starts_with('.')))
+}
+
+/// Sigmaの `attack.*` タグ
+#[derive(Debug, PartialEq, Eq)]
+pub enum SigmaTag {
+    Tactic(&'static Tactic),
+    Technique(String),
+}
+
+/// Sigmaのタグ（`attack.t1110` / `attack.credential_access`）を変換
+pub fn parse_sigma_tag(tag: &str) -> Option<SigmaTag> {
+    let value = tag.strip_prefix("attack.")?;
+    parse_technique(value)
+        .map(SigmaTag::Technique)
+        .or_else(|| find_tactic(value).map(SigmaTag::Tactic))
+}
+
+/// カバレッジの出力形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CoverageFormat {
+    /// 端末表示（マトリクス）
+    Text,
+    /// ATT&CK Navigator レイヤー (JSON)
+    Navigator,
+}
+
+/// 有効なルールのATT&CKカバレッジ
+///
+/// ルールのタクティクとテクニックの対応はわからないため、それぞれ独立に集計する
+#[derive(Debug, Default)]
+pub struct Coverage {
+    /// タクティク（[`TACTICS`] の位置）ごとのルール名
+    pub tactics: BTreeMap<usize, Vec<String>>,
+    /// テクニックIDごとのルール名
+    pub techniques: BTreeMap<String, Vec<String>>,
+    pub enabled_rules: usize,
+    pub mapped_rules: usize,
+}
+
+fn push_name(names: &mut Vec<String>, name: &str) {
+    if !names.iter().any(|n| n == name) {
+        names.push(name.to_string());
+    }
+}
+
+impl Coverage {
+    pub fn build(rules: &[DetectionRule]) -> Self {
+        let mut coverage = Coverage::default();
+
+        for rule in rules.iter().filter(|r| r.enabled) {
+            coverage.enabled_rules += 1;
+
+            let tactics: Vec<usize> = rule
+                .tactics
+                .iter()
+                .flatten()
+                .filter_map(|t| find_tactic(t))
+                .filter_map(|t| TACTICS.iter().position(|x| x == t))
+                .collect();
+            let techniques: Vec<String> = rule
+                .techniques
+                .iter()
+                .flatten()
+                .filter_map(|t| parse_technique(t))
+                .collect();
+            if tactics.is_empty() && techniques.is_empty() {
+                continue;
+            }
+            coverage.mapped_rules += 1;
+
+            for tactic in tactics {
+                push_name(coverage.tactics.entry(tactic).or_default(), &rule.name);
+            }
+            for technique in techniques {
+                push_name(
+                    coverage.techniques.entry(technique).or_default(),
+                    &rule.name,
+                );
+            }
+        }
+
+        coverage
+    }
+
+    /// ルールが1件もマッピングされていないタクティク
+    pub fn uncovered(&self) -> Vec<&'static Tactic> {
+        TACTICS
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !self.tactics.contains_key(i))
+            .map(|(_, t)| t)
+            .collect()
+    }
+
+    /// カバー済みのタクティクとテクニックごとのルール数
+    pub fn render_text(&self) -> String {
+        let mut out = String::new();
+        out.push_str(&format!("\n{}\n", "🗺️ ATT&CK カバレッジ".bold()));
+        out.push_str(&format!(
+            "有効なルール: {}件 / マッピング済み: {}件\n",
+            self.enabled_rules, self.mapped_rules
+        ));
+
+        if self.mapped_rules == 0 {
+            out.push_str("ATT&CKのタグが付いた有効なルールがありません\n");
+            return out;
+        }
+
+        if !self.tactics.is_empty() {
+            let mut builder = Builder::default();
+            builder.push_record(
+                self.tactics
+                    .keys()
+                    .map(|i| format!("{}\n{}", TACTICS[*i].name, TACTICS[*i].id)),
+            );
+            builder.push_record(
+                self.tactics
+                    .values()
+                    .map(|names| format!("{}件", names.len())),
+            );
+            out.push_str(&format!("{}\n", builder.build()));
+        }
+
+        if !self.techniques.is_empty() {
+            let mut builder = Builder::default();
+            builder.push_record(["テクニック", "ルール数", "ルール"]);
+            for (technique, names) in &self.techniques {
+                builder.push_record([technique.clone(), names.len().to_string(), names.join(", ")]);
+            }
+            out.push_str(&format!("{}\n", builder.build()));
+        }
+
+        let uncovered = self.uncovered();
+        if !uncovered.is_empty() {
+            out.push_str(&format!(
+                "{} {}\n",
+                "未カバーのタクティク:".yellow(),
+                uncovered
+                    .iter()
+                    .map(|t| t.name)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+
+        out
+    }
+
+    /// ATT&CK Navigator のレイヤー (v4.5)
+    ///
+    /// タクティクを指定しないため、テクニックが属するすべてのタクティクに反映される
+    pub fn render_navigator(&self) -> String {
+        let techniques: Vec<serde_json::Value> = self
+            .techniques
+            .iter()
+            .map(|(technique, names)| {
+                serde_json::json!({
+                    "techniqueID": technique,
+                    "score": names.len(),
+                    "comment": names.join(", "),
+                    "enabled": true,
+                })
+            })
+            .collect();
+        let max_score = techniques
+            .iter()
+            .filter_map(|t| t["score"].as_u64())
+            .max()
+            .unwrap_or(1);
+
+        let layer = serde_json::json!({
+            "name": "Ghost detection coverage",
+            "versions": { "layer": "4.5", "navigator": "4.9.1", "attack": "15" },
+            "domain": "enterprise-attack",
+            "description": format!(
+                "有効なルール {}件中 {}件のATT&CKマッピング",
+                self.enabled_rules, self.mapped_rules
+            ),
+            "sorting": 3,
+            "hideDisabled": false,
+            "techniques": techniques,
+            "gradient": {
+                "colors": ["#ffffff", "#66b1ff"],
+                "minValue": 0,
+                "maxValue": max_score,
+            },
+            "legendItems": [],
+            "showTacticRowBackground": false,
+            "selectTechniquesAcrossTactics": true,
+            "selectSubtechniquesWithParent": false,
+        });
+        format!(
+            "{}\n",
+            serde_json::to_string_pretty(&layer).unwrap_or_default()
+        )
+    }
+
+    pub fn render(&self, format: CoverageFormat) -> String {
+        match format {
+            CoverageFormat::Text => self.render_text(),
+            CoverageFormat::Navigator => self.render_navigator(),
+        }
+    }
+}
+
+pub async fn cmd_rules_coverage(
+    client: &ApiClient,
+    format: CoverageFormat,
+    out_file: Option<&Path>,
+) -> Result<()> {
+    let rules: Vec<DetectionRule> = client.get("/v1/rules").await?;
+    if !rules.is_empty() && !rules.iter().any(DetectionRule::has_attack_tags) {
+        eprintln!(
+            "{} サーバーがルールのATT&CKタグを返していないため、カバレッジを集計できません",
+            "⚠".yellow()
+        );
+    }
+    let coverage = Coverage::build(&rules);
+
+    match out_file {
+        Some(path) => {
+            // ファイル出力時は色付けしない
+            colored::control::set_override(false);
+            let rendered = coverage.render(format);
+            colored::control::unset_override();
+            std::fs::write(path, rendered)
+                .with_context(|| format!("カバレッジの書き込みに失敗: {}", path.display()))?;
+            println!(
+                "{} カバレッジを出力しました: {}",
+                "✓".green(),
+                path.display()
+            );
+        }
+        None => print!("{}", coverage.render(format)),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(name: &str, enabled: bool, tactics: &[&str], techniques: &[&str]) -> DetectionRule {
+        DetectionRule {
+            id: name.to_string(),
+            name: name.to_string(),
+            description: String::new(),
+            severity: "high".to_string(),
+            category: "Authentication".to_string(),
+            enabled,
+            conditions: String::new(),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            updated_at: "2024-01-01T00:00:00Z".to_string(),
+            triggered_count: 0,
+            tactics: Some(tactics.iter().map(|s| s.to_string()).collect()),
+            techniques: Some(techniques.iter().map(|s| s.to_string()).collect()),
+        }
+    }
+
+    #[test]
+    fn test_parse_tags() {
+        assert_eq!(parse_technique("t1110").as_deref(), Some("T1110"));
+        assert_eq!(parse_technique("T1110.001").as_deref(), Some("T1110.001"));
+        assert_eq!(parse_technique("T111"), None);
+        assert_eq!(parse_technique("T1110.1"), None);
+        assert_eq!(parse_technique("TA0006"), None);
+
+        assert!(technique_matches("T1110", "T1110.003"));
+        assert!(technique_matches("T1110", "t1110"));
+        assert!(!technique_matches("T1110.001", "T1110"));
+        assert!(!technique_matches("T111", "T1110"));
+
+        assert_eq!(find_tactic("credential_access").unwrap().id, "TA0006");
+        assert_eq!(
+            find_tactic("TA0006").unwrap().shortname,
+            "credential-access"
+        );
+        assert_eq!(
+            find_tactic("Command and Control").unwrap().shortname,
+            "command-and-control"
+        );
+        assert!(find_tactic("credential").is_none());
+
+        assert_eq!(
+            parse_sigma_tag("attack.t1059.001"),
+            Some(SigmaTag::Technique("T1059.001".to_string()))
+        );
+        assert_eq!(
+            parse_sigma_tag("attack.lateral_movement"),
+            Some(SigmaTag::Tactic(&TACTICS[9]))
+        );
+        assert_eq!(parse_sigma_tag("attack.g0007"), None);
+        assert_eq!(parse_sigma_tag("cve.2021-44228"), None);
+    }
+
+    #[test]
+    fn test_coverage() {
+        let rules = vec![
+            rule("SSH brute force", true, &["credential-access"], &["T1110"]),
+            rule(
+                "RDP brute force",
+                true,
+                &["TA0006"],
+                &["t1110", "T1021.001"],
+            ),
+            rule("Untagged", true, &[], &[]),
+            rule("Disabled", false, &["impact"], &["T1486"]),
+            rule("Technique only", true, &[], &["T1046"]),
+        ];
+        let coverage = Coverage::build(&rules);
+
+        assert_eq!(coverage.enabled_rules, 4);
+        assert_eq!(coverage.mapped_rules, 3);
+        assert_eq!(coverage.tactics[&7].len(), 2);
+        assert_eq!(coverage.techniques["T1110"].len(), 2);
+        assert_eq!(coverage.techniques["T1021.001"], vec!["RDP brute force"]);
+        assert_eq!(coverage.techniques["T1046"], vec!["Technique only"]);
+        assert_eq!(coverage.uncovered().len(), TACTICS.len() - 1);
+
+        // タクティクとテクニックを掛け合わせない
+        let layer: serde_json::Value = serde_json::from_str(&coverage.render_navigator()).unwrap();
+        let techniques = layer["techniques"].as_array().unwrap();
+        assert_eq!(techniques.len(), 3);
+        assert_eq!(layer["gradient"]["maxValue"], 2);
+        assert!(techniques.iter().all(|t| t.get("tactic").is_none()));
+    }
+}
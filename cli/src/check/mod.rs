@@ -0,0 +1,394 @@
+//! 監視プラグインモード
+//!
+//! Nagios/Icinga のプラグイン規約（終了コード 0/1/2/3、1行のステータス、`|` 以降のperfdata）に従ってチェック結果を出力
+
+use std::fmt;
+use std::time::Instant;
+
+use anyhow::Result;
+use chrono::Duration;
+use clap::ValueEnum;
+
+use crate::{AgentInfo, AlertCount, ApiClient, HealthResponse, MetricsSummary};
+
+/// チェック対象
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CheckKind {
+    /// サーバーのヘルスチェック（しきい値は応答時間・秒）
+    Health,
+    /// 未確認アラート数
+    Alerts,
+    /// 防御率（%、下回ると警告）
+    DefenseRate,
+    /// オフラインのエージェント数
+    Agents,
+}
+
+impl CheckKind {
+    fn service(self) -> &'static str {
+        match self {
+            Self::Health => "GHOST HEALTH",
+            Self::Alerts => "GHOST ALERTS",
+            Self::DefenseRate => "GHOST DEFENSE RATE",
+            Self::Agents => "GHOST AGENTS",
+        }
+    }
+
+    /// しきい値の既定値 (warn, crit)
+    fn default_thresholds(self) -> (f64, f64) {
+        match self {
+            Self::Health => (1.0, 5.0),
+            Self::Alerts => (1.0, 10.0),
+            Self::DefenseRate => (90.0, 80.0),
+            Self::Agents => (1.0, 5.0),
+        }
+    }
+}
+
+/// プラグインの終了ステータス
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Critical,
+    Unknown,
+}
+
+impl CheckStatus {
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Self::Ok => 0,
+            Self::Warning => 1,
+            Self::Critical => 2,
+            Self::Unknown => 3,
+        }
+    }
+}
+
+impl fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Ok => "OK",
+            Self::Warning => "WARNING",
+            Self::Critical => "CRITICAL",
+            Self::Unknown => "UNKNOWN",
+        })
+    }
+}
+
+/// perfdata の1項目
+#[derive(Debug, Clone)]
+pub struct PerfData {
+    pub label: &'static str,
+    pub value: f64,
+    pub uom: &'static str,
+    pub warn: Option<f64>,
+    pub crit: Option<f64>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+impl PerfData {
+    fn new(label: &'static str, value: f64) -> Self {
+        Self {
+            label,
+            value,
+            uom: "",
+            warn: None,
+            crit: None,
+            min: Some(0.0),
+            max: None,
+        }
+    }
+
+    fn uom(mut self, uom: &'static str) -> Self {
+        self.uom = uom;
+        self
+    }
+
+    fn thresholds(mut self, warn: f64, crit: f64) -> Self {
+        self.warn = Some(warn);
+        self.crit = Some(crit);
+        self
+    }
+
+    fn max(mut self, max: f64) -> Self {
+        self.max = Some(max);
+        self
+    }
+}
+
+impl fmt::Display for PerfData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let opt = |v: Option<f64>| v.map(|v| v.to_string()).unwrap_or_default();
+        write!(
+            f,
+            "{}={}{};{};{};{};{}",
+            self.label,
+            self.value,
+            self.uom,
+            opt(self.warn),
+            opt(self.crit),
+            opt(self.min),
+            opt(self.max)
+        )
+    }
+}
+
+/// チェック結果
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub status: CheckStatus,
+    pub message: String,
+    pub perfdata: Vec<PerfData>,
+}
+
+impl CheckResult {
+    fn unknown(message: impl Into<String>) -> Self {
+        Self {
+            status: CheckStatus::Unknown,
+            message: message.into(),
+            perfdata: Vec::new(),
+        }
+    }
+
+    /// プラグイン出力の1行を生成
+    pub fn render(&self, service: &str) -> String {
+        let mut line = format!("{} {} - {}", service, self.status, self.message);
+        if !self.perfdata.is_empty() {
+            let perf: Vec<String> = self.perfdata.iter().map(|p| p.to_string()).collect();
+            line.push_str(" | ");
+            line.push_str(&perf.join(" "));
+        }
+        line
+    }
+}
+
+/// 値が大きいほど悪い指標を評価
+pub fn evaluate_high(value: f64, warn: f64, crit: f64) -> CheckStatus {
+    if value >= crit {
+        CheckStatus::Critical
+    } else if value >= warn {
+        CheckStatus::Warning
+    } else {
+        CheckStatus::Ok
+    }
+}
+
+/// 値が小さいほど悪い指標を評価
+pub fn evaluate_low(value: f64, warn: f64, crit: f64) -> CheckStatus {
+    if value < crit {
+        CheckStatus::Critical
+    } else if value < warn {
+        CheckStatus::Warning
+    } else {
+        CheckStatus::Ok
+    }
+}
+
+async fn check_health(client: &ApiClient, warn: f64, crit: f64) -> CheckResult {
+    let started = Instant::now();
+    let health: HealthResponse = match client.get("/health").await {
+        Ok(health) => health,
+        // サーバーに到達できない場合は監視上CRITICAL
+        Err(e) => {
+            return CheckResult {
+                status: CheckStatus::Critical,
+                message: format!("{:#}", e),
+                perfdata: Vec::new(),
+            }
+        }
+    };
+    let elapsed = started.elapsed().as_secs_f64();
+
+    let status = match health.status.as_str() {
+        "ok" | "healthy" => evaluate_high(elapsed, warn, crit),
+        "degraded" => CheckStatus::Warning.max(evaluate_high(elapsed, warn, crit)),
+        _ => CheckStatus::Critical,
+    };
+
+    CheckResult {
+        status,
+        message: format!(
+            "status {}, version {}, response {:.3}s",
+            health.status, health.version, elapsed
+        ),
+        perfdata: vec![PerfData::new("time", elapsed)
+            .uom("s")
+            .thresholds(warn, crit)],
+    }
+}
+
+async fn check_alerts(client: &ApiClient, warn: f64, crit: f64) -> Result<CheckResult> {
+    let count: AlertCount = client.get("/alerts/count").await?;
+    let unacknowledged = count.unacknowledged as f64;
+
+    Ok(CheckResult {
+        status: evaluate_high(unacknowledged, warn, crit),
+        message: format!(
+            "{} unacknowledged alerts ({} critical, {} warning, {} info)",
+            count.unacknowledged, count.critical, count.warning, count.info
+        ),
+        perfdata: vec![
+            PerfData::new("unacknowledged", unacknowledged).thresholds(warn, crit),
+            PerfData::new("critical", count.critical as f64),
+            PerfData::new("warning", count.warning as f64),
+            PerfData::new("info", count.info as f64),
+            PerfData::new("total", count.total as f64),
+        ],
+    })
+}
+
+async fn check_defense_rate(client: &ApiClient, warn: f64, crit: f64) -> Result<CheckResult> {
+    let summary: MetricsSummary = client.get("/metrics/summary").await?;
+
+    Ok(CheckResult {
+        status: evaluate_low(summary.defense_rate, warn, crit),
+        message: format!(
+            "defense rate {:.1}% ({} attacks, {} defenses)",
+            summary.defense_rate, summary.total_attacks, summary.total_defenses
+        ),
+        perfdata: vec![
+            PerfData::new("defense_rate", summary.defense_rate)
+                .uom("%")
+                .thresholds(warn, crit)
+                .max(100.0),
+            PerfData::new("attacks", summary.total_attacks as f64).uom("c"),
+            PerfData::new("defenses", summary.total_defenses as f64).uom("c"),
+        ],
+    })
+}
+
+async fn check_agents(client: &ApiClient, warn: f64, crit: f64) -> Result<CheckResult> {
+    let agents: Vec<AgentInfo> = client.get("/v1/agents").await?;
+    let count = |status: &str| agents.iter().filter(|a| a.status == status).count();
+    let (online, warning, offline) = (count("online"), count("warning"), count("offline"));
+
+    let offline_hosts: Vec<&str> = agents
+        .iter()
+        .filter(|a| a.status == "offline")
+        .map(|a| a.hostname.as_str())
+        .collect();
+    let mut message = format!(
+        "{} agents: {} online, {} warning, {} offline",
+        agents.len(),
+        online,
+        warning,
+        offline
+    );
+    if !offline_hosts.is_empty() {
+        message.push_str(&format!(" ({})", offline_hosts.join(", ")));
+    }
+
+    Ok(CheckResult {
+        status: evaluate_high(offline as f64, warn, crit),
+        message,
+        perfdata: vec![
+            PerfData::new("offline", offline as f64)
+                .thresholds(warn, crit)
+                .max(agents.len() as f64),
+            PerfData::new("warning", warning as f64).max(agents.len() as f64),
+            PerfData::new("online", online as f64).max(agents.len() as f64),
+        ],
+    })
+}
+
+/// 値を取るグローバルオプション
+const GLOBAL_OPTIONS_WITH_VALUE: [&str; 2] = ["-s", "--server"];
+
+/// コマンドラインが `check` サブコマンドの実行か（引数エラー時の終了コード判定用）
+pub fn is_check_invocation<S: AsRef<str>>(args: &[S]) -> bool {
+    let mut args = args.iter().skip(1).map(AsRef::as_ref);
+    while let Some(arg) = args.next() {
+        if GLOBAL_OPTIONS_WITH_VALUE.contains(&arg) {
+            args.next();
+        } else if !arg.starts_with('-') {
+            return arg == "check";
+        }
+    }
+    false
+}
+
+/// 引数や設定の誤りをプラグイン規約の UNKNOWN として出力して終了
+pub fn exit_unknown(message: &str) -> ! {
+    println!("GHOST UNKNOWN - {}", message);
+    std::process::exit(CheckStatus::Unknown.exit_code());
+}
+
+pub async fn cmd_check(
+    client: &ApiClient,
+    kind: CheckKind,
+    warn: Option<f64>,
+    crit: Option<f64>,
+    timeout: Duration,
+) -> Result<()> {
+    let (default_warn, default_crit) = kind.default_thresholds();
+    let (warn, crit) = (warn.unwrap_or(default_warn), crit.unwrap_or(default_crit));
+
+    let run = async {
+        match kind {
+            CheckKind::Health => Ok(check_health(client, warn, crit).await),
+            CheckKind::Alerts => check_alerts(client, warn, crit).await,
+            CheckKind::DefenseRate => check_defense_rate(client, warn, crit).await,
+            CheckKind::Agents => check_agents(client, warn, crit).await,
+        }
+    };
+
+    let result = match tokio::time::timeout(timeout.to_std()?, run).await {
+        Ok(Ok(result)) => result,
+        Ok(Err(e)) => CheckResult::unknown(format!("{:#}", e)),
+        Err(_) => CheckResult::unknown(format!("timed out after {}s", timeout.num_seconds())),
+    };
+
+    println!("{}", result.render(kind.service()));
+    std::process::exit(result.status.exit_code());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate() {
+        assert_eq!(evaluate_high(0.0, 1.0, 10.0), CheckStatus::Ok);
+        assert_eq!(evaluate_high(1.0, 1.0, 10.0), CheckStatus::Warning);
+        assert_eq!(evaluate_high(12.0, 1.0, 10.0), CheckStatus::Critical);
+        assert_eq!(evaluate_low(95.0, 90.0, 80.0), CheckStatus::Ok);
+        assert_eq!(evaluate_low(85.0, 90.0, 80.0), CheckStatus::Warning);
+        assert_eq!(evaluate_low(79.9, 90.0, 80.0), CheckStatus::Critical);
+    }
+
+    #[test]
+    fn test_render() {
+        let result = CheckResult {
+            status: CheckStatus::Warning,
+            message: "3 unacknowledged alerts".to_string(),
+            perfdata: vec![
+                PerfData::new("unacknowledged", 3.0).thresholds(1.0, 10.0),
+                PerfData::new("defense_rate", 85.5)
+                    .uom("%")
+                    .thresholds(90.0, 80.0)
+                    .max(100.0),
+            ],
+        };
+
+        assert_eq!(
+            result.render("GHOST ALERTS"),
+            "GHOST ALERTS WARNING - 3 unacknowledged alerts | unacknowledged=3;1;10;0; defense_rate=85.5%;90;80;0;100"
+        );
+        assert_eq!(CheckStatus::Unknown.exit_code(), 3);
+    }
+
+    #[test]
+    fn test_is_check_invocation() {
+        assert!(is_check_invocation(&["ghost", "check", "health"]));
+        assert!(is_check_invocation(&[
+            "ghost", "-s", "http://x", "check", "--bogus"
+        ]));
+        assert!(!is_check_invocation(&[
+            "ghost", "--server", "check", "status"
+        ]));
+        assert!(!is_check_invocation(&["ghost", "rules", "show", "check"]));
+        assert!(!is_check_invocation(&["ghost"]));
+    }
+}
@@ -0,0 +1,444 @@
+//! CI向け出力モジュール
+//!
+//! 監査・検知結果を SARIF 2.1.0（コードスキャン）と JUnit XML（テスト結果）に変換する
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use serde_json::json;
+
+use crate::crypto::policy::{Evaluation, Severity};
+use crate::CryptoAuditResult;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// 出力形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// 端末表示
+    Text,
+    /// SARIF 2.1.0
+    Sarif,
+    /// JUnit XML
+    Junit,
+}
+
+/// 指摘の重要度（SARIFの `level` に対応）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Note,
+    Warning,
+    Error,
+}
+
+impl Level {
+    fn as_str(self) -> &'static str {
+        match self {
+            Level::Note => "note",
+            Level::Warning => "warning",
+            Level::Error => "error",
+        }
+    }
+
+    /// コードスキャンUIで使用される数値の重要度 (0.0-10.0)
+    fn security_severity(self) -> &'static str {
+        match self {
+            Level::Note => "3.0",
+            Level::Warning => "6.0",
+            Level::Error => "9.0",
+        }
+    }
+}
+
+impl From<Severity> for Level {
+    fn from(severity: Severity) -> Self {
+        match severity {
+            Severity::Critical | Severity::High => Level::Error,
+            Severity::Medium => Level::Warning,
+            Severity::Low | Severity::Info => Level::Note,
+        }
+    }
+}
+
+/// 1件の指摘
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub rule_id: String,
+    pub rule_name: String,
+    pub level: Level,
+    pub message: String,
+}
+
+/// 1ターゲット（テストケース）分の結果
+#[derive(Debug, Clone)]
+pub struct Case {
+    pub name: String,
+    pub classname: String,
+    pub findings: Vec<Finding>,
+    /// 実行自体の失敗
+    pub error: Option<String>,
+}
+
+impl Case {
+    /// 警告以上の指摘があるか（情報レベルの指摘のみなら成功扱い）
+    fn is_failure(&self) -> bool {
+        self.error.is_none() && self.findings.iter().any(|f| f.level >= Level::Warning)
+    }
+}
+
+/// CI向けの結果一式
+#[derive(Debug, Clone)]
+pub struct CiReport {
+    /// テストスイート名（例: `ghost crypto audit`）
+    pub suite: String,
+    pub cases: Vec<Case>,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// 暗号監査結果をテストケースに変換
+///
+/// ポリシー指定時はルール違反ごとに指摘とし、未指定時は安全でない結果を指摘とする
+pub fn crypto_case(result: &CryptoAuditResult, evaluation: Option<&Evaluation>) -> Case {
+    let findings = match evaluation {
+        Some(evaluation) => evaluation
+            .violations()
+            .map(|(rule, reasons)| Finding {
+                rule_id: format!("crypto-policy/{}", rule.id),
+                rule_name: rule.description.clone().unwrap_or_else(|| rule.id.clone()),
+                level: rule.severity.into(),
+                message: format!("{}: {}", result.target, reasons.join(", ")),
+            })
+            .collect(),
+        None if !result.is_secure => vec![Finding {
+            rule_id: "crypto/insecure".to_string(),
+            rule_name: "安全でないTLS設定".to_string(),
+            level: if result.security_score < 50 {
+                Level::Error
+            } else {
+                Level::Warning
+            },
+            message: format!(
+                "{}: スコア {} ({} / {}){}",
+                result.target,
+                result.security_score,
+                result.tls_version,
+                result.cipher_suite,
+                result
+                    .chain_error
+                    .as_ref()
+                    .map(|e| format!(" / 証明書: {}", e))
+                    .unwrap_or_default()
+            ),
+        }],
+        None => Vec::new(),
+    };
+
+    Case {
+        name: result.target.clone(),
+        classname: "ghost.crypto.audit".to_string(),
+        findings,
+        error: None,
+    }
+}
+
+/// 監査に失敗したターゲット
+pub fn error_case(target: &str, classname: &str, error: &anyhow::Error) -> Case {
+    Case {
+        name: target.to_string(),
+        classname: classname.to_string(),
+        findings: Vec::new(),
+        error: Some(format!("{:#}", error)),
+    }
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+impl CiReport {
+    pub fn new(suite: &str, cases: Vec<Case>) -> Self {
+        Self {
+            suite: suite.to_string(),
+            cases,
+            generated_at: Utc::now(),
+        }
+    }
+
+    /// 失敗（警告以上の指摘あり）のテストケース数
+    pub fn failures(&self) -> usize {
+        self.cases.iter().filter(|c| c.is_failure()).count()
+    }
+
+    pub fn errors(&self) -> usize {
+        self.cases.iter().filter(|c| c.error.is_some()).count()
+    }
+
+    /// CIを失敗させるか（警告以上の指摘、または実行エラーがある）
+    pub fn is_failed(&self) -> bool {
+        self.failures() > 0 || self.errors() > 0
+    }
+
+    pub fn render_sarif(&self) -> String {
+        let mut rules: BTreeMap<&str, &Finding> = BTreeMap::new();
+        for finding in self.cases.iter().flat_map(|c| &c.findings) {
+            let entry = rules.entry(finding.rule_id.as_str()).or_insert(finding);
+            if finding.level > entry.level {
+                *entry = finding;
+            }
+        }
+
+        let rules: Vec<_> = rules
+            .values()
+            .map(|f| {
+                json!({
+                    "id": f.rule_id,
+                    "name": f.rule_name,
+                    "shortDescription": { "text": f.rule_name },
+                    "defaultConfiguration": { "level": f.level.as_str() },
+                    "properties": { "security-severity": f.level.security_severity() },
+                })
+            })
+            .collect();
+
+        let results: Vec<_> = self
+            .cases
+            .iter()
+            .flat_map(|case| {
+                case.findings.iter().map(move |f| {
+                    json!({
+                        "ruleId": f.rule_id,
+                        "level": f.level.as_str(),
+                        "message": { "text": f.message },
+                        // ターゲットはファイルではないため論理的な位置のみ示す
+                        "locations": [{
+                            "logicalLocations": [{
+                                "name": case.name,
+                                "fullyQualifiedName": format!("{}/{}", case.classname, case.name),
+                            }],
+                        }],
+                    })
+                })
+            })
+            .collect();
+
+        let notifications: Vec<_> = self
+            .cases
+            .iter()
+            .filter_map(|case| {
+                let error = case.error.as_ref()?;
+                Some(json!({
+                    "level": "error",
+                    "message": { "text": format!("{}: {}", case.name, error) },
+                }))
+            })
+            .collect();
+
+        let sarif = json!({
+            "$schema": SARIF_SCHEMA,
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "ghost",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                        "rules": rules,
+                    }
+                },
+                "automationDetails": { "id": format!("{}/", self.suite.replace(' ', "-")) },
+                "invocations": [{
+                    "executionSuccessful": notifications.is_empty(),
+                    "endTimeUtc": self.generated_at.to_rfc3339(),
+                    "toolExecutionNotifications": notifications,
+                }],
+                "results": results,
+            }],
+        });
+
+        serde_json::to_string_pretty(&sarif).unwrap_or_default()
+    }
+
+    pub fn render_junit(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        let _ = writeln!(
+            out,
+            r#"<testsuites name="ghost" tests="{}" failures="{}" errors="{}">"#,
+            self.cases.len(),
+            self.failures(),
+            self.errors()
+        );
+        let _ = writeln!(
+            out,
+            r#"  <testsuite name="{}" tests="{}" failures="{}" errors="{}" timestamp="{}">"#,
+            xml_escape(&self.suite),
+            self.cases.len(),
+            self.failures(),
+            self.errors(),
+            self.generated_at.format("%Y-%m-%dT%H:%M:%S")
+        );
+
+        for case in &self.cases {
+            let open = format!(
+                r#"    <testcase name="{}" classname="{}""#,
+                xml_escape(&case.name),
+                xml_escape(&case.classname)
+            );
+            if let Some(error) = &case.error {
+                let _ = writeln!(
+                    out,
+                    "{}>\n      <error message=\"{}\"/>\n    </testcase>",
+                    open,
+                    xml_escape(error)
+                );
+                continue;
+            }
+
+            let details = case
+                .findings
+                .iter()
+                .map(|f| format!("[{}] {}: {}", f.level.as_str(), f.rule_id, f.message))
+                .collect::<Vec<_>>()
+                .join("\n");
+            if let Some(worst) = case
+                .findings
+                .iter()
+                .max_by_key(|f| f.level)
+                .filter(|_| case.is_failure())
+            {
+                let _ = writeln!(
+                    out,
+                    "{}>\n      <failure message=\"{}\" type=\"{}\">{}</failure>\n    </testcase>",
+                    open,
+                    xml_escape(&worst.message),
+                    worst.level.as_str(),
+                    xml_escape(&details)
+                );
+            } else if !details.is_empty() {
+                // 情報レベルの指摘は成功として出力に残す
+                let _ = writeln!(
+                    out,
+                    "{}>\n      <system-out>{}</system-out>\n    </testcase>",
+                    open,
+                    xml_escape(&details)
+                );
+            } else {
+                let _ = writeln!(out, "{}/>", open);
+            }
+        }
+
+        let _ = writeln!(out, "  </testsuite>\n</testsuites>");
+        out
+    }
+
+    /// 指定形式で標準出力へ出力（`Text` は呼び出し側で表示するため何もしない）
+    pub fn print(&self, format: OutputFormat) {
+        match format {
+            OutputFormat::Text => {}
+            OutputFormat::Sarif => println!("{}", self.render_sarif()),
+            OutputFormat::Junit => print!("{}", self.render_junit()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::testing;
+
+    fn result(target: &str, score: u8) -> CryptoAuditResult {
+        testing::result(
+            target,
+            "TLSv1.2",
+            "TLS_ECDHE_RSA_WITH_AES_128_CBC_SHA",
+            score,
+        )
+    }
+
+    fn report() -> CiReport {
+        CiReport::new(
+            "ghost crypto audit",
+            vec![
+                crypto_case(&result("a.example.com:443", 95), None),
+                crypto_case(&result("b.example.com:443", 40), None),
+                error_case(
+                    "c.example.com:443",
+                    "ghost.crypto.audit",
+                    &anyhow::anyhow!("接続がタイムアウトしました"),
+                ),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_sarif() {
+        let sarif: serde_json::Value = serde_json::from_str(&report().render_sarif()).unwrap();
+        let run = &sarif["runs"][0];
+
+        assert_eq!(sarif["version"], "2.1.0");
+        assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "crypto/insecure");
+        assert_eq!(run["results"].as_array().unwrap().len(), 1);
+        assert_eq!(run["results"][0]["level"], "error");
+        let location = &run["results"][0]["locations"][0];
+        assert!(location.get("physicalLocation").is_none());
+        assert_eq!(location["logicalLocations"][0]["name"], "b.example.com:443");
+        assert_eq!(run["invocations"][0]["executionSuccessful"], false);
+    }
+
+    #[test]
+    fn test_junit() {
+        let report = report();
+        let xml = report.render_junit();
+
+        assert_eq!((report.failures(), report.errors()), (1, 1));
+        assert!(xml
+            .contains(r#"<testsuite name="ghost crypto audit" tests="3" failures="1" errors="1""#));
+        assert!(
+            xml.contains(r#"<testcase name="a.example.com:443" classname="ghost.crypto.audit"/>"#)
+        );
+        assert!(xml.contains(r#"type="error">[error] crypto/insecure: b.example.com:443"#));
+    }
+
+    #[test]
+    fn test_is_failed() {
+        assert!(report().is_failed());
+
+        // 全ターゲットが実行エラー
+        let errors = CiReport::new(
+            "ghost crypto audit",
+            vec![error_case(
+                "c.example.com:443",
+                "ghost.crypto.audit",
+                &anyhow::anyhow!("接続がタイムアウトしました"),
+            )],
+        );
+        assert_eq!(errors.failures(), 0);
+        assert!(errors.is_failed());
+
+        // 情報レベルの指摘のみ
+        let notes = CiReport::new(
+            "ghost detect",
+            vec![Case {
+                name: "a".to_string(),
+                classname: "ghost.detect".to_string(),
+                findings: vec![Finding {
+                    rule_id: "detect/info".to_string(),
+                    rule_name: "info".to_string(),
+                    level: Level::Note,
+                    message: "m".to_string(),
+                }],
+                error: None,
+            }],
+        );
+        assert!(!notes.is_failed());
+        assert!(notes
+            .render_junit()
+            .contains("<system-out>[note] detect/info: m"));
+    }
+}
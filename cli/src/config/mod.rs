@@ -0,0 +1,75 @@
+//! 設定ファイルモジュール
+//!
+//! `~/.ghost/config.toml` からサーバーURLを読み込む
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+/// 設定ファイル
+#[derive(Debug, Default, Deserialize)]
+pub struct Config {
+    #[serde(default)]
+    pub server: ServerConfig,
+}
+
+/// `[server]` セクション
+#[derive(Debug, Default, Deserialize)]
+pub struct ServerConfig {
+    pub url: Option<String>,
+}
+
+/// CLIのデータディレクトリ (`~/.ghost`)
+pub fn ghost_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(|home| PathBuf::from(home).join(".ghost"))
+}
+
+/// 設定ファイルのパス（`GHOST_CONFIG` で上書き可能）
+pub fn config_path() -> Option<PathBuf> {
+    std::env::var_os("GHOST_CONFIG")
+        .map(PathBuf::from)
+        .or_else(|| ghost_dir().map(|dir| dir.join("config.toml")))
+}
+
+impl Config {
+    /// 設定ファイルを読み込む（存在しない場合は既定値）
+    pub fn load() -> Result<Self> {
+        let Some(path) = config_path() else {
+            return Ok(Self::default());
+        };
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let text = std::fs::read_to_string(&path)
+            .with_context(|| format!("設定ファイルの読み込みに失敗: {}", path.display()))?;
+        toml::from_str(&text)
+            .with_context(|| format!("設定ファイルのパースに失敗: {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let config: Config = toml::from_str(
+            r#"
+            [server]
+            url = "https://ghost.example.com:6660"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.server.url.as_deref(),
+            Some("https://ghost.example.com:6660")
+        );
+
+        let empty: Config = toml::from_str("").unwrap();
+        assert!(empty.server.url.is_none());
+    }
+}
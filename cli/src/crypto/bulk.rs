@@ -0,0 +1,221 @@
+//! 一括暗号監査
+//!
+//! ターゲット一覧（ファイル/標準入力）を読み込み、CIDRを展開して同時実行数を制限しながら監査する
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use super::{local, Target, DEFAULT_PORT};
+use crate::util::Progress;
+use crate::{ApiClient, CryptoAuditResult};
+
+/// CIDR展開で許容する最大アドレス数
+const MAX_EXPANSION: u128 = 65536;
+
+/// `10.0.0.0/24` / `10.0.0.0/24:8443` / `[2001:db8::/120]:443` を展開
+fn expand_cidr(spec: &str) -> Result<Option<Vec<Target>>> {
+    let (cidr, port) = if let Some(rest) = spec.strip_prefix('[') {
+        let (cidr, rest) = rest
+            .split_once(']')
+            .with_context(|| format!("ターゲットの形式が不正です: {}", spec))?;
+        let port = match rest.strip_prefix(':') {
+            Some(port) => port
+                .parse()
+                .with_context(|| format!("ポート番号が不正です: {}", spec))?,
+            None => DEFAULT_PORT,
+        };
+        (cidr, port)
+    } else {
+        match spec.rsplit_once(':') {
+            // IPv4 CIDR の後ろのポート指定
+            Some((cidr, port)) if cidr.contains('/') && !cidr.contains(':') => (
+                cidr,
+                port.parse()
+                    .with_context(|| format!("ポート番号が不正です: {}", spec))?,
+            ),
+            _ => (spec, DEFAULT_PORT),
+        }
+    };
+
+    let Some((addr, prefix)) = cidr.split_once('/') else {
+        return Ok(None);
+    };
+    let addr: IpAddr = addr
+        .parse()
+        .with_context(|| format!("CIDRのアドレスが不正です: {}", spec))?;
+    let prefix: u32 = prefix
+        .parse()
+        .with_context(|| format!("CIDRのプレフィックス長が不正です: {}", spec))?;
+
+    let addrs: Vec<IpAddr> = match addr {
+        IpAddr::V4(v4) => {
+            anyhow::ensure!(prefix <= 32, "プレフィックス長が不正です: {}", spec);
+            let size = 1u128 << (32 - prefix);
+            anyhow::ensure!(size <= MAX_EXPANSION, "CIDRの範囲が大きすぎます: {}", spec);
+            let mask = if prefix == 0 {
+                0
+            } else {
+                u32::MAX << (32 - prefix)
+            };
+            let network = u32::from(v4) & mask;
+            // /31 と /32 以外はネットワーク・ブロードキャストアドレスを除外
+            let (first, last) = if prefix >= 31 {
+                (network as u128, network as u128 + size - 1)
+            } else {
+                (network as u128 + 1, network as u128 + size - 2)
+            };
+            (first..=last)
+                .map(|n| IpAddr::V4(Ipv4Addr::from(n as u32)))
+                .collect()
+        }
+        IpAddr::V6(v6) => {
+            anyhow::ensure!(prefix <= 128, "プレフィックス長が不正です: {}", spec);
+            anyhow::ensure!(
+                128 - prefix < 128 && (1u128 << (128 - prefix)) <= MAX_EXPANSION,
+                "CIDRの範囲が大きすぎます: {}",
+                spec
+            );
+            let size = 1u128 << (128 - prefix);
+            let network = u128::from(v6) & !(size - 1);
+            (network..network + size)
+                .map(|n| IpAddr::V6(Ipv6Addr::from(n)))
+                .collect()
+        }
+    };
+
+    Ok(Some(
+        addrs
+            .into_iter()
+            .map(|ip| Target {
+                host: ip.to_string(),
+                port,
+            })
+            .collect(),
+    ))
+}
+
+/// ターゲット一覧をパース（空行と `#` 以降はコメントとして無視）
+pub fn parse_targets(text: &str) -> Result<Vec<Target>> {
+    let mut targets = Vec::new();
+
+    for (lineno, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let parsed = match expand_cidr(line) {
+            Ok(Some(expanded)) => Ok(expanded),
+            Ok(None) => Target::parse(line).map(|t| vec![t]),
+            Err(e) => Err(e),
+        };
+        targets.extend(parsed.with_context(|| format!("{}行目", lineno + 1))?);
+    }
+
+    // 重複を除外（順序は維持）
+    let mut seen = std::collections::HashSet::new();
+    targets.retain(|t| seen.insert(t.clone()));
+
+    Ok(targets)
+}
+
+/// ファイルまたは標準入力（`-`）からターゲット一覧を読み込む
+pub fn read_targets(path: &str) -> Result<Vec<Target>> {
+    let text = if path == "-" {
+        std::io::read_to_string(std::io::stdin()).context("標準入力の読み込みに失敗")?
+    } else {
+        std::fs::read_to_string(path)
+            .with_context(|| format!("ターゲット一覧の読み込みに失敗: {}", path))?
+    };
+    parse_targets(&text)
+}
+
+/// 1件を監査
+pub async fn audit_one(
+    client: &ApiClient,
+    target: &Target,
+    local: bool,
+) -> Result<CryptoAuditResult> {
+    if local {
+        local::audit(target).await
+    } else {
+        let body = serde_json::json!({ "target": target.to_string() }).to_string();
+        client.post("/crypto/audit", Some(&body)).await
+    }
+}
+
+/// 同時実行数を制限して一括監査（結果は入力順）
+pub async fn audit_all(
+    client: &ApiClient,
+    targets: Vec<Target>,
+    local: bool,
+    concurrency: usize,
+) -> Vec<(Target, Result<CryptoAuditResult>)> {
+    let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+    let progress = Progress::new("監査中", targets.len());
+    let mut tasks = JoinSet::new();
+
+    for (index, target) in targets.into_iter().enumerate() {
+        let semaphore = semaphore.clone();
+        let client = client.clone();
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let result = audit_one(&client, &target, local).await;
+            (index, target, result)
+        });
+    }
+
+    let mut results = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        if let Ok(entry) = joined {
+            results.push(entry);
+        }
+        progress.inc();
+    }
+    progress.finish();
+
+    results.sort_by_key(|(index, _, _)| *index);
+    results.into_iter().map(|(_, t, r)| (t, r)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_targets() {
+        let targets = parse_targets(
+            "# 本番\nexample.com\napi.example.com:8443  # API\n\n10.0.0.0/30\n10.0.1.0/31:8443\nexample.com:443\n",
+        )
+        .unwrap();
+        let names: Vec<String> = targets.iter().map(|t| t.to_string()).collect();
+
+        assert_eq!(
+            names,
+            vec![
+                "example.com:443",
+                "api.example.com:8443",
+                "10.0.0.1:443",
+                "10.0.0.2:443",
+                "10.0.1.0:8443",
+                "10.0.1.1:8443",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_expand_cidr_ipv6_and_limits() {
+        let targets = expand_cidr("[2001:db8::/126]:8443").unwrap().unwrap();
+        assert_eq!(targets.len(), 4);
+        assert_eq!(targets[3].to_string(), "[2001:db8::3]:8443");
+
+        assert!(expand_cidr("10.0.0.0/8").is_err());
+        assert!(expand_cidr("10.0.0.0/33").is_err());
+        assert!(expand_cidr("example.com").unwrap().is_none());
+        assert!(parse_targets("example.com:abc").is_err());
+    }
+}
@@ -0,0 +1,233 @@
+//! 証明書一覧
+//!
+//! 監査済みターゲットの証明書チェーンを一覧表示し、期限切れ間近の証明書を検出する
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use colored::*;
+use tabled::{Table, Tabled};
+
+use super::{bulk, CertificateInfo, Target};
+use crate::util::format_duration;
+use crate::{ApiClient, CryptoAuditResult};
+
+/// SAN列に表示する最大件数
+const MAX_SAN_DISPLAY: usize = 2;
+
+#[derive(Tabled)]
+struct CertRow {
+    #[tabled(rename = "ターゲット")]
+    target: String,
+    #[tabled(rename = "#")]
+    depth: usize,
+    #[tabled(rename = "サブジェクト")]
+    subject: String,
+    #[tabled(rename = "SAN")]
+    san: String,
+    #[tabled(rename = "発行者")]
+    issuer: String,
+    #[tabled(rename = "鍵")]
+    key: String,
+    #[tabled(rename = "署名")]
+    signature: String,
+    #[tabled(rename = "残り日数")]
+    days: String,
+}
+
+fn format_san(san: &[String]) -> String {
+    if san.len() > MAX_SAN_DISPLAY {
+        format!(
+            "{} 他{}件",
+            san[..MAX_SAN_DISPLAY].join(", "),
+            san.len() - MAX_SAN_DISPLAY
+        )
+    } else {
+        san.join(", ")
+    }
+}
+
+/// 有効期限が `within` 以内（期限切れを含む）か
+pub fn is_expiring(cert: &CertificateInfo, now: DateTime<Utc>, within: Duration) -> bool {
+    cert.not_after - now <= within
+}
+
+/// 証明書を取得するターゲットを決定
+///
+/// 明示指定がなければサーバーの監査結果を使用し、証明書を含まない結果はローカルで再取得する
+async fn collect(
+    client: &ApiClient,
+    targets: Vec<String>,
+    file: Option<&str>,
+    concurrency: usize,
+) -> Result<(Vec<CryptoAuditResult>, Vec<(String, anyhow::Error)>)> {
+    let mut results = Vec::new();
+    let mut pending = Vec::new();
+    let mut failures = Vec::new();
+
+    if let Some(file) = file {
+        pending = bulk::read_targets(file)?;
+    } else if !targets.is_empty() {
+        pending = targets
+            .iter()
+            .map(|t| Target::parse(t))
+            .collect::<Result<_>>()?;
+    } else {
+        let audited: Vec<CryptoAuditResult> = client.get("/crypto/results").await?;
+        for result in audited {
+            if !result.certificates.is_empty() {
+                results.push(result);
+                continue;
+            }
+            // 解釈できないターゲットはその1件のみ失敗として扱う
+            match Target::parse(&result.target) {
+                Ok(target) => pending.push(target),
+                Err(e) => failures.push((result.target, e)),
+            }
+        }
+    }
+
+    for (target, outcome) in bulk::audit_all(client, pending, true, concurrency).await {
+        match outcome {
+            Ok(result) => results.push(result),
+            Err(e) => failures.push((target.to_string(), e)),
+        }
+    }
+
+    Ok((results, failures))
+}
+
+pub async fn cmd_crypto_certs(
+    client: &ApiClient,
+    targets: Vec<String>,
+    file: Option<&str>,
+    expiring_within: Option<Duration>,
+    concurrency: usize,
+) -> Result<()> {
+    let (mut results, failures) = collect(client, targets, file, concurrency).await?;
+    let now = Utc::now();
+
+    // 有効期限の近い順
+    results.sort_by_key(|r| r.certificates.iter().map(|c| c.not_after).min());
+
+    let mut rows = Vec::new();
+    let mut expiring = Vec::new();
+    for result in &results {
+        for (depth, cert) in result.certificates.iter().enumerate() {
+            let days = cert.days_until_expiry(now);
+            let flagged = expiring_within.is_some_and(|w| is_expiring(cert, now, w));
+            if flagged {
+                expiring.push((result.target.clone(), cert));
+            }
+            rows.push(CertRow {
+                target: result.target.clone(),
+                depth,
+                subject: cert.subject.clone(),
+                san: format_san(&cert.san),
+                issuer: cert.issuer.clone(),
+                key: format!("{} {}bit", cert.key_type, cert.key_bits),
+                signature: cert.signature_algorithm.clone(),
+                days: if days < 0 {
+                    "期限切れ".red().to_string()
+                } else if flagged {
+                    days.to_string().yellow().to_string()
+                } else {
+                    days.to_string()
+                },
+            });
+        }
+    }
+
+    println!("\n{}", "📜 証明書一覧".bold());
+
+    if rows.is_empty() {
+        println!("証明書情報がありません");
+    } else {
+        println!("{}", Table::new(rows));
+    }
+
+    if !failures.is_empty() {
+        println!("\n{} ({}件)", "⚠ 取得失敗".yellow().bold(), failures.len());
+        for (target, e) in &failures {
+            println!("  {} {}: {:#}", "✗".red(), target, e);
+        }
+    }
+
+    if let Some(within) = expiring_within {
+        println!();
+        if expiring.is_empty() && !failures.is_empty() {
+            // 取得できなかった証明書が期限切れ間近の可能性があるため成功とはしない
+            println!(
+                "{} {}件のターゲットの証明書を確認できませんでした",
+                "✗".red(),
+                failures.len()
+            );
+            println!();
+            std::process::exit(1);
+        } else if expiring.is_empty() {
+            println!(
+                "{} {}以内に期限切れとなる証明書はありません",
+                "✓".green(),
+                format_duration(within)
+            );
+        } else {
+            println!(
+                "{} {}以内に期限切れとなる証明書: {}件",
+                "✗".red(),
+                format_duration(within),
+                expiring.len()
+            );
+            for (target, cert) in &expiring {
+                println!(
+                    "  {} {} ({})",
+                    target,
+                    cert.subject,
+                    cert.not_after.format("%Y-%m-%d")
+                );
+            }
+            std::process::exit(1);
+        }
+    }
+    println!();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cert(not_after: DateTime<Utc>) -> CertificateInfo {
+        CertificateInfo {
+            subject: "CN=example.com".to_string(),
+            issuer: "CN=Example CA".to_string(),
+            san: vec![
+                "example.com".to_string(),
+                "www.example.com".to_string(),
+                "api.example.com".to_string(),
+            ],
+            serial: "01".to_string(),
+            not_before: not_after - Duration::days(90),
+            not_after,
+            key_type: "ECDSA".to_string(),
+            key_bits: 256,
+            signature_algorithm: "ecdsa-with-SHA256".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_is_expiring() {
+        let now = Utc::now();
+        let within = Duration::days(30);
+
+        assert!(is_expiring(&cert(now + Duration::days(10)), now, within));
+        assert!(is_expiring(&cert(now - Duration::days(1)), now, within));
+        assert!(!is_expiring(&cert(now + Duration::days(60)), now, within));
+    }
+
+    #[test]
+    fn test_format_san() {
+        let c = cert(Utc::now());
+        assert_eq!(format_san(&c.san), "example.com, www.example.com 他1件");
+        assert_eq!(format_san(&c.san[..1]), "example.com");
+    }
+}
@@ -0,0 +1,467 @@
+//! 暗号監査履歴
+//!
+//! 監査コマンドの結果を `~/.ghost/crypto_history.jsonl` に記録し、期間内の変化（悪化・改善）を検出する。
+//! 監査元（ローカル監査またはサーバーURL）によってスコアが異なるため、監査元ごとに比較する
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use colored::*;
+use serde::{Deserialize, Serialize};
+use tabled::{Table, Tabled};
+
+use super::policy::tls_version_rank;
+use super::Target;
+use crate::config;
+use crate::util::format_duration;
+use crate::{ApiClient, CryptoAuditResult};
+
+/// 履歴の保持期間（日）
+const RETENTION_DAYS: i64 = 180;
+
+/// ローカル監査の監査元
+const LOCAL_SOURCE: &str = "local";
+
+/// 履歴の1件（ターゲットごとのスナップショット）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entry {
+    pub recorded_at: DateTime<Utc>,
+    /// 監査元（`local` またはサーバーURL）
+    #[serde(default)]
+    pub source: String,
+    pub result: CryptoAuditResult,
+}
+
+impl Entry {
+    /// 比較対象の値が同じか
+    fn same_posture(&self, result: &CryptoAuditResult) -> bool {
+        self.result.tls_version == result.tls_version
+            && self.result.cipher_suite == result.cipher_suite
+            && self.result.security_score == result.security_score
+            && self.result.is_secure == result.is_secure
+    }
+}
+
+/// ターゲットの正規化（`example.com` と `example.com:443` を同一視するため `host:port` にする）
+fn target_key(target: &str) -> String {
+    Target::parse(target)
+        .map(|t| t.to_string())
+        .unwrap_or_else(|_| target.trim().to_string())
+}
+
+impl Entry {
+    /// 履歴のキー（監査元, 正規化したターゲット）
+    fn key(&self) -> (String, String) {
+        (self.source.clone(), target_key(&self.result.target))
+    }
+}
+
+/// 監査元の識別子
+pub fn source(client: &ApiClient, local: bool) -> String {
+    if local {
+        LOCAL_SOURCE.to_string()
+    } else {
+        client.base_url.clone()
+    }
+}
+
+/// 監査元の表示名
+fn source_label(source: &str) -> &str {
+    match source {
+        LOCAL_SOURCE => "ローカル",
+        "" => "不明",
+        server => server,
+    }
+}
+
+/// 履歴ファイルのパス
+pub fn history_path() -> Option<PathBuf> {
+    config::ghost_dir().map(|dir| dir.join("crypto_history.jsonl"))
+}
+
+/// 履歴を読み込む（壊れた行は無視）
+pub fn load() -> Result<Vec<Entry>> {
+    let Some(path) = history_path() else {
+        return Ok(Vec::new());
+    };
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let text = std::fs::read_to_string(&path)
+        .with_context(|| format!("監査履歴の読み込みに失敗: {}", path.display()))?;
+    Ok(text
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// 前回から変化のあった結果を追加し、保持期間を過ぎた履歴を削除した一覧を返す
+fn merge(
+    mut entries: Vec<Entry>,
+    source: &str,
+    results: &[CryptoAuditResult],
+    now: DateTime<Utc>,
+) -> Vec<Entry> {
+    let cutoff = now - Duration::days(RETENTION_DAYS);
+    entries.retain(|e| e.recorded_at >= cutoff);
+
+    for result in results {
+        let entry = Entry {
+            recorded_at: now,
+            source: source.to_string(),
+            result: CryptoAuditResult {
+                target: target_key(&result.target),
+                ..result.clone()
+            },
+        };
+        let key = entry.key();
+        let latest = entries.iter().rev().find(|e| e.key() == key);
+        if latest.is_some_and(|e| e.same_posture(result)) {
+            continue;
+        }
+        entries.push(entry);
+    }
+
+    entries
+}
+
+/// 監査結果を監査元ごとの履歴に記録
+pub fn record(source: &str, results: &[CryptoAuditResult]) -> Result<()> {
+    let Some(path) = history_path() else {
+        return Ok(());
+    };
+    if results.is_empty() {
+        return Ok(());
+    }
+
+    let entries = merge(load()?, source, results, Utc::now());
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("ディレクトリの作成に失敗: {}", dir.display()))?;
+    }
+    let tmp = path.with_extension("jsonl.tmp");
+    let mut file = std::fs::File::create(&tmp)
+        .with_context(|| format!("監査履歴の書き込みに失敗: {}", tmp.display()))?;
+    for entry in &entries {
+        writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    }
+    file.flush()?;
+    std::fs::rename(&tmp, &path)
+        .with_context(|| format!("監査履歴の書き込みに失敗: {}", path.display()))?;
+
+    Ok(())
+}
+
+/// 履歴の記録に失敗しても監査自体は成功として扱う
+pub fn record_or_warn(source: &str, results: &[CryptoAuditResult]) {
+    if let Err(e) = record(source, results) {
+        eprintln!("{} 監査履歴を記録できませんでした: {:#}", "⚠".yellow(), e);
+    }
+}
+
+/// 変化の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ChangeKind {
+    /// スコアまたはTLSバージョンが低下
+    Regression,
+    /// 値は変わったが悪化していない
+    Changed,
+    /// スコアまたはTLSバージョンが向上
+    Improvement,
+}
+
+/// ターゲットごとの変化
+#[derive(Debug, Clone)]
+pub struct Change {
+    pub source: String,
+    pub target: String,
+    pub kind: ChangeKind,
+    pub before: Entry,
+    pub after: Entry,
+}
+
+fn classify(before: &CryptoAuditResult, after: &CryptoAuditResult) -> ChangeKind {
+    let tls = match (
+        tls_version_rank(&before.tls_version),
+        tls_version_rank(&after.tls_version),
+    ) {
+        (Some(b), Some(a)) => a.cmp(&b),
+        _ => std::cmp::Ordering::Equal,
+    };
+    let score = after.security_score.cmp(&before.security_score);
+
+    if tls.is_lt() || score.is_lt() {
+        ChangeKind::Regression
+    } else if tls.is_gt() || score.is_gt() {
+        ChangeKind::Improvement
+    } else {
+        ChangeKind::Changed
+    }
+}
+
+/// `since` 時点から最新までに `tls_version` / `cipher_suite` / `security_score` が変化したターゲット
+///
+/// 監査元ごとに比較する。基準は `since` 時点の状態（それ以前の最新記録）。期間内に初めて記録されたターゲットは最初の記録を基準とする
+pub fn diff(entries: &[Entry], since: DateTime<Utc>) -> Vec<Change> {
+    let mut by_target: BTreeMap<(String, String), Vec<&Entry>> = BTreeMap::new();
+    for entry in entries {
+        by_target.entry(entry.key()).or_default().push(entry);
+    }
+
+    let mut changes = Vec::new();
+    for ((source, target), mut history) in by_target {
+        history.sort_by_key(|e| e.recorded_at);
+        let Some(after) = history.last() else {
+            continue;
+        };
+        if after.recorded_at < since {
+            continue;
+        }
+        let before = history
+            .iter()
+            .rev()
+            .find(|e| e.recorded_at <= since)
+            .or_else(|| history.first())
+            .unwrap();
+
+        let (b, a) = (&before.result, &after.result);
+        if b.tls_version == a.tls_version
+            && b.cipher_suite == a.cipher_suite
+            && b.security_score == a.security_score
+        {
+            continue;
+        }
+
+        changes.push(Change {
+            source,
+            target,
+            kind: classify(b, a),
+            before: (*before).clone(),
+            after: (*after).clone(),
+        });
+    }
+
+    changes.sort_by(|a, b| {
+        a.kind
+            .cmp(&b.kind)
+            .then_with(|| a.target.cmp(&b.target))
+            .then_with(|| a.source.cmp(&b.source))
+    });
+    changes
+}
+
+#[derive(Tabled)]
+struct ChangeRow {
+    #[tabled(rename = "ターゲット")]
+    target: String,
+    #[tabled(rename = "監査元")]
+    source: String,
+    #[tabled(rename = "変化")]
+    kind: String,
+    #[tabled(rename = "TLS")]
+    tls_version: String,
+    #[tabled(rename = "暗号方式")]
+    cipher_suite: String,
+    #[tabled(rename = "スコア")]
+    score: String,
+    #[tabled(rename = "変化日時")]
+    changed_at: String,
+}
+
+/// 値が変わっていれば `前 → 後`、同じなら値のみ
+fn transition(before: &str, after: &str) -> String {
+    if before == after {
+        after.to_string()
+    } else {
+        format!("{} → {}", before, after)
+    }
+}
+
+pub fn cmd_crypto_diff(since: Duration) -> Result<()> {
+    let entries = load()?;
+    let changes = diff(&entries, Utc::now() - since);
+
+    println!(
+        "\n{} (過去{})",
+        "🔐 暗号監査の変化".bold(),
+        format_duration(since)
+    );
+
+    if entries.is_empty() {
+        println!("監査履歴がありません。`ghost crypto audit` の実行時に記録されます");
+        return Ok(());
+    }
+    if changes.is_empty() {
+        println!("{} 変化はありません", "✓".green());
+        return Ok(());
+    }
+
+    let regressions = changes
+        .iter()
+        .filter(|c| c.kind == ChangeKind::Regression)
+        .count();
+
+    let rows: Vec<ChangeRow> = changes
+        .iter()
+        .map(|c| {
+            let (b, a) = (&c.before.result, &c.after.result);
+            let delta = a.security_score as i16 - b.security_score as i16;
+            let score = if delta == 0 {
+                a.security_score.to_string()
+            } else {
+                format!("{} → {} ({:+})", b.security_score, a.security_score, delta)
+            };
+            let (kind, score) = match c.kind {
+                ChangeKind::Regression => {
+                    ("悪化".red().bold().to_string(), score.red().to_string())
+                }
+                ChangeKind::Improvement => ("改善".green().to_string(), score.green().to_string()),
+                ChangeKind::Changed => ("変更".yellow().to_string(), score),
+            };
+            ChangeRow {
+                target: c.target.clone(),
+                source: source_label(&c.source).to_string(),
+                kind,
+                tls_version: transition(&b.tls_version, &a.tls_version),
+                cipher_suite: transition(&b.cipher_suite, &a.cipher_suite),
+                score,
+                changed_at: c
+                    .after
+                    .recorded_at
+                    .with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string(),
+            }
+        })
+        .collect();
+
+    println!("{}", Table::new(rows));
+    println!(
+        "変化: {}件 (悪化: {})",
+        changes.len(),
+        if regressions > 0 {
+            regressions.to_string().red().bold()
+        } else {
+            regressions.to_string().normal()
+        }
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::testing::result;
+
+    #[test]
+    fn test_merge_skips_unchanged_and_expired() {
+        let now = Utc::now();
+        let old = Entry {
+            recorded_at: now - Duration::days(RETENTION_DAYS + 2),
+            source: LOCAL_SOURCE.to_string(),
+            result: result("old.example.com", "TLSv1.3", "TLS13_AES_256_GCM_SHA384", 95),
+        };
+        let entries = merge(
+            vec![old],
+            LOCAL_SOURCE,
+            &[result(
+                "a.example.com",
+                "TLSv1.3",
+                "TLS13_AES_256_GCM_SHA384",
+                95,
+            )],
+            now - Duration::days(1),
+        );
+        assert_eq!(entries.len(), 1);
+
+        // 同じターゲットはポートの省略有無に関わらず同一視する
+        let entries = merge(
+            entries,
+            LOCAL_SOURCE,
+            &[
+                result(
+                    "https://a.example.com:443",
+                    "TLSv1.3",
+                    "TLS13_AES_256_GCM_SHA384",
+                    95,
+                ),
+                result(
+                    "b.example.com",
+                    "TLSv1.2",
+                    "TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256",
+                    90,
+                ),
+            ],
+            now,
+        );
+        let targets: Vec<&str> = entries.iter().map(|e| e.result.target.as_str()).collect();
+        assert_eq!(targets, vec!["a.example.com:443", "b.example.com:443"]);
+
+        // 監査元が異なれば別の履歴として記録する
+        let entries = merge(
+            entries,
+            "https://ghost.example.com",
+            &[result(
+                "a.example.com",
+                "TLSv1.3",
+                "TLS13_AES_256_GCM_SHA384",
+                95,
+            )],
+            now,
+        );
+        assert_eq!(entries.len(), 3);
+    }
+
+    #[test]
+    fn test_diff() {
+        let now = Utc::now();
+        let at = |days: i64, r: CryptoAuditResult| Entry {
+            recorded_at: now - Duration::days(days),
+            source: LOCAL_SOURCE.to_string(),
+            result: r,
+        };
+        let entries = vec![
+            at(30, result("a", "TLSv1.3", "TLS13_AES_256_GCM_SHA384", 95)),
+            at(10, result("a", "TLSv1.3", "TLS13_AES_256_GCM_SHA384", 90)),
+            at(
+                2,
+                result("a", "TLSv1.2", "TLS_ECDHE_RSA_WITH_AES_128_CBC_SHA", 55),
+            ),
+            at(
+                10,
+                result("b", "TLSv1.2", "TLS_ECDHE_RSA_WITH_AES_128_CBC_SHA", 60),
+            ),
+            at(1, result("b", "TLSv1.3", "TLS13_AES_256_GCM_SHA384", 95)),
+            at(3, result("c", "TLSv1.3", "TLS13_AES_128_GCM_SHA256", 95)),
+            at(1, result("c", "TLSv1.3", "TLS13_AES_256_GCM_SHA384", 95)),
+            // 期間内に変化なし
+            at(20, result("d", "TLSv1.3", "TLS13_AES_256_GCM_SHA384", 95)),
+            // 監査元が異なるスコアは比較しない
+            Entry {
+                source: "https://ghost.example.com".to_string(),
+                ..at(1, result("d", "TLSv1.3", "TLS13_AES_256_GCM_SHA384", 80))
+            },
+        ];
+
+        let changes = diff(&entries, now - Duration::days(7));
+        let summary: Vec<(&str, ChangeKind, u8)> = changes
+            .iter()
+            .map(|c| (c.target.as_str(), c.kind, c.before.result.security_score))
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                ("a:443", ChangeKind::Regression, 90),
+                ("c:443", ChangeKind::Changed, 95),
+                ("b:443", ChangeKind::Improvement, 60),
+            ]
+        );
+    }
+}
@@ -0,0 +1,515 @@
+//! ローカルTLS監査
+//!
+//! サーバーを経由せず、CLIから対象へ直接TLS接続してネゴシエーション結果と証明書チェーンを取得し、
+//! セキュリティスコアを算出する
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::client::WebPkiServerVerifier;
+use rustls::crypto::CryptoProvider;
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio_rustls::client::TlsStream;
+use tokio_rustls::TlsConnector;
+use x509_parser::objects::{oid2sn, oid_registry};
+use x509_parser::oid_registry::OID_SIG_ED25519;
+use x509_parser::prelude::{FromDer, GeneralName, X509Certificate};
+use x509_parser::public_key::PublicKey;
+
+use super::{CertificateInfo, Hsts, Target};
+use crate::probe::protocol_name;
+use crate::CryptoAuditResult;
+
+/// 接続・ハンドシェイクのタイムアウト
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// HSTS確認用HTTPリクエストのタイムアウト
+const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// HTTPレスポンスヘッダーとして読み込む最大バイト数
+const MAX_HEADER_BYTES: usize = 16 * 1024;
+
+/// 安全と判定する最低スコア
+pub const SECURE_SCORE: u8 = 80;
+
+/// TLS1.2以上でネゴシエーションできなかったサーバーのプロトコル表記
+pub const LEGACY_TLS: &str = "レガシーTLSのみ";
+
+/// 証明書チェーンの検証結果を記録し、接続自体は常に許可する検証器
+///
+/// 信頼されていない証明書のホストも監査対象とするため、検証エラーで接続を中断しない
+#[derive(Debug)]
+struct RecordingVerifier {
+    inner: Arc<WebPkiServerVerifier>,
+    outcome: Mutex<Option<String>>,
+}
+
+impl ServerCertVerifier for RecordingVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        if let Err(e) = self.inner.verify_server_cert(
+            end_entity,
+            intermediates,
+            server_name,
+            ocsp_response,
+            now,
+        ) {
+            *self.outcome.lock().unwrap() = Some(e.to_string());
+        }
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+}
+
+/// DER形式の証明書から詳細を取得
+pub fn parse_certificate(der: &[u8]) -> Result<CertificateInfo> {
+    let (_, cert) = X509Certificate::from_der(der).context("証明書のパースに失敗")?;
+
+    let san = cert
+        .subject_alternative_name()
+        .ok()
+        .flatten()
+        .map(|ext| {
+            ext.value
+                .general_names
+                .iter()
+                .filter_map(|name| match name {
+                    GeneralName::DNSName(dns) => Some(dns.to_string()),
+                    GeneralName::IPAddress(ip) => match ip.len() {
+                        4 => Some(
+                            std::net::Ipv4Addr::from(<[u8; 4]>::try_from(*ip).ok()?).to_string(),
+                        ),
+                        16 => Some(
+                            std::net::Ipv6Addr::from(<[u8; 16]>::try_from(*ip).ok()?).to_string(),
+                        ),
+                        _ => None,
+                    },
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let spki = cert.public_key();
+    let (key_type, key_bits) = match spki.parsed() {
+        Ok(PublicKey::RSA(rsa)) => ("RSA".to_string(), rsa.key_size() as u32),
+        // 非圧縮形式の P-521 は 528bit と算出されるため補正
+        Ok(PublicKey::EC(ec)) => ("ECDSA".to_string(), (ec.key_size() as u32).min(521)),
+        _ if spki.algorithm.algorithm == OID_SIG_ED25519 => ("Ed25519".to_string(), 256),
+        _ => (
+            oid2sn(&spki.algorithm.algorithm, oid_registry())
+                .map(str::to_string)
+                .unwrap_or_else(|_| spki.algorithm.algorithm.to_id_string()),
+            0,
+        ),
+    };
+
+    let signature_algorithm = oid2sn(&cert.signature_algorithm.algorithm, oid_registry())
+        .map(str::to_string)
+        .unwrap_or_else(|_| cert.signature_algorithm.algorithm.to_id_string());
+
+    let timestamp = |ts: i64| DateTime::from_timestamp(ts, 0).unwrap_or_default();
+
+    Ok(CertificateInfo {
+        subject: cert.subject().to_string(),
+        issuer: cert.issuer().to_string(),
+        san,
+        serial: cert.raw_serial_as_string(),
+        not_before: timestamp(cert.validity().not_before.timestamp()),
+        not_after: timestamp(cert.validity().not_after.timestamp()),
+        key_type,
+        key_bits,
+        signature_algorithm,
+    })
+}
+
+/// 鍵長が十分かどうか
+pub fn is_strong_key(cert: &CertificateInfo) -> bool {
+    match cert.key_type.as_str() {
+        "RSA" => cert.key_bits >= 2048,
+        "ECDSA" => cert.key_bits >= 256,
+        "Ed25519" => true,
+        _ => false,
+    }
+}
+
+/// セキュリティスコアを算出 (0-100)
+///
+/// - プロトコル 40点: TLS1.3=40, TLS1.2=30
+/// - 暗号スイート 30点: AEAD=20, 前方秘匿性=10
+/// - 証明書 30点: チェーン検証=15, 期限(30日超)=10/期限内=5, 鍵長=5
+pub fn security_score(
+    tls_version: &str,
+    cipher_suite: &str,
+    certificates: &[CertificateInfo],
+    chain_valid: bool,
+    now: DateTime<Utc>,
+) -> u8 {
+    let mut score = match tls_version {
+        "TLSv1.3" => 40,
+        "TLSv1.2" => 30,
+        _ => 0,
+    };
+
+    let suite = cipher_suite.to_uppercase();
+    if suite.contains("GCM") || suite.contains("CHACHA20") || suite.contains("CCM") {
+        score += 20;
+    }
+    if suite.starts_with("TLS13_") || suite.contains("ECDHE") || suite.contains("DHE") {
+        score += 10;
+    }
+
+    if let Some(leaf) = certificates.first() {
+        if chain_valid {
+            score += 15;
+        }
+        score += match leaf.days_until_expiry(now) {
+            d if d > 30 => 10,
+            d if d >= 0 => 5,
+            _ => 0,
+        };
+        if is_strong_key(leaf) {
+            score += 5;
+        }
+    }
+
+    score
+}
+
+/// TLS1.2以上・AEADスイートに対応しないサーバーとのハンドシェイクで発生するエラーか
+///
+/// rustls は TLS1.0/1.1 やCBCのみのスイートをネゴシエーションできないため、
+/// これらは接続エラーではなく安全でない設定として扱う。
+/// `handshake_failure` はクライアント証明書の要求などでも返るため監査エラーとする
+fn is_legacy_only(error: &rustls::Error) -> bool {
+    use rustls::{AlertDescription, Error, PeerIncompatible, PeerMisbehaved};
+    matches!(
+        error,
+        Error::AlertReceived(AlertDescription::ProtocolVersion)
+            | Error::PeerIncompatible(PeerIncompatible::ServerDoesNotSupportTls12Or13)
+            | Error::PeerMisbehaved(PeerMisbehaved::SelectedUnofferedCipherSuite)
+    )
+}
+
+/// レガシーなTLSのみ対応するサーバーの監査結果
+fn legacy_only_result(target: &Target) -> CryptoAuditResult {
+    CryptoAuditResult {
+        target: target.to_string(),
+        tls_version: LEGACY_TLS.to_string(),
+        cipher_suite: "不明".to_string(),
+        is_secure: false,
+        security_score: 0,
+        certificates: Vec::new(),
+        chain_error: None,
+        hsts: None,
+    }
+}
+
+/// 対象へ直接TLS接続して監査
+pub async fn audit(target: &Target) -> Result<CryptoAuditResult> {
+    audit_with_roots(
+        target,
+        webpki_roots::TLS_SERVER_ROOTS.iter().cloned().collect(),
+    )
+    .await
+}
+
+/// 指定したルート証明書で監査（テスト用に分離）
+async fn audit_with_roots(target: &Target, roots: RootCertStore) -> Result<CryptoAuditResult> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let verifier = Arc::new(RecordingVerifier {
+        inner: WebPkiServerVerifier::builder_with_provider(Arc::new(roots), provider.clone())
+            .build()
+            .context("証明書検証器の初期化に失敗")?,
+        outcome: Mutex::new(None),
+    });
+    let config = client_config(provider, verifier.clone())?;
+
+    let server_name = ServerName::try_from(target.host.clone())
+        .with_context(|| format!("サーバー名が不正です: {}", target.host))?;
+
+    let stream = tokio::time::timeout(
+        TIMEOUT,
+        TcpStream::connect((target.host.as_str(), target.port)),
+    )
+    .await
+    .with_context(|| format!("接続がタイムアウトしました: {}", target))?
+    .with_context(|| format!("TCP接続に失敗: {}", target))?;
+
+    let handshake = tokio::time::timeout(
+        TIMEOUT,
+        TlsConnector::from(config).connect(server_name, stream),
+    )
+    .await
+    .with_context(|| format!("TLSハンドシェイクがタイムアウトしました: {}", target))?;
+    let mut stream = match handshake {
+        Ok(stream) => stream,
+        Err(e) => {
+            let legacy = e
+                .get_ref()
+                .and_then(|inner| inner.downcast_ref::<rustls::Error>())
+                .is_some_and(is_legacy_only);
+            if legacy {
+                return Ok(legacy_only_result(target));
+            }
+            return Err(
+                anyhow::Error::new(e).context(format!("TLSハンドシェイクに失敗: {}", target))
+            );
+        }
+    };
+
+    let (_, session) = stream.get_ref();
+    let tls_version = session
+        .protocol_version()
+        .map(protocol_name)
+        .unwrap_or_else(|| "不明".to_string());
+    let cipher_suite = session
+        .negotiated_cipher_suite()
+        .map(|s| format!("{:?}", s.suite()))
+        .unwrap_or_else(|| "不明".to_string());
+    let certificates = session
+        .peer_certificates()
+        .unwrap_or_default()
+        .iter()
+        .map(|der| parse_certificate(der))
+        .collect::<Result<Vec<_>>>()?;
+
+    let hsts = fetch_hsts(&mut stream, &target.host).await;
+    let chain_error = verifier.outcome.lock().unwrap().take();
+    let now = Utc::now();
+    let chain_valid = chain_error.is_none() && !certificates.is_empty();
+    let security_score =
+        security_score(&tls_version, &cipher_suite, &certificates, chain_valid, now);
+    let expired = certificates
+        .first()
+        .is_none_or(|leaf| leaf.days_until_expiry(now) < 0);
+
+    Ok(CryptoAuditResult {
+        target: target.to_string(),
+        tls_version,
+        cipher_suite,
+        is_secure: security_score >= SECURE_SCORE && chain_valid && !expired,
+        security_score,
+        certificates,
+        chain_error,
+        hsts,
+    })
+}
+
+/// TLS接続上で `GET /` を送信し、HSTSヘッダーを確認する
+///
+/// HTTPで応答しないサービスは判定不可として `None` を返す
+async fn fetch_hsts(stream: &mut TlsStream<TcpStream>, host: &str) -> Option<Hsts> {
+    let request = format!(
+        "GET / HTTP/1.1\r\nHost: {}\r\nUser-Agent: ghost-cli/{}\r\nConnection: close\r\n\r\n",
+        host,
+        env!("CARGO_PKG_VERSION")
+    );
+
+    let exchange = async {
+        stream.write_all(request.as_bytes()).await.ok()?;
+        let mut buf = Vec::new();
+        let mut chunk = [0u8; 4096];
+        while !buf.windows(4).any(|w| w == b"\r\n\r\n") && buf.len() < MAX_HEADER_BYTES {
+            let n = stream.read(&mut chunk).await.ok()?;
+            if n == 0 {
+                break;
+            }
+            buf.extend_from_slice(&chunk[..n]);
+        }
+        Some(buf)
+    };
+    let buf = tokio::time::timeout(HTTP_TIMEOUT, exchange).await.ok()??;
+
+    let text = String::from_utf8_lossy(&buf);
+    if !text.starts_with("HTTP/") {
+        return None;
+    }
+    let header = text
+        .lines()
+        .skip(1)
+        .take_while(|line| !line.is_empty())
+        .find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            name.trim()
+                .eq_ignore_ascii_case("strict-transport-security")
+                .then(|| value.trim().to_string())
+        });
+
+    Some(header.map(|h| Hsts::parse(&h)).unwrap_or_default())
+}
+
+fn client_config(
+    provider: Arc<CryptoProvider>,
+    verifier: Arc<RecordingVerifier>,
+) -> Result<Arc<ClientConfig>> {
+    let config = ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .context("TLS設定の初期化に失敗")?
+        .dangerous()
+        .with_custom_certificate_verifier(verifier)
+        .with_no_client_auth();
+
+    Ok(Arc::new(config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustls::pki_types::{PrivateKeyDer, PrivatePkcs8KeyDer};
+    use rustls::ServerConfig;
+    use tokio::io::AsyncWriteExt;
+    use tokio::net::TcpListener;
+    use tokio_rustls::TlsAcceptor;
+
+    /// 自己署名証明書で待ち受けるテスト用TLSサーバーを起動
+    async fn spawn_tls_server() -> (u16, CertificateDer<'static>) {
+        let certified = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let cert = certified.cert.der().clone();
+        let key =
+            PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(certified.key_pair.serialize_der()));
+
+        let config =
+            ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+                .with_safe_default_protocol_versions()
+                .unwrap()
+                .with_no_client_auth()
+                .with_single_cert(vec![cert.clone()], key)
+                .unwrap();
+        let acceptor = TlsAcceptor::from(Arc::new(config));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                if let Ok(mut tls) = acceptor.accept(stream).await {
+                    let _ = tls.shutdown().await;
+                }
+            }
+        });
+
+        (port, cert)
+    }
+
+    fn target(port: u16) -> Target {
+        Target {
+            host: "localhost".to_string(),
+            port,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_audit_self_signed() {
+        let (port, _) = spawn_tls_server().await;
+
+        let result = audit(&target(port)).await.unwrap();
+
+        assert_eq!(result.tls_version, "TLSv1.3");
+        assert!(result.cipher_suite.starts_with("TLS13_"));
+        assert!(result.chain_error.is_some());
+        assert!(!result.is_secure);
+        assert_eq!(result.certificates.len(), 1);
+        assert_eq!(result.certificates[0].san, vec!["localhost".to_string()]);
+        assert_eq!(result.certificates[0].key_type, "ECDSA");
+        assert_eq!(result.certificates[0].key_bits, 256);
+        // 40 (TLS1.3) + 30 (AEAD/PFS) + 10 (期限) + 5 (鍵長)
+        assert_eq!(result.security_score, 85);
+    }
+
+    #[tokio::test]
+    async fn test_audit_trusted_root() {
+        let (port, cert) = spawn_tls_server().await;
+        let mut roots = RootCertStore::empty();
+        roots.add(cert).unwrap();
+
+        let result = audit_with_roots(&target(port), roots).await.unwrap();
+
+        assert!(result.chain_error.is_none());
+        assert_eq!(result.security_score, 100);
+        assert!(result.is_secure);
+    }
+
+    /// ClientHelloに対して指定したアラートを返すサーバーを起動
+    async fn spawn_alert_server(description: u8) -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = [0u8; 4096];
+                let _ = stream.read(&mut buf).await;
+                let _ = stream
+                    .write_all(&[0x15, 0x03, 0x01, 0x00, 0x02, 0x02, description])
+                    .await;
+            }
+        });
+        port
+    }
+
+    #[tokio::test]
+    async fn test_audit_legacy_only() {
+        // protocol_version アラート (TLS1.0のみ対応を想定)
+        let port = spawn_alert_server(0x46).await;
+        let result = audit(&target(port)).await.unwrap();
+
+        assert_eq!(result.tls_version, LEGACY_TLS);
+        assert_eq!(result.security_score, 0);
+        assert!(!result.is_secure);
+        assert!(result.certificates.is_empty());
+
+        // handshake_failure アラート (クライアント証明書の要求など) は監査エラー
+        let port = spawn_alert_server(0x28).await;
+        assert!(audit(&target(port)).await.is_err());
+    }
+
+    #[test]
+    fn test_security_score_without_certificate() {
+        let now = Utc::now();
+        assert_eq!(
+            security_score(
+                "TLSv1.2",
+                "TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256",
+                &[],
+                false,
+                now
+            ),
+            60
+        );
+        assert_eq!(
+            security_score("TLSv1.0", "TLS_RSA_WITH_AES_128_CBC_SHA", &[], false, now),
+            0
+        );
+    }
+}
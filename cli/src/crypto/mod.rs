@@ -0,0 +1,507 @@
+//! 暗号監査モジュール
+//!
+//! サーバー経由の監査に加えて、CLIから対象へ直接TLS接続するローカル監査を提供
+
+pub mod bulk;
+pub mod certs;
+pub mod history;
+pub mod local;
+pub mod policy;
+pub mod report;
+#[cfg(test)]
+pub mod testing;
+
+use std::fmt;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use colored::*;
+use serde::{Deserialize, Serialize};
+use tabled::Table;
+
+use self::policy::{Evaluation, Policy, ScoreThresholds, Verdict};
+use crate::ci::{self, CiReport, OutputFormat};
+use crate::{ApiClient, CryptoAuditResult, CryptoRow};
+
+/// ポート未指定時の既定値
+pub const DEFAULT_PORT: u16 = 443;
+
+/// 監査対象 (`host` / `host:port` / `[::1]:port`)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Target {
+    pub host: String,
+    pub port: u16,
+}
+
+impl Target {
+    pub fn parse(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let s = s
+            .strip_prefix("https://")
+            .unwrap_or(s)
+            .trim_end_matches('/');
+
+        // [IPv6]:port / [IPv6]
+        if let Some(rest) = s.strip_prefix('[') {
+            let (host, rest) = rest
+                .split_once(']')
+                .with_context(|| format!("ターゲットの形式が不正です: {}", s))?;
+            let port = match rest.strip_prefix(':') {
+                Some(port) => port
+                    .parse()
+                    .with_context(|| format!("ポート番号が不正です: {}", s))?,
+                None => DEFAULT_PORT,
+            };
+            return Ok(Self {
+                host: host.to_string(),
+                port,
+            });
+        }
+
+        // 括弧なしのIPv6アドレスはポートなしとして扱う
+        if s.matches(':').count() > 1 {
+            return Ok(Self {
+                host: s.to_string(),
+                port: DEFAULT_PORT,
+            });
+        }
+
+        match s.split_once(':') {
+            Some((host, port)) => Ok(Self {
+                host: host.to_string(),
+                port: port
+                    .parse()
+                    .with_context(|| format!("ポート番号が不正です: {}", s))?,
+            }),
+            None if s.is_empty() => anyhow::bail!("ターゲットが空です"),
+            None => Ok(Self {
+                host: s.to_string(),
+                port: DEFAULT_PORT,
+            }),
+        }
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.host.contains(':') {
+            write!(f, "[{}]:{}", self.host, self.port)
+        } else {
+            write!(f, "{}:{}", self.host, self.port)
+        }
+    }
+}
+
+/// 証明書の詳細（ローカル監査で取得）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CertificateInfo {
+    pub subject: String,
+    pub issuer: String,
+    #[serde(default)]
+    pub san: Vec<String>,
+    pub serial: String,
+    pub not_before: DateTime<Utc>,
+    pub not_after: DateTime<Utc>,
+    pub key_type: String,
+    pub key_bits: u32,
+    pub signature_algorithm: String,
+}
+
+impl CertificateInfo {
+    /// 有効期限までの日数（期限切れは負の値）
+    pub fn days_until_expiry(&self, now: DateTime<Utc>) -> i64 {
+        (self.not_after - now).num_days()
+    }
+}
+
+/// HSTS (Strict-Transport-Security) の設定状況
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hsts {
+    pub enabled: bool,
+    #[serde(default)]
+    pub max_age: Option<u64>,
+    #[serde(default)]
+    pub include_subdomains: bool,
+}
+
+impl Hsts {
+    /// `Strict-Transport-Security` ヘッダーの値をパース（`max-age=0` は無効扱い）
+    pub fn parse(header: &str) -> Self {
+        let mut hsts = Self::default();
+        for directive in header.split(';').map(str::trim) {
+            match directive.split_once('=') {
+                Some((name, value)) if name.trim().eq_ignore_ascii_case("max-age") => {
+                    hsts.max_age = value.trim().trim_matches('"').parse().ok();
+                }
+                None if directive.eq_ignore_ascii_case("includeSubDomains") => {
+                    hsts.include_subdomains = true;
+                }
+                _ => {}
+            }
+        }
+        hsts.enabled = hsts.max_age.is_some_and(|age| age > 0);
+        hsts
+    }
+}
+
+impl From<CryptoAuditResult> for CryptoRow {
+    fn from(r: CryptoAuditResult) -> Self {
+        CryptoRow {
+            target: r.target,
+            tls_version: r.tls_version,
+            cipher_suite: if r.cipher_suite.len() > 20 {
+                format!("{}...", &r.cipher_suite[..20])
+            } else {
+                r.cipher_suite
+            },
+            security_score: format!("{}点", r.security_score),
+            status: if r.is_secure {
+                "安全".to_string()
+            } else {
+                "要改善".to_string()
+            },
+        }
+    }
+}
+
+/// スコアを色分け
+pub fn score_colored(score: u8, thresholds: &ScoreThresholds) -> ColoredString {
+    if score >= thresholds.secure {
+        score.to_string().green()
+    } else if score >= thresholds.warning {
+        score.to_string().yellow()
+    } else {
+        score.to_string().red()
+    }
+}
+
+fn print_result(result: &CryptoAuditResult, thresholds: &ScoreThresholds) {
+    println!("\n{}", "🔐 暗号監査結果".bold());
+    println!("{}", "=".repeat(40));
+    println!("ターゲット: {}", result.target);
+    println!("TLSバージョン: {}", result.tls_version);
+    println!("暗号方式: {}", result.cipher_suite);
+    println!(
+        "セキュリティスコア: {}",
+        score_colored(result.security_score, thresholds)
+    );
+    println!(
+        "状態: {}",
+        if result.is_secure {
+            "安全".green()
+        } else {
+            "要改善".red()
+        }
+    );
+
+    if let Some(hsts) = &result.hsts {
+        println!(
+            "HSTS: {}",
+            match hsts.max_age {
+                Some(age) if hsts.enabled => format!(
+                    "有効 (max-age={}{})",
+                    age,
+                    if hsts.include_subdomains {
+                        "; includeSubDomains"
+                    } else {
+                        ""
+                    }
+                )
+                .green(),
+                _ => "無効".red(),
+            }
+        );
+    }
+
+    if !result.certificates.is_empty() {
+        let now = Utc::now();
+        println!("\n--- 証明書チェーン ---");
+        match &result.chain_error {
+            None => println!("検証: {}", "✓ 信頼済み".green()),
+            Some(e) => println!("検証: {} {}", "✗".red(), e),
+        }
+        for (i, cert) in result.certificates.iter().enumerate() {
+            let days = cert.days_until_expiry(now);
+            let expiry = format!("残り{}日", days);
+            println!("  [{}] {}", i, cert.subject);
+            println!("      発行者: {}", cert.issuer);
+            if !cert.san.is_empty() {
+                println!("      SAN: {}", cert.san.join(", "));
+            }
+            println!(
+                "      鍵: {} {}bit / 署名: {}",
+                cert.key_type, cert.key_bits, cert.signature_algorithm
+            );
+            println!(
+                "      有効期限: {} ({})",
+                cert.not_after.format("%Y-%m-%d"),
+                if days < 0 {
+                    "期限切れ".red()
+                } else if days <= 30 {
+                    expiry.yellow()
+                } else {
+                    expiry.normal()
+                }
+            );
+        }
+    }
+    println!();
+}
+
+/// ポリシーの評価結果をルールごとに表示し、準拠しているかを返す
+fn print_policy_evaluation(evaluation: &Evaluation) -> bool {
+    println!("--- ポリシー評価 ---");
+    for (rule, verdict) in &evaluation.verdicts {
+        let label = match &rule.description {
+            Some(description) => format!("{} ({})", rule.id, description),
+            None => rule.id.clone(),
+        };
+        match verdict {
+            Verdict::Pass => println!("  {} {}", "✓".green(), label),
+            Verdict::Violation(reasons) => {
+                println!("  {} {} [{}]", "✗".red(), label, rule.severity);
+                for reason in reasons {
+                    println!("      {}", reason);
+                }
+            }
+            Verdict::Unknown(reason) => {
+                println!("  {} {}: {}", "?".yellow(), label, reason.dimmed())
+            }
+        }
+    }
+    println!();
+
+    evaluation.is_compliant()
+}
+
+/// 監査コマンドの共通オプション
+#[derive(Debug, Clone, Copy)]
+pub struct AuditOptions<'a> {
+    /// サーバーを経由せずローカルで監査
+    pub local: bool,
+    pub policy: Option<&'a Policy>,
+    pub output: OutputFormat,
+}
+
+pub async fn cmd_crypto_audit(
+    client: &ApiClient,
+    target: &str,
+    options: AuditOptions<'_>,
+) -> Result<()> {
+    let mut result: CryptoAuditResult = if options.local {
+        local::audit(&Target::parse(target)?).await?
+    } else {
+        let body = serde_json::json!({ "target": target }).to_string();
+        client.post("/crypto/audit", Some(&body)).await?
+    };
+
+    history::record_or_warn(
+        &history::source(client, options.local),
+        std::slice::from_ref(&result),
+    );
+
+    let evaluation = options.policy.map(|p| p.evaluate(&result, Utc::now()));
+    // ポリシー指定時は一括監査と同じくしきい値とルール違反で判定
+    if let (Some(policy), Some(evaluation)) = (options.policy, &evaluation) {
+        result.is_secure = policy.is_secure(&result, evaluation);
+    }
+
+    let compliant = match options.output {
+        OutputFormat::Text => {
+            let thresholds = options.policy.map(|p| p.score).unwrap_or_default();
+            print_result(&result, &thresholds);
+            evaluation.as_ref().is_none_or(print_policy_evaluation)
+        }
+        format => {
+            let case = ci::crypto_case(&result, evaluation.as_ref());
+            let report = CiReport::new("ghost crypto audit", vec![case]);
+            report.print(format);
+            !report.is_failed()
+        }
+    };
+
+    if !compliant {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// ターゲット一覧を一括監査し、スコアの低い順にサマリーを表示
+pub async fn cmd_crypto_audit_bulk(
+    client: &ApiClient,
+    file: &str,
+    concurrency: usize,
+    options: AuditOptions<'_>,
+) -> Result<()> {
+    let targets = bulk::read_targets(file)?;
+    if targets.is_empty() {
+        anyhow::bail!("監査対象がありません: {}", file);
+    }
+
+    let text = options.output == OutputFormat::Text;
+    if text {
+        println!(
+            "\n{} ({}件 / 同時実行数 {})",
+            "🔐 一括暗号監査".bold(),
+            targets.len(),
+            concurrency
+        );
+    }
+
+    let outcomes = bulk::audit_all(client, targets, options.local, concurrency).await;
+
+    let mut results = Vec::new();
+    let mut failures = Vec::new();
+    for (target, outcome) in outcomes {
+        match outcome {
+            Ok(result) => results.push(result),
+            Err(e) => failures.push((target, e)),
+        }
+    }
+    results.sort_by_key(|r| r.security_score);
+    history::record_or_warn(&history::source(client, options.local), &results);
+
+    let now = Utc::now();
+
+    if !text {
+        let mut cases: Vec<ci::Case> = results
+            .iter()
+            .map(|result| {
+                let evaluation = options.policy.map(|p| p.evaluate(result, now));
+                ci::crypto_case(result, evaluation.as_ref())
+            })
+            .collect();
+        cases.extend(
+            failures
+                .iter()
+                .map(|(target, e)| ci::error_case(&target.to_string(), "ghost.crypto.audit", e)),
+        );
+        let report = CiReport::new("ghost crypto audit", cases);
+        report.print(options.output);
+        // ポリシー違反、ポリシー未指定時の安全でない結果、または監査失敗があれば失敗
+        if report.is_failed() {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let mut violations = Vec::new();
+
+    if !results.is_empty() {
+        let total = results.len();
+        let mut secure = 0;
+        let mut rows = Vec::new();
+        for mut result in results {
+            let mut violated = Vec::new();
+            // ポリシー指定時はしきい値とルール違反で判定
+            if let Some(policy) = options.policy {
+                let evaluation = policy.evaluate(&result, now);
+                violated = evaluation
+                    .violations()
+                    .map(|(rule, reasons)| format!("{}: {}", rule.id, reasons.join(", ")))
+                    .collect();
+                result.is_secure = policy.is_secure(&result, &evaluation);
+            }
+            if result.is_secure {
+                secure += 1;
+            }
+
+            let target = result.target.clone();
+            let mut row = CryptoRow::from(result);
+            if !violated.is_empty() {
+                row.status = format!("違反{}件", violated.len());
+                violations.push((target, violated));
+            }
+            rows.push(row);
+        }
+        println!("{}", Table::new(&rows));
+        println!(
+            "安全: {} / 要改善: {}",
+            secure.to_string().green(),
+            (total - secure).to_string().red()
+        );
+    }
+
+    if !violations.is_empty() {
+        println!(
+            "\n{} ({}件)",
+            "✗ ポリシー違反".red().bold(),
+            violations.len()
+        );
+        for (target, violated) in &violations {
+            println!("  {}", target.bold());
+            for v in violated {
+                println!("    - {}", v);
+            }
+        }
+    }
+
+    if !failures.is_empty() {
+        println!("\n{} ({}件)", "⚠ 監査失敗".yellow().bold(), failures.len());
+        for (target, e) in &failures {
+            println!("  {} {}: {:#}", "✗".red(), target, e);
+        }
+    }
+    println!();
+
+    // ポリシー違反または監査できなかったターゲットがあれば失敗
+    if !violations.is_empty() || !failures.is_empty() {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+pub async fn cmd_crypto_results(client: &ApiClient) -> Result<()> {
+    let results: Vec<CryptoAuditResult> = client.get("/crypto/results").await?;
+
+    println!("\n{}", "🔐 暗号監査結果一覧".bold());
+
+    if results.is_empty() {
+        println!("監査結果がありません");
+        return Ok(());
+    }
+
+    let rows: Vec<CryptoRow> = results.into_iter().map(CryptoRow::from).collect();
+
+    let table = Table::new(rows).to_string();
+    println!("{}", table);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_target() {
+        let t = Target::parse("example.com").unwrap();
+        assert_eq!((t.host.as_str(), t.port), ("example.com", 443));
+
+        let t = Target::parse("example.com:8443").unwrap();
+        assert_eq!((t.host.as_str(), t.port), ("example.com", 8443));
+
+        let t = Target::parse("[::1]:8443").unwrap();
+        assert_eq!((t.host.as_str(), t.port), ("::1", 8443));
+        assert_eq!(t.to_string(), "[::1]:8443");
+
+        let t = Target::parse("https://example.com/").unwrap();
+        assert_eq!(t.to_string(), "example.com:443");
+
+        assert!(Target::parse("example.com:http").is_err());
+        assert!(Target::parse("").is_err());
+    }
+
+    #[test]
+    fn test_parse_hsts() {
+        let hsts = Hsts::parse("max-age=31536000; includeSubDomains; preload");
+        assert!(hsts.enabled);
+        assert_eq!(hsts.max_age, Some(31536000));
+        assert!(hsts.include_subdomains);
+
+        assert!(!Hsts::parse("max-age=0").enabled);
+        assert!(!Hsts::parse("includeSubDomains").enabled);
+    }
+}
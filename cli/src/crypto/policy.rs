@@ -0,0 +1,424 @@
+//! 暗号ポリシー
+//!
+//! `policy.toml` に記述した宣言的ルールで監査結果を評価する
+//!
+//! ```toml
+//! [score]
+//! secure = 80
+//! warning = 50
+//!
+//! [[rule]]
+//! id = "tls12-plus"
+//! description = "TLS 1.2以上のみ許可"
+//! severity = "high"
+//! min_tls_version = "1.2"
+//!
+//! [[rule]]
+//! id = "no-cbc"
+//! forbid_ciphers = ["CBC"]
+//! ```
+
+use std::fmt;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::CryptoAuditResult;
+
+/// ルールの重要度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Low,
+    #[default]
+    Medium,
+    High,
+    Critical,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Severity::Info => "info",
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        };
+        f.write_str(s)
+    }
+}
+
+/// スコアの色分けしきい値
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScoreThresholds {
+    /// これ以上を安全とみなす
+    pub secure: u8,
+    /// これ以上を注意とみなす（未満は危険）
+    pub warning: u8,
+}
+
+impl Default for ScoreThresholds {
+    fn default() -> Self {
+        Self {
+            secure: 80,
+            warning: 50,
+        }
+    }
+}
+
+/// 宣言的ルール（指定した条件をすべて満たす必要がある）
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Rule {
+    pub id: String,
+    pub description: Option<String>,
+    pub severity: Severity,
+    /// 許可する最低TLSバージョン (例: "1.2")
+    pub min_tls_version: Option<String>,
+    /// 暗号スイート名に含まれてはならない文字列 (例: "CBC")
+    pub forbid_ciphers: Vec<String>,
+    /// 許可するリーフ証明書の鍵種別 (例: "ECDSA")
+    pub key_types: Vec<String>,
+    /// RSA鍵の最低ビット長
+    pub min_rsa_bits: Option<u32>,
+    /// ECDSA鍵の最低ビット長 (P-256 = 256)
+    pub min_ec_bits: Option<u32>,
+    /// HSTSヘッダーを必須とする
+    pub require_hsts: bool,
+    /// 信頼済み証明書チェーンを必須とする
+    pub require_trusted_chain: bool,
+    /// 有効期限までの最低日数
+    pub min_days_to_expiry: Option<i64>,
+    /// 最低セキュリティスコア
+    pub min_score: Option<u8>,
+}
+
+/// ポリシー定義
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Policy {
+    #[serde(default)]
+    pub score: ScoreThresholds,
+    #[serde(default, rename = "rule")]
+    pub rules: Vec<Rule>,
+}
+
+/// ルールごとの評価結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    /// 違反（理由）
+    Violation(Vec<String>),
+    /// 判定に必要な情報がない（理由）
+    Unknown(String),
+}
+
+/// 1ターゲットの評価結果
+#[derive(Debug, Clone)]
+pub struct Evaluation<'a> {
+    pub verdicts: Vec<(&'a Rule, Verdict)>,
+}
+
+impl Evaluation<'_> {
+    pub fn violations(&self) -> impl Iterator<Item = (&Rule, &[String])> {
+        self.verdicts
+            .iter()
+            .filter_map(|(rule, verdict)| match verdict {
+                Verdict::Violation(reasons) => Some((*rule, reasons.as_slice())),
+                _ => None,
+            })
+    }
+
+    pub fn is_compliant(&self) -> bool {
+        self.violations().next().is_none()
+    }
+}
+
+impl Policy {
+    /// ポリシー指定時の安全判定（ルール違反がなく、スコアが `score.secure` 以上）
+    pub fn is_secure(&self, result: &CryptoAuditResult, evaluation: &Evaluation) -> bool {
+        evaluation.is_compliant() && result.security_score >= self.score.secure
+    }
+}
+
+/// `TLSv1.2` / `TLS 1.3` / `SSLv3` などを比較可能な値に変換
+pub fn tls_version_rank(version: &str) -> Option<(u8, u8)> {
+    let upper = version.to_uppercase();
+    if upper.contains("SSL") {
+        return Some((0, 0));
+    }
+    let digits: String = upper
+        .chars()
+        .skip_while(|c| !c.is_ascii_digit())
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    let (major, minor) = digits.split_once('.').unwrap_or((&digits, "0"));
+    Some((major.parse().ok()?, minor.parse().ok()?))
+}
+
+impl Policy {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("ポリシーの読み込みに失敗: {}", path.display()))?;
+        let policy: Policy = toml::from_str(&text)
+            .with_context(|| format!("ポリシーの形式が不正です: {}", path.display()))?;
+        policy.validate()?;
+        Ok(policy)
+    }
+
+    fn validate(&self) -> Result<()> {
+        let mut seen = std::collections::HashSet::new();
+        for rule in &self.rules {
+            anyhow::ensure!(!rule.id.is_empty(), "ルールIDが空です");
+            anyhow::ensure!(
+                seen.insert(&rule.id),
+                "ルールIDが重複しています: {}",
+                rule.id
+            );
+            if let Some(version) = &rule.min_tls_version {
+                anyhow::ensure!(
+                    tls_version_rank(version).is_some(),
+                    "TLSバージョンが不正です: {} ({})",
+                    version,
+                    rule.id
+                );
+            }
+        }
+        Ok(())
+    }
+
+    pub fn evaluate<'a>(
+        &'a self,
+        result: &CryptoAuditResult,
+        now: DateTime<Utc>,
+    ) -> Evaluation<'a> {
+        Evaluation {
+            verdicts: self
+                .rules
+                .iter()
+                .map(|rule| (rule, rule.evaluate(result, now)))
+                .collect(),
+        }
+    }
+}
+
+impl Rule {
+    fn needs_certificate(&self) -> bool {
+        !self.key_types.is_empty()
+            || self.min_rsa_bits.is_some()
+            || self.min_ec_bits.is_some()
+            || self.require_trusted_chain
+            || self.min_days_to_expiry.is_some()
+    }
+
+    /// 判定できる条件を評価し、違反がなければ情報が足りない条件を不明として返す
+    pub fn evaluate(&self, result: &CryptoAuditResult, now: DateTime<Utc>) -> Verdict {
+        let leaf = result.certificates.first();
+        let mut reasons = Vec::new();
+        let mut unknown = Vec::new();
+        if self.needs_certificate() && leaf.is_none() {
+            unknown.push("証明書情報がありません (--local で監査してください)");
+        }
+        if self.require_hsts && result.hsts.is_none() {
+            unknown.push("HSTSを確認できません (--local でHTTPSサービスを監査してください)");
+        }
+
+        if let Some(min) = &self.min_tls_version {
+            let ok = match (tls_version_rank(&result.tls_version), tls_version_rank(min)) {
+                (Some(actual), Some(min)) => actual >= min,
+                _ => false,
+            };
+            if !ok {
+                reasons.push(format!("TLSバージョン {} < {}", result.tls_version, min));
+            }
+        }
+
+        let suite = result.cipher_suite.to_uppercase();
+        for pattern in &self.forbid_ciphers {
+            if suite.contains(&pattern.to_uppercase()) {
+                reasons.push(format!(
+                    "禁止された暗号方式 {} ({})",
+                    result.cipher_suite, pattern
+                ));
+            }
+        }
+
+        if let Some(leaf) = leaf {
+            if !self.key_types.is_empty()
+                && !self
+                    .key_types
+                    .iter()
+                    .any(|t| t.eq_ignore_ascii_case(&leaf.key_type))
+            {
+                reasons.push(format!("鍵種別 {} は許可されていません", leaf.key_type));
+            }
+            let min_bits = match leaf.key_type.as_str() {
+                "RSA" => self.min_rsa_bits,
+                "ECDSA" => self.min_ec_bits,
+                _ => None,
+            };
+            if let Some(min) = min_bits.filter(|&min| leaf.key_bits < min) {
+                reasons.push(format!(
+                    "鍵長 {} {}bit < {}bit",
+                    leaf.key_type, leaf.key_bits, min
+                ));
+            }
+            if let Some(min) = self.min_days_to_expiry {
+                let days = leaf.days_until_expiry(now);
+                if days < min {
+                    reasons.push(format!("有効期限まで{}日 < {}日", days, min));
+                }
+            }
+        }
+
+        if self.require_trusted_chain && leaf.is_some() {
+            if let Some(e) = &result.chain_error {
+                reasons.push(format!("証明書チェーンが信頼されていません: {}", e));
+            }
+        }
+
+        if self.require_hsts && result.hsts.as_ref().is_some_and(|h| !h.enabled) {
+            reasons.push("HSTSヘッダーがありません".to_string());
+        }
+
+        if let Some(min) = self.min_score.filter(|&min| result.security_score < min) {
+            reasons.push(format!("スコア {} < {}", result.security_score, min));
+        }
+
+        if !reasons.is_empty() {
+            Verdict::Violation(reasons)
+        } else if !unknown.is_empty() {
+            Verdict::Unknown(unknown.join(" / "))
+        } else {
+            Verdict::Pass
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::{testing, CertificateInfo, Hsts};
+
+    const POLICY: &str = r#"
+[score]
+secure = 90
+
+[[rule]]
+id = "tls12-plus"
+severity = "high"
+min_tls_version = "1.2"
+
+[[rule]]
+id = "no-cbc"
+forbid_ciphers = ["CBC"]
+
+[[rule]]
+id = "ecdsa-p256"
+key_types = ["ECDSA"]
+min_ec_bits = 256
+
+[[rule]]
+id = "hsts"
+require_hsts = true
+"#;
+
+    fn result(tls: &str, cipher: &str) -> CryptoAuditResult {
+        testing::result("example.com:443", tls, cipher, 90)
+    }
+
+    fn cert(key_type: &str, key_bits: u32) -> CertificateInfo {
+        let now = Utc::now();
+        CertificateInfo {
+            subject: "CN=example.com".to_string(),
+            issuer: "CN=Example CA".to_string(),
+            san: Vec::new(),
+            serial: "01".to_string(),
+            not_before: now,
+            not_after: now + chrono::Duration::days(90),
+            key_type: key_type.to_string(),
+            key_bits,
+            signature_algorithm: "ecdsa-with-SHA256".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_tls_version_rank() {
+        assert_eq!(tls_version_rank("TLSv1.3"), Some((1, 3)));
+        assert_eq!(tls_version_rank("TLS 1.2"), Some((1, 2)));
+        assert_eq!(tls_version_rank("1.2"), Some((1, 2)));
+        assert_eq!(tls_version_rank("SSLv3"), Some((0, 0)));
+        assert_eq!(tls_version_rank("不明"), None);
+    }
+
+    #[test]
+    fn test_evaluate_policy() {
+        let policy: Policy = toml::from_str(POLICY).unwrap();
+        policy.validate().unwrap();
+        assert_eq!(policy.score.secure, 90);
+        assert_eq!(policy.score.warning, 50);
+        assert_eq!(policy.rules[0].severity, Severity::High);
+
+        let now = Utc::now();
+
+        // サーバー監査結果は証明書・HSTSが不明
+        let r = result("TLS 1.1", "TLS_ECDHE_RSA_WITH_AES_128_CBC_SHA");
+        let eval = policy.evaluate(&r, now);
+        let violated: Vec<&str> = eval
+            .violations()
+            .map(|(rule, _)| rule.id.as_str())
+            .collect();
+        assert_eq!(violated, vec!["tls12-plus", "no-cbc"]);
+        assert!(matches!(eval.verdicts[2].1, Verdict::Unknown(_)));
+        assert!(matches!(eval.verdicts[3].1, Verdict::Unknown(_)));
+
+        let mut r = result("TLSv1.3", "TLS13_AES_256_GCM_SHA384");
+        r.certificates.push(cert("RSA", 4096));
+        r.hsts = Some(Hsts::default());
+        let eval = policy.evaluate(&r, now);
+        let violated: Vec<&str> = eval
+            .violations()
+            .map(|(rule, _)| rule.id.as_str())
+            .collect();
+        assert_eq!(violated, vec!["ecdsa-p256", "hsts"]);
+
+        r.certificates[0] = cert("ECDSA", 256);
+        r.hsts = Some(Hsts::parse("max-age=31536000; includeSubDomains"));
+        assert!(policy.evaluate(&r, now).is_compliant());
+    }
+
+    #[test]
+    fn test_evaluate_without_certificate() {
+        let policy: Policy = toml::from_str(
+            r#"
+[[rule]]
+id = "tls12-ecdsa"
+min_tls_version = "1.2"
+key_types = ["ECDSA"]
+"#,
+        )
+        .unwrap();
+        let now = Utc::now();
+
+        // 証明書がなくても判定できる条件の違反は報告する
+        let eval = policy.evaluate(&result("TLSv1.0", "TLS_RSA_WITH_AES_128_CBC_SHA"), now);
+        assert!(!eval.is_compliant());
+
+        let eval = policy.evaluate(&result("TLSv1.3", "TLS13_AES_256_GCM_SHA384"), now);
+        assert!(matches!(eval.verdicts[0].1, Verdict::Unknown(_)));
+        assert!(eval.is_compliant());
+    }
+
+    #[test]
+    fn test_validate_rejects_duplicates() {
+        let policy: Policy =
+            toml::from_str("[[rule]]\nid = \"a\"\n[[rule]]\nid = \"a\"\n").unwrap();
+        assert!(policy.validate().is_err());
+        assert!(toml::from_str::<Policy>("[[rule]]\nid = \"a\"\nunknown = 1\n").is_err());
+    }
+}
@@ -0,0 +1,718 @@
+//! 暗号監査レポート
+//!
+//! `/crypto/report` の監査結果を集計し、端末表示・Markdown・HTML で出力する
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use colored::*;
+use serde::Deserialize;
+use tabled::{Table, Tabled};
+
+use super::local::is_strong_key;
+use super::policy::tls_version_rank;
+use crate::util::bar;
+use crate::{ApiClient, CryptoAuditResult};
+
+/// 最も弱いターゲットとして表示する件数（安全かつ評価B以上のターゲットは含めない）
+const WEAKEST_COUNT: usize = 5;
+/// 証明書更新を推奨する残り日数
+const EXPIRY_WARN_DAYS: i64 = 30;
+
+/// レポートの出力形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    /// 端末表示
+    Text,
+    /// Markdown
+    Markdown,
+    /// HTML
+    Html,
+}
+
+/// スコアに基づく評価
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Grade {
+    A,
+    B,
+    C,
+    D,
+    F,
+}
+
+impl Grade {
+    pub const ALL: [Grade; 5] = [Grade::A, Grade::B, Grade::C, Grade::D, Grade::F];
+
+    pub fn from_score(score: u8) -> Self {
+        match score {
+            90.. => Grade::A,
+            80..=89 => Grade::B,
+            65..=79 => Grade::C,
+            50..=64 => Grade::D,
+            _ => Grade::F,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Grade::A => "A",
+            Grade::B => "B",
+            Grade::C => "C",
+            Grade::D => "D",
+            Grade::F => "F",
+        }
+    }
+}
+
+/// 改善項目
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Remediation {
+    LegacyProtocol,
+    NonAeadCipher,
+    NoForwardSecrecy,
+    UntrustedChain,
+    ExpiringCertificate,
+    WeakKey,
+    MissingHsts,
+}
+
+impl Remediation {
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::LegacyProtocol => "TLS 1.2未満のプロトコル",
+            Self::NonAeadCipher => "AEADではない暗号方式 (CBC等)",
+            Self::NoForwardSecrecy => "前方秘匿性のない鍵交換",
+            Self::UntrustedChain => "信頼されていない証明書チェーン",
+            Self::ExpiringCertificate => "期限切れ・期限間近の証明書",
+            Self::WeakKey => "鍵長の不足",
+            Self::MissingHsts => "HSTS未設定",
+        }
+    }
+
+    pub fn hint(self) -> &'static str {
+        match self {
+            Self::LegacyProtocol => {
+                "TLS 1.0/1.1 と SSL を無効化し、TLS 1.2 以上（可能なら TLS 1.3）のみを許可してください"
+            }
+            Self::NonAeadCipher => {
+                "AES-GCM または ChaCha20-Poly1305 の暗号スイートを優先し、CBCモードを無効化してください"
+            }
+            Self::NoForwardSecrecy => "ECDHE による鍵交換を有効化し、静的RSA鍵交換を無効化してください",
+            Self::UntrustedChain => {
+                "信頼されたCAが発行した証明書と中間証明書を含む完全なチェーンを設定してください"
+            }
+            Self::ExpiringCertificate => "証明書を更新し、ACME等による自動更新を検討してください",
+            Self::WeakKey => "RSA 2048bit 以上、または ECDSA P-256 以上の鍵で証明書を再発行してください",
+            Self::MissingHsts => {
+                "Strict-Transport-Security ヘッダー (max-age=31536000 以上) を付与してください"
+            }
+        }
+    }
+
+    /// 監査結果に該当する改善項目
+    pub fn detect(result: &CryptoAuditResult, now: DateTime<Utc>) -> Vec<Self> {
+        let mut found = Vec::new();
+        let suite = result.cipher_suite.to_uppercase();
+
+        if tls_version_rank(&result.tls_version).is_none_or(|v| v < (1, 2)) {
+            found.push(Self::LegacyProtocol);
+        }
+        if !(suite.contains("GCM") || suite.contains("CHACHA20") || suite.contains("CCM")) {
+            found.push(Self::NonAeadCipher);
+        }
+        if !(suite.starts_with("TLS13_")
+            || suite.starts_with("TLS_AES")
+            || suite.starts_with("TLS_CHACHA20")
+            || suite.contains("DHE"))
+        {
+            found.push(Self::NoForwardSecrecy);
+        }
+        if result.chain_error.is_some() {
+            found.push(Self::UntrustedChain);
+        }
+        if let Some(leaf) = result.certificates.first() {
+            if leaf.days_until_expiry(now) <= EXPIRY_WARN_DAYS {
+                found.push(Self::ExpiringCertificate);
+            }
+            if !is_strong_key(leaf) {
+                found.push(Self::WeakKey);
+            }
+        }
+        if result.hsts.as_ref().is_some_and(|h| !h.enabled) {
+            found.push(Self::MissingHsts);
+        }
+
+        found
+    }
+}
+
+/// `/crypto/report` のレスポンス
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServerCryptoReport {
+    /// サーバーでの生成日時
+    #[serde(default)]
+    pub generated_at: Option<DateTime<Utc>>,
+    /// レポート対象の監査結果
+    pub results: Vec<CryptoAuditResult>,
+}
+
+/// 弱いターゲットの概要
+#[derive(Debug, Clone)]
+pub struct WeakTarget {
+    pub target: String,
+    pub score: u8,
+    pub grade: Grade,
+    pub tls_version: String,
+    pub cipher_suite: String,
+    pub issues: Vec<Remediation>,
+}
+
+/// 暗号監査レポート
+#[derive(Debug, Clone)]
+pub struct CryptoReport {
+    pub generated_at: DateTime<Utc>,
+    pub total: usize,
+    pub secure: usize,
+    pub average_score: f64,
+    pub grades: BTreeMap<Grade, usize>,
+    pub weakest: Vec<WeakTarget>,
+    /// (プロトコル, 件数) 件数の多い順
+    pub protocols: Vec<(String, usize)>,
+    /// (暗号方式, 件数) 件数の多い順
+    pub ciphers: Vec<(String, usize)>,
+    /// (改善項目, 該当ターゲット)
+    pub remediations: Vec<(Remediation, Vec<String>)>,
+}
+
+/// 件数の多い順（同数は名前順）に並べる
+fn ranked(counts: BTreeMap<String, usize>) -> Vec<(String, usize)> {
+    let mut ranked: Vec<_> = counts.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    ranked
+}
+
+impl CryptoReport {
+    pub fn build(results: &[CryptoAuditResult], now: DateTime<Utc>) -> Self {
+        let mut grades: BTreeMap<Grade, usize> = Grade::ALL.iter().map(|&g| (g, 0)).collect();
+        let mut protocols = BTreeMap::new();
+        let mut ciphers = BTreeMap::new();
+        let mut remediations: BTreeMap<Remediation, Vec<String>> = BTreeMap::new();
+        let mut targets = Vec::new();
+
+        for result in results {
+            let grade = Grade::from_score(result.security_score);
+            *grades.entry(grade).or_default() += 1;
+            *protocols.entry(result.tls_version.clone()).or_default() += 1;
+            *ciphers.entry(result.cipher_suite.clone()).or_default() += 1;
+
+            let issues = Remediation::detect(result, now);
+            for &issue in &issues {
+                remediations
+                    .entry(issue)
+                    .or_default()
+                    .push(result.target.clone());
+            }
+            if result.is_secure && grade <= Grade::B {
+                continue;
+            }
+            targets.push(WeakTarget {
+                target: result.target.clone(),
+                score: result.security_score,
+                grade,
+                tls_version: result.tls_version.clone(),
+                cipher_suite: result.cipher_suite.clone(),
+                issues,
+            });
+        }
+
+        targets.sort_by(|a, b| a.score.cmp(&b.score).then_with(|| a.target.cmp(&b.target)));
+        targets.truncate(WEAKEST_COUNT);
+
+        let total_score: u64 = results.iter().map(|r| r.security_score as u64).sum();
+
+        Self {
+            generated_at: now,
+            total: results.len(),
+            secure: results.iter().filter(|r| r.is_secure).count(),
+            average_score: if results.is_empty() {
+                0.0
+            } else {
+                total_score as f64 / results.len() as f64
+            },
+            grades,
+            weakest: targets,
+            protocols: ranked(protocols),
+            ciphers: ranked(ciphers),
+            remediations: remediations.into_iter().collect(),
+        }
+    }
+
+    fn issue_labels(issues: &[Remediation]) -> String {
+        issues
+            .iter()
+            .map(|i| i.title())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// 端末向けに表示
+    pub fn render_text(&self) -> String {
+        #[derive(Tabled)]
+        struct WeakRow {
+            #[tabled(rename = "ターゲット")]
+            target: String,
+            #[tabled(rename = "評価")]
+            grade: String,
+            #[tabled(rename = "スコア")]
+            score: String,
+            #[tabled(rename = "TLS")]
+            tls_version: String,
+            #[tabled(rename = "問題点")]
+            issues: String,
+        }
+
+        let mut out = String::new();
+        let _ = writeln!(out, "\n{}", "🔐 暗号監査レポート".bold());
+        let _ = writeln!(out, "{}", "=".repeat(40));
+        let _ = writeln!(
+            out,
+            "生成日時: {}",
+            self.generated_at.format("%Y-%m-%d %H:%M:%S UTC")
+        );
+        let _ = writeln!(
+            out,
+            "ターゲット: {}件 (安全: {} / 要改善: {})",
+            self.total,
+            self.secure.to_string().green(),
+            (self.total - self.secure).to_string().red()
+        );
+        let _ = writeln!(out, "平均スコア: {:.1}", self.average_score);
+
+        let _ = writeln!(out, "\n--- 評価分布 ---");
+        let max = self.grades.values().copied().max().unwrap_or(0) as u64;
+        for (grade, count) in &self.grades {
+            let line = bar(*count as u64, max, 30);
+            let line = match grade {
+                Grade::A | Grade::B => line.green(),
+                Grade::C => line.yellow(),
+                Grade::D | Grade::F => line.red(),
+            };
+            let _ = writeln!(out, "  {}  {:>4}  {}", grade.label(), count, line);
+        }
+
+        if !self.weakest.is_empty() {
+            let _ = writeln!(out, "\n--- スコアの低いターゲット ---");
+            let rows: Vec<WeakRow> = self
+                .weakest
+                .iter()
+                .map(|w| WeakRow {
+                    target: w.target.clone(),
+                    grade: w.grade.label().to_string(),
+                    score: format!("{}点", w.score),
+                    tls_version: w.tls_version.clone(),
+                    issues: Self::issue_labels(&w.issues),
+                })
+                .collect();
+            let _ = writeln!(out, "{}", Table::new(rows));
+        }
+
+        for (title, stats) in [("プロトコル", &self.protocols), ("暗号方式", &self.ciphers)]
+        {
+            if stats.is_empty() {
+                continue;
+            }
+            let _ = writeln!(out, "\n--- {}の利用状況 ---", title);
+            let width = stats.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+            let max = stats.first().map(|(_, c)| *c as u64).unwrap_or(0);
+            for (name, count) in stats {
+                let _ = writeln!(
+                    out,
+                    "  {:<width$}  {:>4}  {}",
+                    name,
+                    count,
+                    bar(*count as u64, max, 20).cyan(),
+                    width = width
+                );
+            }
+        }
+
+        if !self.remediations.is_empty() {
+            let _ = writeln!(out, "\n--- 改善のヒント ---");
+            for (remediation, targets) in &self.remediations {
+                let _ = writeln!(
+                    out,
+                    "  {} {} ({}件)",
+                    "•".yellow(),
+                    remediation.title().bold(),
+                    targets.len()
+                );
+                let _ = writeln!(out, "    {}", remediation.hint());
+                let _ = writeln!(out, "    対象: {}", targets.join(", ").dimmed());
+            }
+        }
+
+        out
+    }
+
+    /// Markdown形式で出力
+    pub fn render_markdown(&self) -> String {
+        let escape = |s: &str| s.replace('|', "\\|");
+        let mut out = String::new();
+
+        let _ = writeln!(out, "# 暗号監査レポート\n");
+        let _ = writeln!(
+            out,
+            "- 生成日時: {}",
+            self.generated_at.format("%Y-%m-%d %H:%M:%S UTC")
+        );
+        let _ = writeln!(
+            out,
+            "- ターゲット: {}件 (安全: {} / 要改善: {})",
+            self.total,
+            self.secure,
+            self.total - self.secure
+        );
+        let _ = writeln!(out, "- 平均スコア: {:.1}\n", self.average_score);
+
+        let _ = writeln!(out, "## 評価分布\n");
+        let _ = writeln!(out, "| 評価 | 件数 |\n| --- | ---: |");
+        for (grade, count) in &self.grades {
+            let _ = writeln!(out, "| {} | {} |", grade.label(), count);
+        }
+
+        if !self.weakest.is_empty() {
+            let _ = writeln!(out, "\n## スコアの低いターゲット\n");
+            let _ = writeln!(
+                out,
+                "| ターゲット | 評価 | スコア | TLS | 暗号方式 | 問題点 |\n| --- | --- | ---: | --- | --- | --- |"
+            );
+            for w in &self.weakest {
+                let _ = writeln!(
+                    out,
+                    "| {} | {} | {} | {} | `{}` | {} |",
+                    escape(&w.target),
+                    w.grade.label(),
+                    w.score,
+                    escape(&w.tls_version),
+                    escape(&w.cipher_suite),
+                    escape(&Self::issue_labels(&w.issues))
+                );
+            }
+        }
+
+        for (title, stats) in [("プロトコル", &self.protocols), ("暗号方式", &self.ciphers)]
+        {
+            if stats.is_empty() {
+                continue;
+            }
+            let _ = writeln!(out, "\n## {}の利用状況\n", title);
+            let _ = writeln!(out, "| {} | 件数 |\n| --- | ---: |", title);
+            for (name, count) in stats {
+                let _ = writeln!(out, "| `{}` | {} |", escape(name), count);
+            }
+        }
+
+        if !self.remediations.is_empty() {
+            let _ = writeln!(out, "\n## 改善のヒント\n");
+            for (remediation, targets) in &self.remediations {
+                let _ = writeln!(
+                    out,
+                    "### {} ({}件)\n\n{}\n\n対象: {}\n",
+                    remediation.title(),
+                    targets.len(),
+                    remediation.hint(),
+                    targets
+                        .iter()
+                        .map(|t| format!("`{}`", t))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+            }
+        }
+
+        out
+    }
+
+    /// HTML形式で出力（外部リソースに依存しない単一ファイル）
+    pub fn render_html(&self) -> String {
+        fn escape(s: &str) -> String {
+            s.replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;")
+                .replace('"', "&quot;")
+        }
+
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            r#"<!DOCTYPE html>
+<html lang="ja">
+<head>
+<meta charset="utf-8">
+<title>暗号監査レポート</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em; color: #222; }}
+table {{ border-collapse: collapse; margin-bottom: 1.5em; }}
+th, td {{ border: 1px solid #ccc; padding: 4px 10px; text-align: left; }}
+th {{ background: #f4f4f4; }}
+.num {{ text-align: right; }}
+.grade-A, .grade-B {{ color: #1a7f37; }}
+.grade-C {{ color: #9a6700; }}
+.grade-D, .grade-F {{ color: #cf222e; }}
+.bar {{ display: inline-block; height: 0.8em; background: #0969da; }}
+</style>
+</head>
+<body>
+<h1>暗号監査レポート</h1>"#
+        );
+        let _ = writeln!(
+            out,
+            "<ul>\n<li>生成日時: {}</li>\n<li>ターゲット: {}件 (安全: {} / 要改善: {})</li>\n<li>平均スコア: {:.1}</li>\n</ul>",
+            self.generated_at.format("%Y-%m-%d %H:%M:%S UTC"),
+            self.total,
+            self.secure,
+            self.total - self.secure,
+            self.average_score
+        );
+
+        let _ = writeln!(
+            out,
+            "<h2>評価分布</h2>\n<table>\n<tr><th>評価</th><th>件数</th><th></th></tr>"
+        );
+        let max = self.grades.values().copied().max().unwrap_or(0).max(1);
+        for (grade, count) in &self.grades {
+            let _ = writeln!(
+                out,
+                "<tr><td class=\"grade-{0}\">{0}</td><td class=\"num\">{1}</td><td><span class=\"bar\" style=\"width: {2}px\"></span></td></tr>",
+                grade.label(),
+                count,
+                count * 200 / max
+            );
+        }
+        let _ = writeln!(out, "</table>");
+
+        if !self.weakest.is_empty() {
+            let _ = writeln!(
+                out,
+                "<h2>スコアの低いターゲット</h2>\n<table>\n<tr><th>ターゲット</th><th>評価</th><th>スコア</th><th>TLS</th><th>暗号方式</th><th>問題点</th></tr>"
+            );
+            for w in &self.weakest {
+                let _ = writeln!(
+                    out,
+                    "<tr><td>{}</td><td class=\"grade-{}\">{}</td><td class=\"num\">{}</td><td>{}</td><td><code>{}</code></td><td>{}</td></tr>",
+                    escape(&w.target),
+                    w.grade.label(),
+                    w.grade.label(),
+                    w.score,
+                    escape(&w.tls_version),
+                    escape(&w.cipher_suite),
+                    escape(&Self::issue_labels(&w.issues))
+                );
+            }
+            let _ = writeln!(out, "</table>");
+        }
+
+        for (title, stats) in [("プロトコル", &self.protocols), ("暗号方式", &self.ciphers)]
+        {
+            if stats.is_empty() {
+                continue;
+            }
+            let _ = writeln!(
+                out,
+                "<h2>{0}の利用状況</h2>\n<table>\n<tr><th>{0}</th><th>件数</th></tr>",
+                title
+            );
+            for (name, count) in stats {
+                let _ = writeln!(
+                    out,
+                    "<tr><td><code>{}</code></td><td class=\"num\">{}</td></tr>",
+                    escape(name),
+                    count
+                );
+            }
+            let _ = writeln!(out, "</table>");
+        }
+
+        if !self.remediations.is_empty() {
+            let _ = writeln!(out, "<h2>改善のヒント</h2>");
+            for (remediation, targets) in &self.remediations {
+                let _ = writeln!(
+                    out,
+                    "<h3>{} ({}件)</h3>\n<p>{}</p>\n<p>対象: {}</p>",
+                    escape(remediation.title()),
+                    targets.len(),
+                    escape(remediation.hint()),
+                    targets
+                        .iter()
+                        .map(|t| format!("<code>{}</code>", escape(t)))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+            }
+        }
+
+        let _ = writeln!(out, "</body>\n</html>");
+        out
+    }
+
+    pub fn render(&self, format: ReportFormat) -> String {
+        match format {
+            ReportFormat::Text => self.render_text(),
+            ReportFormat::Markdown => self.render_markdown(),
+            ReportFormat::Html => self.render_html(),
+        }
+    }
+}
+
+/// `/crypto/report` からレポートを作成する
+///
+/// サーバーがエンドポイントに未対応、またはレスポンス形式を解釈できない場合に限り、
+/// `/crypto/results` の監査結果から同じ集計をローカルで行う
+async fn fetch(client: &ApiClient) -> Result<CryptoReport> {
+    let now = Utc::now();
+    let fallback = match client
+        .get_if_supported::<serde_json::Value>("/crypto/report")
+        .await?
+    {
+        Some(value) => match serde_json::from_value::<ServerCryptoReport>(value) {
+            Ok(report) => {
+                return Ok(CryptoReport::build(
+                    &report.results,
+                    report.generated_at.unwrap_or(now),
+                ))
+            }
+            Err(e) => format!("レスポンス形式を解釈できません: {}", e),
+        },
+        None => "サーバーが未対応です".to_string(),
+    };
+
+    eprintln!(
+        "{} /crypto/report を利用できないため、/crypto/results から集計します ({})",
+        "⚠".yellow(),
+        fallback
+    );
+    let results: Vec<CryptoAuditResult> = client.get("/crypto/results").await?;
+    Ok(CryptoReport::build(&results, now))
+}
+
+pub async fn cmd_crypto_report(
+    client: &ApiClient,
+    format: ReportFormat,
+    out_file: Option<&Path>,
+) -> Result<()> {
+    let report = fetch(client).await?;
+
+    match out_file {
+        Some(path) => {
+            // ファイル出力時は色付けしない
+            colored::control::set_override(false);
+            let rendered = report.render(format);
+            colored::control::unset_override();
+            std::fs::write(path, rendered)
+                .with_context(|| format!("レポートの書き込みに失敗: {}", path.display()))?;
+            println!("{} レポートを出力しました: {}", "✓".green(), path.display());
+        }
+        None => print!("{}", report.render(format)),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::testing::result;
+
+    fn sample() -> Vec<CryptoAuditResult> {
+        vec![
+            result("a.example.com", "TLSv1.3", "TLS13_AES_256_GCM_SHA384", 95),
+            result("b.example.com", "TLSv1.3", "TLS13_AES_256_GCM_SHA384", 85),
+            result(
+                "c.example.com",
+                "TLSv1.2",
+                "TLS_ECDHE_RSA_WITH_AES_128_CBC_SHA",
+                55,
+            ),
+            result(
+                "d.example.com",
+                "TLSv1.0",
+                "TLS_RSA_WITH_AES_128_CBC_SHA",
+                10,
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_parse_server_report() {
+        let report: ServerCryptoReport = serde_json::from_value(serde_json::json!({
+            "generated_at": "2026-10-01T00:00:00Z",
+            "results": [{
+                "target": "a.example.com:443",
+                "tls_version": "TLSv1.3",
+                "cipher_suite": "TLS13_AES_256_GCM_SHA384",
+                "is_secure": true,
+                "security_score": 95
+            }]
+        }))
+        .unwrap();
+        assert_eq!(report.results.len(), 1);
+        assert!(report.generated_at.is_some());
+
+        // 集計前の監査結果を含まない形式は未対応
+        assert!(
+            serde_json::from_value::<ServerCryptoReport>(serde_json::json!({
+                "total": 1
+            }))
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_build_report() {
+        let report = CryptoReport::build(&sample(), Utc::now());
+
+        assert_eq!(report.total, 4);
+        assert_eq!(report.secure, 2);
+        assert!((report.average_score - 61.25).abs() < f64::EPSILON);
+        assert_eq!(report.grades[&Grade::A], 1);
+        assert_eq!(report.grades[&Grade::B], 1);
+        assert_eq!(report.grades[&Grade::C], 0);
+        assert_eq!(report.grades[&Grade::F], 1);
+        assert_eq!(report.weakest[0].target, "d.example.com");
+        // 安全な評価A/Bのターゲットは含めない
+        assert_eq!(report.weakest.len(), 2);
+        assert_eq!(report.protocols[0], ("TLSv1.3".to_string(), 2));
+
+        let remediations: BTreeMap<_, _> = report.remediations.iter().cloned().collect();
+        assert_eq!(
+            remediations[&Remediation::LegacyProtocol],
+            vec!["d.example.com".to_string()]
+        );
+        assert_eq!(remediations[&Remediation::NonAeadCipher].len(), 2);
+        assert_eq!(
+            remediations[&Remediation::NoForwardSecrecy],
+            vec!["d.example.com".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_render_exports() {
+        let mut results = sample();
+        results[0].target = "<script>".to_string();
+        let report = CryptoReport::build(&results, Utc::now());
+
+        let markdown = report.render_markdown();
+        assert!(markdown.starts_with("# 暗号監査レポート"));
+        assert!(markdown.contains("| A | 1 |"));
+        assert!(markdown.contains("## 改善のヒント"));
+
+        let html = report.render_html();
+        assert!(html.contains("<h2>評価分布</h2>"));
+        assert!(!html.contains("<script>"));
+        assert!(html.trim_end().ends_with("</html>"));
+    }
+}
@@ -0,0 +1,17 @@
+//! テスト用の監査結果
+
+use crate::CryptoAuditResult;
+
+/// 証明書・HSTSを含まない監査結果（スコア80以上を安全とする）
+pub fn result(target: &str, tls: &str, cipher: &str, score: u8) -> CryptoAuditResult {
+    CryptoAuditResult {
+        target: target.to_string(),
+        tls_version: tls.to_string(),
+        cipher_suite: cipher.to_string(),
+        is_secure: score >= 80,
+        security_score: score,
+        certificates: Vec::new(),
+        chain_error: None,
+        hsts: None,
+    }
+}
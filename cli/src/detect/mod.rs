@@ -0,0 +1,279 @@
+//! 異常検知モジュール
+//!
+//! `/detector/check` を実行し、発火したルール・証拠・対象エージェントを表示する
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use colored::*;
+use serde::Deserialize;
+
+use crate::ci::{self, CiReport, Level, OutputFormat};
+use crate::rules::short_id;
+use crate::util::slug;
+use crate::{AgentInfo, ApiClient};
+
+/// 1件あたりに表示する証拠の最大件数
+const MAX_EVIDENCE: usize = 3;
+
+/// 証拠1件の表示幅
+const EVIDENCE_WIDTH: usize = 100;
+
+/// 検知結果（従来のアラート形式のレスポンスも受け付ける）
+#[derive(Debug, Clone, Deserialize)]
+pub struct Detection {
+    /// 作成されたアラートのID
+    #[serde(default, alias = "id")]
+    pub alert_id: Option<String>,
+    #[serde(alias = "severity")]
+    pub level: String,
+    pub title: String,
+    #[serde(default)]
+    pub message: String,
+    #[serde(default)]
+    pub rule_id: Option<String>,
+    #[serde(default)]
+    pub rule_name: Option<String>,
+    #[serde(default)]
+    pub agent_id: Option<String>,
+    #[serde(default, alias = "source")]
+    pub hostname: Option<String>,
+    /// ルールに一致したイベント
+    #[serde(default)]
+    pub evidence: Vec<serde_json::Value>,
+}
+
+impl Detection {
+    /// ルール名（なければタイトル）
+    pub fn rule_label(&self) -> &str {
+        self.rule_name.as_deref().unwrap_or(&self.title)
+    }
+
+    /// 対象エージェントの表示名
+    pub fn agent_label(&self) -> Option<String> {
+        match (&self.hostname, &self.agent_id) {
+            (Some(host), Some(id)) if host != id => Some(format!("{} ({})", host, id)),
+            (Some(host), _) => Some(host.clone()),
+            (None, Some(id)) => Some(id.clone()),
+            (None, None) => None,
+        }
+    }
+
+    pub fn ci_level(&self) -> Level {
+        match self.level.as_str() {
+            "critical" | "high" => Level::Error,
+            "warning" | "medium" => Level::Warning,
+            _ => Level::Note,
+        }
+    }
+}
+
+/// 証拠を1行に要約
+fn format_evidence(value: &serde_json::Value) -> String {
+    let text = match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    if text.chars().count() > EVIDENCE_WIDTH {
+        let truncated: String = text.chars().take(EVIDENCE_WIDTH - 1).collect();
+        format!("{}…", truncated)
+    } else {
+        text
+    }
+}
+
+/// ホスト名がない結果にエージェント一覧から補完
+fn fill_hostnames(detections: &mut [Detection], agents: &[AgentInfo]) {
+    for detection in detections.iter_mut().filter(|d| d.hostname.is_none()) {
+        detection.hostname = detection.agent_id.as_ref().and_then(|id| {
+            agents
+                .iter()
+                .find(|a| &a.agent_id == id)
+                .map(|a| a.hostname.clone())
+        });
+    }
+}
+
+pub fn detection_case(detection: &Detection) -> ci::Case {
+    let host = detection
+        .hostname
+        .as_deref()
+        .or(detection.agent_id.as_deref())
+        .unwrap_or("server");
+    let mut message = detection.message.clone();
+    if let Some(evidence) = detection.evidence.first() {
+        message.push_str(&format!(" (証拠: {})", format_evidence(evidence)));
+    }
+
+    ci::Case {
+        name: format!(
+            "{} ({})",
+            detection.title,
+            detection.alert_id.as_deref().unwrap_or(host)
+        ),
+        classname: format!("ghost.detect.{}", host),
+        findings: vec![ci::Finding {
+            rule_id: format!("detect/{}", slug(detection.rule_label(), "alert")),
+            rule_name: detection.rule_label().to_string(),
+            level: detection.ci_level(),
+            message,
+        }],
+        error: None,
+    }
+}
+
+pub async fn cmd_detect(client: &ApiClient, output: OutputFormat) -> Result<()> {
+    // エージェント一覧はホスト名の補完に使うだけなので取得できなくても続行
+    let agents: Vec<AgentInfo> = client.get("/v1/agents").await.unwrap_or_default();
+
+    let mut detections: Vec<Detection> = client.post("/detector/check", None).await?;
+    fill_hostnames(&mut detections, &agents);
+
+    if output != OutputFormat::Text {
+        let mut cases: Vec<ci::Case> = detections.iter().map(detection_case).collect();
+        // 検知なしの場合も成功したテストケースとして記録
+        if cases.is_empty() {
+            cases.push(ci::Case {
+                name: "異常検知".to_string(),
+                classname: "ghost.detect".to_string(),
+                findings: Vec::new(),
+                error: None,
+            });
+        }
+        let report = CiReport::new("ghost detect", cases);
+        report.print(output);
+        if report.is_failed() {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    println!("\n{}", "🔍 異常検知結果".bold());
+    if detections.is_empty() {
+        println!("{}", "異常は検出されませんでした".green());
+        println!();
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        format!("{}件の異常を検出しました", detections.len()).yellow()
+    );
+    for detection in &detections {
+        let prefix = match detection.ci_level() {
+            Level::Error => "🚨",
+            Level::Warning => "⚠️",
+            Level::Note => "ℹ️",
+        };
+        println!(
+            "\n  {} {} [{}]",
+            prefix,
+            detection.title.bold(),
+            detection.level
+        );
+        match &detection.rule_id {
+            Some(id) => println!(
+                "     ルール:       {} ({})",
+                detection.rule_label(),
+                short_id(id)
+            ),
+            None => println!("     ルール:       {}", detection.rule_label()),
+        }
+        if let Some(agent) = detection.agent_label() {
+            println!("     エージェント: {}", agent);
+        }
+        if !detection.message.is_empty() {
+            println!("     内容:         {}", detection.message);
+        }
+        for (i, evidence) in detection.evidence.iter().take(MAX_EVIDENCE).enumerate() {
+            let label = if i == 0 {
+                "証拠:         "
+            } else {
+                "              "
+            };
+            println!("     {}{}", label, format_evidence(evidence).dimmed());
+        }
+        if detection.evidence.len() > MAX_EVIDENCE {
+            println!(
+                "                   他{}件",
+                detection.evidence.len() - MAX_EVIDENCE
+            );
+        }
+    }
+
+    let mut by_rule: BTreeMap<&str, usize> = BTreeMap::new();
+    for detection in &detections {
+        *by_rule.entry(detection.rule_label()).or_default() += 1;
+    }
+    if by_rule.len() > 1 {
+        println!("\n--- ルール別 ---");
+        let mut by_rule: Vec<_> = by_rule.into_iter().collect();
+        by_rule.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        for (rule, count) in by_rule {
+            println!("  {:>4}  {}", count, rule);
+        }
+    }
+    println!();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_detection() {
+        // 従来のアラート形式
+        let legacy: Detection = serde_json::from_value(json!({
+            "id": "a1",
+            "level": "critical",
+            "title": "SSH Brute Force <web-01>",
+            "message": "many failures",
+            "source": "web-01",
+            "created_at": "2024-01-01T00:00:00Z",
+            "acknowledged": false,
+        }))
+        .unwrap();
+        let case = detection_case(&legacy);
+
+        assert_eq!(case.classname, "ghost.detect.web-01");
+        assert_eq!(case.findings[0].rule_id, "detect/ssh-brute-force-web-01");
+        assert_eq!(case.findings[0].level, Level::Error);
+        assert!(CiReport::new("ghost detect", vec![case])
+            .render_junit()
+            .contains("SSH Brute Force &lt;web-01&gt; (a1)"));
+
+        // ルール・エージェント・証拠を含む検知結果
+        let mut detections: Vec<Detection> = serde_json::from_value(json!([{
+            "severity": "medium",
+            "title": "Port scan from 203.0.113.9",
+            "rule_id": "r2b3c4d5-0002",
+            "rule_name": "Port scan",
+            "agent_id": "ag2",
+            "evidence": [{ "dst_port": 22 }, "raw log line"],
+        }]))
+        .unwrap();
+        let agents: Vec<AgentInfo> = serde_json::from_value(json!([{
+            "agent_id": "ag2",
+            "hostname": "web-02",
+            "ip_address": null,
+            "status": "online",
+            "last_seen": "2024-01-01T00:00:00Z",
+            "version": null,
+        }]))
+        .unwrap();
+        fill_hostnames(&mut detections, &agents);
+
+        let detection = &detections[0];
+        assert_eq!(detection.agent_label().as_deref(), Some("web-02 (ag2)"));
+        let case = detection_case(detection);
+        assert_eq!(case.name, "Port scan from 203.0.113.9 (web-02)");
+        assert_eq!(case.findings[0].rule_id, "detect/port-scan");
+        assert_eq!(case.findings[0].level, Level::Warning);
+        assert!(case.findings[0]
+            .message
+            .ends_with("(証拠: {\"dst_port\":22})"));
+    }
+}
@@ -0,0 +1,422 @@
+//! 自己診断モジュール
+//!
+//! 設定ファイルからサーバー接続・TLS・時刻同期・認証までを順に確認し、
+//! 失敗した項目には `messages.json` の対処方法を表示。
+//! クライアント登録はサーバーの状態を変更するため `--register` 指定時のみ確認する
+
+use std::time::{Duration as StdDuration, Instant};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use colored::*;
+use sha2::{Digest, Sha256};
+use tabled::{Table, Tabled};
+
+use crate::auth::{BuildInfo, ClientAuthenticator};
+use crate::config;
+use crate::messages;
+use crate::probe::{self, format_millis, Endpoint};
+use crate::status;
+use crate::ApiClient;
+
+/// 時刻ずれの警告しきい値（秒）
+const CLOCK_SKEW_WARN_SECS: i64 = 5;
+/// 時刻ずれの失敗しきい値（秒）。認証リクエストのタイムスタンプ検証に影響する
+const CLOCK_SKEW_FAIL_SECS: i64 = 60;
+/// 各リクエストのタイムアウト
+const REQUEST_TIMEOUT: StdDuration = StdDuration::from_secs(10);
+
+/// 診断結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Pass,
+    Warn,
+    Fail,
+    Skip,
+}
+
+impl Outcome {
+    fn label(self) -> ColoredString {
+        match self {
+            Self::Pass => "✓ OK".green(),
+            Self::Warn => "! 注意".yellow(),
+            Self::Fail => "✗ 失敗".red(),
+            Self::Skip => "- スキップ".dimmed(),
+        }
+    }
+}
+
+/// 1項目の診断
+#[derive(Debug, Clone)]
+pub struct Diagnosis {
+    pub name: &'static str,
+    pub outcome: Outcome,
+    pub detail: String,
+    pub fix: Option<String>,
+}
+
+impl Diagnosis {
+    fn new(name: &'static str, outcome: Outcome, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            outcome,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    /// メッセージコードの対処方法を付与
+    fn fix_from(mut self, code: &str) -> Self {
+        self.fix = messages::lookup(code)
+            .and_then(|m| Some(format!("[{}] {}: {}", code, m.title, m.action.as_ref()?)));
+        self
+    }
+
+    fn fix(mut self, fix: impl Into<String>) -> Self {
+        self.fix = Some(fix.into());
+        self
+    }
+
+    fn skip(name: &'static str) -> Self {
+        Self::new(name, Outcome::Skip, "前の項目が失敗したため未実施")
+    }
+}
+
+/// サーバー時刻とのずれを評価
+pub fn evaluate_clock_skew(skew_secs: i64) -> Outcome {
+    match skew_secs.abs() {
+        s if s > CLOCK_SKEW_FAIL_SECS => Outcome::Fail,
+        s if s > CLOCK_SKEW_WARN_SECS => Outcome::Warn,
+        _ => Outcome::Pass,
+    }
+}
+
+fn check_config(config_error: Option<&anyhow::Error>) -> Diagnosis {
+    const NAME: &str = "設定ファイル";
+    let path = config::config_path();
+    let display = path
+        .as_ref()
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|| "-".to_string());
+
+    match (config_error, path) {
+        (Some(e), _) => {
+            Diagnosis::new(NAME, Outcome::Fail, format!("{:#}", e)).fix_from("E21A0002")
+        }
+        (None, Some(path)) if path.exists() => Diagnosis::new(NAME, Outcome::Pass, display),
+        (None, _) => Diagnosis::new(
+            NAME,
+            Outcome::Pass,
+            format!("{} (未作成、既定値を使用)", display),
+        ),
+    }
+}
+
+/// ビルド署名の有無（登録時にサーバーが検証する署名対象のダイジェストも表示）
+fn check_build_signature() -> Diagnosis {
+    const NAME: &str = "ビルド署名";
+    let info = BuildInfo::from_embedded();
+    let digest = hex::encode(Sha256::digest(info.signing_data()));
+    if info.signature.is_empty() {
+        Diagnosis::new(
+            NAME,
+            Outcome::Warn,
+            format!("未署名のビルド (署名対象 {}...)", &digest[..16]),
+        )
+        .fix("公式ビルドを使用してください（クライアント登録時の信頼スコアに影響します）")
+    } else {
+        Diagnosis::new(
+            NAME,
+            Outcome::Pass,
+            format!("署名あり (署名対象 {}...)", &digest[..16]),
+        )
+    }
+}
+
+fn check_clock(response: &reqwest::Response) -> Diagnosis {
+    const NAME: &str = "時刻同期";
+    let server_time = response
+        .headers()
+        .get(reqwest::header::DATE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| DateTime::parse_from_rfc2822(v).ok())
+        .map(|dt| dt.with_timezone(&Utc));
+
+    let Some(server_time) = server_time else {
+        return Diagnosis::new(NAME, Outcome::Skip, "サーバーがDateヘッダーを返しません");
+    };
+
+    let skew = (Utc::now() - server_time).num_seconds();
+    let outcome = evaluate_clock_skew(skew);
+    let diagnosis = Diagnosis::new(NAME, outcome, format!("サーバーとの差 {:+}秒", skew));
+    if outcome == Outcome::Pass {
+        diagnosis
+    } else {
+        diagnosis
+            .fix("NTP等でシステム時刻を同期してください（認証のタイムスタンプ検証に影響します）")
+    }
+}
+
+/// すべての診断を順に実行（`register` 指定時のみクライアント登録を試行）
+pub async fn diagnose(
+    client: &ApiClient,
+    config_error: Option<&anyhow::Error>,
+    register: bool,
+) -> Vec<Diagnosis> {
+    let mut results = vec![check_config(config_error), check_build_signature()];
+
+    let endpoint = match Endpoint::from_url(&client.base_url) {
+        Ok(endpoint) => {
+            results.push(Diagnosis::new(
+                "サーバーURL",
+                Outcome::Pass,
+                &client.base_url,
+            ));
+            endpoint
+        }
+        Err(e) => {
+            results.push(
+                Diagnosis::new("サーバーURL", Outcome::Fail, format!("{:#}", e))
+                    .fix_from("E21B0002"),
+            );
+            return results;
+        }
+    };
+
+    // DNS解決とTCP接続のみ先に確認
+    let plain = Endpoint {
+        tls: false,
+        ..endpoint.clone()
+    };
+    match probe::probe(&plain).await {
+        Ok(t) => results.push(Diagnosis::new(
+            "サーバー到達性",
+            Outcome::Pass,
+            format!(
+                "{} (DNS {}, TCP {})",
+                t.addr,
+                format_millis(t.dns),
+                format_millis(t.tcp)
+            ),
+        )),
+        Err(e) => {
+            results.push(
+                Diagnosis::new("サーバー到達性", Outcome::Fail, format!("{:#}", e))
+                    .fix_from("E20A0002"),
+            );
+            for name in [
+                "TLS証明書",
+                "API応答",
+                "時刻同期",
+                "認証情報",
+                "クライアント登録",
+            ] {
+                results.push(Diagnosis::skip(name));
+            }
+            return results;
+        }
+    }
+
+    if endpoint.tls {
+        results.push(match probe::probe(&endpoint).await {
+            Ok(t) => {
+                let tls = t.tls.expect("TLS接続の結果");
+                Diagnosis::new(
+                    "TLS証明書",
+                    Outcome::Pass,
+                    format!("検証成功 ({}, {})", tls.version, tls.cipher_suite),
+                )
+            }
+            Err(e) => {
+                Diagnosis::new("TLS証明書", Outcome::Fail, format!("{:#}", e)).fix_from("E20A0002")
+            }
+        });
+    } else {
+        results.push(
+            Diagnosis::new("TLS証明書", Outcome::Warn, "暗号化されていない接続 (http)")
+                .fix("https:// のURLを使用してください"),
+        );
+    }
+
+    let started = Instant::now();
+    let request = client
+        .client
+        .get(format!("{}/api/health", client.base_url))
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await;
+    match request {
+        Ok(response) if response.status().is_success() => {
+            results.push(Diagnosis::new(
+                "API応答",
+                Outcome::Pass,
+                format!(
+                    "{} ({})",
+                    response.status(),
+                    format_millis(started.elapsed())
+                ),
+            ));
+            results.push(check_clock(&response));
+        }
+        Ok(response) => {
+            results.push(
+                Diagnosis::new("API応答", Outcome::Fail, response.status().to_string())
+                    .fix_from("E20A0003"),
+            );
+            results.push(check_clock(&response));
+        }
+        Err(e) => {
+            let code = if e.is_timeout() {
+                "E20A0004"
+            } else {
+                "E20A0003"
+            };
+            results.push(Diagnosis::new("API応答", Outcome::Fail, e.to_string()).fix_from(code));
+            results.push(Diagnosis::skip("時刻同期"));
+        }
+    }
+
+    results.push(match status::whoami(client).await {
+        Ok(Some(user)) => Diagnosis::new(
+            "認証情報",
+            Outcome::Pass,
+            format!("{} としてログイン", user.username),
+        ),
+        Ok(None) => Diagnosis::new(
+            "認証情報",
+            Outcome::Warn,
+            "未認証 (サーバーが認証を要求しています)",
+        ),
+        Err(e) => {
+            Diagnosis::new("認証情報", Outcome::Fail, format!("{:#}", e)).fix_from("E20A0003")
+        }
+    });
+
+    if !register {
+        results.push(Diagnosis::new(
+            "クライアント登録",
+            Outcome::Skip,
+            "サーバーに登録情報を送信するため --register 指定時のみ確認",
+        ));
+        return results;
+    }
+
+    let authenticator = ClientAuthenticator::new(&format!("{}/api", client.base_url));
+    results.push(match authenticator.authenticate().await {
+        Ok(auth) if auth.success => {
+            let mut detail = format!(
+                "client_id {} (信頼スコア {})",
+                auth.client_id.as_deref().unwrap_or("-"),
+                auth.trust_score
+                    .map(|s| s.to_string())
+                    .unwrap_or_else(|| "-".to_string())
+            );
+            if !auth.warnings.is_empty() {
+                detail.push_str(&format!(" / {}", auth.warnings.join(", ")));
+            }
+            let outcome = if auth.warnings.is_empty() {
+                Outcome::Pass
+            } else {
+                Outcome::Warn
+            };
+            Diagnosis::new("クライアント登録", outcome, detail)
+        }
+        Ok(auth) => Diagnosis::new(
+            "クライアント登録",
+            Outcome::Fail,
+            auth.error
+                .unwrap_or_else(|| "認証が拒否されました".to_string()),
+        )
+        .fix_from("E21A0003"),
+        Err(e) => Diagnosis::new("クライアント登録", Outcome::Fail, format!("{:#}", e))
+            .fix_from("E20A0003"),
+    });
+
+    results
+}
+
+#[derive(Tabled)]
+struct DiagnosisRow {
+    #[tabled(rename = "項目")]
+    name: String,
+    #[tabled(rename = "結果")]
+    outcome: String,
+    #[tabled(rename = "詳細")]
+    detail: String,
+    #[tabled(rename = "対処")]
+    fix: String,
+}
+
+pub async fn cmd_doctor(
+    client: &ApiClient,
+    config_error: Option<anyhow::Error>,
+    verbose: bool,
+    register: bool,
+) -> Result<()> {
+    println!("\n{}", "🩺 Ghost CLI 自己診断".bold());
+    println!("{}", "=".repeat(40));
+
+    if verbose {
+        ClientAuthenticator::print_build_info();
+        println!();
+    }
+
+    let results = diagnose(client, config_error.as_ref(), register).await;
+    let failed = results
+        .iter()
+        .filter(|d| d.outcome == Outcome::Fail)
+        .count();
+    let warned = results
+        .iter()
+        .filter(|d| d.outcome == Outcome::Warn)
+        .count();
+
+    let rows: Vec<DiagnosisRow> = results
+        .into_iter()
+        .map(|d| DiagnosisRow {
+            name: d.name.to_string(),
+            outcome: d.outcome.label().to_string(),
+            detail: d.detail,
+            fix: d.fix.unwrap_or_else(|| "-".to_string()),
+        })
+        .collect();
+    println!("{}", Table::new(rows));
+
+    if failed > 0 {
+        println!(
+            "{}",
+            format!("✗ {}件の問題が見つかりました（注意 {}件）", failed, warned).red()
+        );
+        println!();
+        std::process::exit(1);
+    }
+
+    if warned > 0 {
+        println!("{}", format!("! 注意 {}件", warned).yellow());
+    } else {
+        println!("{}", "✓ すべての項目に問題はありません".green());
+    }
+    println!();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate_clock_skew() {
+        assert_eq!(evaluate_clock_skew(0), Outcome::Pass);
+        assert_eq!(evaluate_clock_skew(-10), Outcome::Warn);
+        assert_eq!(evaluate_clock_skew(120), Outcome::Fail);
+    }
+
+    #[test]
+    fn test_fix_from_messages() {
+        let diagnosis = Diagnosis::new("x", Outcome::Fail, "").fix_from("E20A0002");
+        assert!(diagnosis.fix.is_some());
+        let diagnosis = Diagnosis::new("x", Outcome::Fail, "").fix_from("UNKNOWN");
+        assert!(diagnosis.fix.is_none());
+    }
+}
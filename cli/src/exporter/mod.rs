@@ -0,0 +1,443 @@
+//! Prometheus/OpenMetrics エクスポーター
+//!
+//! スクレイプ要求ごとにGhost APIを取得し、OpenMetrics形式のテキストに変換して返す
+
+use std::fmt::Write as _;
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use colored::*;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::alerts::parse_timestamp;
+use crate::{AgentInfo, AlertCount, ApiClient, MetricsSummary};
+
+const CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// リクエストヘッダーの受信タイムアウト
+const READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// エージェントの状態（ラベル値）
+const AGENT_STATUSES: [&str; 3] = ["online", "warning", "offline"];
+
+/// 1回のスクレイプで取得したデータ
+pub struct Scrape {
+    pub summary: Option<MetricsSummary>,
+    pub alerts: Option<AlertCount>,
+    pub agents: Option<Vec<AgentInfo>>,
+    pub duration_secs: f64,
+}
+
+impl Scrape {
+    /// エンドポイントごとの取得結果
+    fn endpoints(&self) -> [(&'static str, bool); 3] {
+        [
+            ("metrics_summary", self.summary.is_some()),
+            ("alerts_count", self.alerts.is_some()),
+            ("agents", self.agents.is_some()),
+        ]
+    }
+
+    /// Ghost APIから各エンドポイントを並行取得
+    pub async fn collect(client: &ApiClient) -> Self {
+        let started = Instant::now();
+        let (summary, alerts, agents) = tokio::join!(
+            client.get::<MetricsSummary>("/metrics/summary"),
+            client.get::<AlertCount>("/alerts/count"),
+            client.get::<Vec<AgentInfo>>("/v1/agents"),
+        );
+
+        Self {
+            summary: summary.ok(),
+            alerts: alerts.ok(),
+            agents: agents.ok(),
+            duration_secs: started.elapsed().as_secs_f64(),
+        }
+    }
+}
+
+/// `:9660` 形式のリッスンアドレスを補完
+pub fn normalize_listen(listen: &str) -> String {
+    if listen.starts_with(':') {
+        format!("0.0.0.0{}", listen)
+    } else {
+        listen.to_string()
+    }
+}
+
+/// ラベル値をエスケープ
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// メトリクスファミリーのヘッダーを出力
+fn family(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+}
+
+/// スクレイプ結果をOpenMetrics形式に変換
+pub fn render(scrape: &Scrape) -> String {
+    let mut out = String::new();
+
+    family(
+        &mut out,
+        "ghost_up",
+        "gauge",
+        "Whether every Ghost API endpoint responded to the last scrape.",
+    );
+    let up = scrape.endpoints().iter().all(|(_, ok)| *ok);
+    let _ = writeln!(out, "ghost_up {}", u8::from(up));
+
+    family(
+        &mut out,
+        "ghost_scrape_success",
+        "gauge",
+        "Whether each Ghost API endpoint was scraped successfully.",
+    );
+    for (endpoint, ok) in scrape.endpoints() {
+        let _ = writeln!(
+            out,
+            "ghost_scrape_success{{endpoint=\"{}\"}} {}",
+            endpoint,
+            u8::from(ok)
+        );
+    }
+
+    family(
+        &mut out,
+        "ghost_scrape_duration_seconds",
+        "gauge",
+        "Time spent querying the Ghost API.",
+    );
+    let _ = writeln!(
+        out,
+        "ghost_scrape_duration_seconds {:.6}",
+        scrape.duration_secs
+    );
+
+    if let Some(summary) = &scrape.summary {
+        render_summary(&mut out, summary);
+    }
+    if let Some(alerts) = &scrape.alerts {
+        render_alerts(&mut out, alerts);
+    }
+    if let Some(agents) = &scrape.agents {
+        render_agents(&mut out, agents);
+    }
+
+    out.push_str("# EOF\n");
+    out
+}
+
+fn render_summary(out: &mut String, summary: &MetricsSummary) {
+    for (name, help, value) in [
+        (
+            "ghost_attacks",
+            "Total attacks detected.",
+            summary.total_attacks,
+        ),
+        (
+            "ghost_defenses",
+            "Total successful defenses.",
+            summary.total_defenses,
+        ),
+        (
+            "ghost_anomalies",
+            "Total anomalies detected.",
+            summary.total_anomalies,
+        ),
+    ] {
+        family(out, name, "counter", help);
+        let _ = writeln!(out, "{}_total {}", name, value);
+    }
+
+    family(
+        out,
+        "ghost_defense_rate_percent",
+        "gauge",
+        "Defense success rate in percent.",
+    );
+    let _ = writeln!(out, "ghost_defense_rate_percent {}", summary.defense_rate);
+
+    family(
+        out,
+        "ghost_current_events",
+        "gauge",
+        "Events in the current window by kind.",
+    );
+    for (kind, value) in [
+        ("attack", summary.current_attack_count),
+        ("defense", summary.current_defense_count),
+        ("anomaly", summary.current_anomaly_count),
+    ] {
+        let _ = writeln!(out, "ghost_current_events{{kind=\"{}\"}} {}", kind, value);
+    }
+
+    for (name, help, types) in [
+        (
+            "ghost_attack_type",
+            "Attacks detected by type.",
+            &summary.attack_types,
+        ),
+        (
+            "ghost_defense_type",
+            "Defenses by type.",
+            &summary.defense_types,
+        ),
+        (
+            "ghost_anomaly_type",
+            "Anomalies detected by type.",
+            &summary.anomaly_types,
+        ),
+    ] {
+        if types.is_empty() {
+            continue;
+        }
+        family(out, name, "counter", help);
+        for (kind, count) in types {
+            let _ = writeln!(
+                out,
+                "{}_total{{type=\"{}\"}} {}",
+                name,
+                escape_label(kind),
+                count
+            );
+        }
+    }
+}
+
+fn render_alerts(out: &mut String, alerts: &AlertCount) {
+    family(out, "ghost_alerts", "gauge", "Current alerts by level.");
+    for (level, count) in [
+        ("info", alerts.info),
+        ("warning", alerts.warning),
+        ("critical", alerts.critical),
+    ] {
+        let _ = writeln!(out, "ghost_alerts{{level=\"{}\"}} {}", level, count);
+    }
+
+    family(
+        out,
+        "ghost_alerts_unacknowledged",
+        "gauge",
+        "Alerts not yet acknowledged.",
+    );
+    let _ = writeln!(out, "ghost_alerts_unacknowledged {}", alerts.unacknowledged);
+
+    family(out, "ghost_alerts_all", "gauge", "Total number of alerts.");
+    let _ = writeln!(out, "ghost_alerts_all {}", alerts.total);
+}
+
+fn render_agents(out: &mut String, agents: &[AgentInfo]) {
+    family(out, "ghost_agents", "gauge", "Number of agents by status.");
+    for status in AGENT_STATUSES {
+        let count = agents.iter().filter(|a| a.status == status).count();
+        let _ = writeln!(out, "ghost_agents{{status=\"{}\"}} {}", status, count);
+    }
+
+    if agents.is_empty() {
+        return;
+    }
+
+    family(
+        out,
+        "ghost_agent_up",
+        "gauge",
+        "Whether the agent is online.",
+    );
+    for agent in agents {
+        let _ = writeln!(
+            out,
+            "ghost_agent_up{{agent_id=\"{}\",hostname=\"{}\"}} {}",
+            escape_label(&agent.agent_id),
+            escape_label(&agent.hostname),
+            u8::from(agent.status == "online")
+        );
+    }
+
+    family(out, "ghost_agent", "info", "Agent metadata.");
+    for agent in agents {
+        let _ = writeln!(
+            out,
+            "ghost_agent_info{{agent_id=\"{}\",hostname=\"{}\",ip_address=\"{}\",version=\"{}\",status=\"{}\"}} 1",
+            escape_label(&agent.agent_id),
+            escape_label(&agent.hostname),
+            escape_label(agent.ip_address.as_deref().unwrap_or("")),
+            escape_label(agent.version.as_deref().unwrap_or("")),
+            escape_label(&agent.status)
+        );
+    }
+
+    family(
+        out,
+        "ghost_agent_last_seen_timestamp_seconds",
+        "gauge",
+        "Unix time the agent was last seen.",
+    );
+    for agent in agents {
+        if let Some(last_seen) = parse_timestamp(&agent.last_seen) {
+            let _ = writeln!(
+                out,
+                "ghost_agent_last_seen_timestamp_seconds{{agent_id=\"{}\"}} {}",
+                escape_label(&agent.agent_id),
+                last_seen.timestamp()
+            );
+        }
+    }
+}
+
+// ==================== HTTPサーバー ====================
+
+pub async fn cmd_exporter(client: &ApiClient, listen: &str) -> Result<()> {
+    let addr = normalize_listen(listen);
+    let listener = TcpListener::bind(&addr)
+        .await
+        .with_context(|| format!("{} でのリッスンに失敗", addr))?;
+
+    println!("\n{}", "📡 OpenMetrics エクスポーター".bold());
+    println!("{}", "=".repeat(40));
+    println!("リッスン: http://{}/metrics", addr);
+    println!("Ghost API: {}", client.base_url);
+    println!();
+
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let client = client.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, &client).await {
+                eprintln!("{} {}: {:#}", "⚠️".yellow(), peer, e);
+            }
+        });
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, client: &ApiClient) -> Result<()> {
+    let mut buf = vec![0u8; 8192];
+    let mut len = 0;
+
+    // リクエストヘッダーの終端まで読み込む（応答のないクライアントは切断）
+    let read = async {
+        loop {
+            let n = stream.read(&mut buf[len..]).await?;
+            if n == 0 {
+                break;
+            }
+            len += n;
+            if buf[..len].windows(4).any(|w| w == b"\r\n\r\n") || len == buf.len() {
+                break;
+            }
+        }
+        Ok::<_, std::io::Error>(())
+    };
+    tokio::time::timeout(READ_TIMEOUT, read)
+        .await
+        .context("リクエストの受信がタイムアウトしました")??;
+
+    let request = String::from_utf8_lossy(&buf[..len]);
+    let mut parts = request.lines().next().unwrap_or("").split_whitespace();
+    let method = parts.next().unwrap_or("");
+    let path = parts.next().unwrap_or("");
+
+    let (status, content_type, body) = match (method, path) {
+        ("GET", "/metrics") => {
+            let scrape = Scrape::collect(client).await;
+            ("200 OK", CONTENT_TYPE, render(&scrape))
+        }
+        ("GET", "/") => (
+            "200 OK",
+            "text/plain; charset=utf-8",
+            "Ghost exporter - metrics at /metrics\n".to_string(),
+        ),
+        ("GET", _) => (
+            "404 Not Found",
+            "text/plain; charset=utf-8",
+            "Not Found\n".to_string(),
+        ),
+        _ => (
+            "405 Method Not Allowed",
+            "text/plain; charset=utf-8",
+            "Method Not Allowed\n".to_string(),
+        ),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let scrape = Scrape {
+            summary: Some(MetricsSummary {
+                total_attacks: 10,
+                total_defenses: 9,
+                total_anomalies: 2,
+                defense_rate: 90.0,
+                current_attack_count: 1,
+                current_defense_count: 1,
+                current_anomaly_count: 0,
+                attack_types: [("sql\"injection".to_string(), 4)].into_iter().collect(),
+                defense_types: Default::default(),
+                anomaly_types: Default::default(),
+            }),
+            alerts: None,
+            agents: Some(vec![AgentInfo {
+                agent_id: "a1".to_string(),
+                hostname: "web-01".to_string(),
+                ip_address: None,
+                status: "offline".to_string(),
+                last_seen: "2026-10-01T00:00:00Z".to_string(),
+                version: Some("0.1.0".to_string()),
+            }]),
+            duration_secs: 0.5,
+        };
+
+        let text = render(&scrape);
+
+        // alerts_count の取得に失敗しているため up は 0
+        assert!(text.contains("ghost_up 0\n"));
+        assert!(text.contains("ghost_attacks_total 10\n"));
+        assert!(text.contains("ghost_current_events{kind=\"attack\"} 1\n"));
+        assert!(text.contains("ghost_attack_type_total{type=\"sql\\\"injection\"} 4\n"));
+        assert!(text.contains("ghost_scrape_success{endpoint=\"alerts_count\"} 0\n"));
+        assert!(text.contains("ghost_agents{status=\"offline\"} 1\n"));
+        assert!(text.contains("ghost_agent_up{agent_id=\"a1\",hostname=\"web-01\"} 0\n"));
+        assert!(!text.contains("ghost_alerts{"));
+        assert!(text.ends_with("# EOF\n"));
+
+        let scrape = Scrape {
+            alerts: Some(AlertCount {
+                total: 0,
+                unacknowledged: 0,
+                info: 0,
+                warning: 0,
+                critical: 0,
+            }),
+            ..scrape
+        };
+        assert!(render(&scrape).contains("ghost_up 1\n"));
+    }
+
+    #[test]
+    fn test_normalize_listen() {
+        assert_eq!(normalize_listen(":9660"), "0.0.0.0:9660");
+        assert_eq!(normalize_listen("127.0.0.1:9660"), "127.0.0.1:9660");
+    }
+}
@@ -1,20 +1,46 @@
 //! Ghost CLI - セキュリティ監視ツールのコマンドラインインターフェース
 
+mod agents;
+mod alerts;
+mod attack;
+#[allow(clippy::needless_borrows_for_generic_args)]
+mod auth;
+mod check;
+mod ci;
+mod config;
+mod crypto;
+mod detect;
+mod doctor;
+mod exporter;
+mod messages;
+mod metrics;
+mod probe;
+mod report;
+mod rules;
+mod services;
+mod status;
+mod util;
+
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
 use colored::*;
 use serde::de::DeserializeOwned;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 use tabled::{Table, Tabled};
 
+/// 既定のAPIサーバーURL
+const DEFAULT_SERVER: &str = "http://localhost:3000";
+
 /// Ghost Security Monitor CLI
 #[derive(Parser)]
 #[command(name = "ghost")]
 #[command(author, version, about = "セキュリティ監視ツールのCLI", long_about = None)]
 struct Cli {
-    /// APIサーバーのURL
-    #[arg(short, long, default_value = "http://localhost:3000")]
-    server: String,
+    /// APIサーバーのURL（未指定時は設定ファイル、既定値 http://localhost:3000）
+    #[arg(short, long)]
+    server: Option<String>,
 
     #[command(subcommand)]
     command: Commands,
@@ -26,10 +52,20 @@ enum Commands {
     Status,
 
     /// メトリクスを表示
+    #[command(args_conflicts_with_subcommands = true)]
     Metrics {
         /// サマリーを表示
         #[arg(short, long)]
         summary: bool,
+
+        #[command(subcommand)]
+        action: Option<MetricsAction>,
+    },
+
+    /// エージェントを管理
+    Agents {
+        #[command(subcommand)]
+        action: AgentsAction,
     },
 
     /// アラートを管理
@@ -44,14 +80,135 @@ enum Commands {
         action: CryptoAction,
     },
 
+    /// 検出ルールを管理
+    Rules {
+        #[command(subcommand)]
+        action: RulesAction,
+    },
+
+    /// 稼働サービスを管理
+    Services {
+        #[command(subcommand)]
+        action: ServicesAction,
+    },
+
     /// 異常検知を実行
-    Detect,
+    Detect {
+        /// 出力形式 (CI向けに sarif / junit)
+        #[arg(long, value_enum, default_value = "text")]
+        output: ci::OutputFormat,
+    },
 
     /// レポートを生成
-    Report,
+    Report {
+        /// レポートの種類
+        #[arg(
+            long = "type",
+            value_enum,
+            default_value = "daily",
+            conflicts_with = "from"
+        )]
+        report_type: report::ReportType,
+        /// 期間指定の開始日 (例: 2026-09-01)
+        #[arg(long)]
+        from: Option<chrono::NaiveDate>,
+        /// 期間指定の終了日 (この日を含む、既定は今日)
+        #[arg(long, requires = "from")]
+        to: Option<chrono::NaiveDate>,
+    },
 
     /// デモデータを生成
     Demo,
+
+    /// クライアント設定の自己診断
+    Doctor {
+        /// ビルド情報も表示
+        #[arg(short, long)]
+        verbose: bool,
+
+        /// クライアント登録も確認（サーバーに登録情報を送信します）
+        #[arg(long)]
+        register: bool,
+    },
+
+    /// 監視プラグイン形式でチェックを実行 (Nagios/Icinga互換)
+    Check {
+        /// チェック対象
+        #[arg(value_enum)]
+        kind: check::CheckKind,
+        /// 警告しきい値
+        #[arg(short, long)]
+        warn: Option<f64>,
+        /// 危険しきい値
+        #[arg(short, long)]
+        crit: Option<f64>,
+        /// タイムアウト (例: 10s)
+        #[arg(short, long, default_value = "10s", value_parser = util::parse_duration)]
+        timeout: chrono::Duration,
+    },
+
+    /// Prometheus/OpenMetrics エクスポーターを起動
+    Exporter {
+        /// リッスンアドレス
+        #[arg(short, long, default_value = ":9660")]
+        listen: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum MetricsAction {
+    /// メトリクスを自動更新で表示
+    Watch {
+        /// 更新間隔 (例: 5s, 1m)
+        #[arg(short, long, default_value = "5s", value_parser = util::parse_duration)]
+        interval: chrono::Duration,
+        /// 防御率の警告しきい値（%）
+        #[arg(short, long, default_value_t = 80.0)]
+        threshold: f64,
+    },
+}
+
+#[derive(Subcommand)]
+enum AgentsAction {
+    /// エージェント一覧を表示
+    List {
+        /// 状態で絞り込み (複数指定可)
+        #[arg(long, value_parser = agents::STATUSES)]
+        status: Vec<String>,
+        /// サーバーより古いバージョンのみ表示
+        #[arg(long)]
+        outdated: bool,
+    },
+    /// エージェントの詳細と稼働サービスを表示
+    Show {
+        /// エージェントID（前方一致可）またはホスト名
+        agent: String,
+    },
+    /// 一定時間応答のないエージェントを表示 (該当があれば終了コード1)
+    Stale {
+        /// 無応答とみなす経過時間 (例: 15m, 1h)
+        #[arg(long, default_value = "15m", value_parser = util::parse_duration)]
+        threshold: chrono::Duration,
+        /// 継続的に監視し、オフライン化と復旧を通知
+        #[arg(short, long)]
+        watch: bool,
+        /// 監視モードの確認間隔 (例: 30s, 1m)
+        #[arg(short, long, default_value = "1m", value_parser = util::parse_duration)]
+        interval: chrono::Duration,
+    },
+}
+
+#[derive(Subcommand)]
+enum ServicesAction {
+    /// 全インターフェースで待ち受けるサービスを監査 (許可外があれば終了コード1)
+    Audit {
+        /// 許可リスト (TOML)
+        #[arg(long)]
+        allow: Option<PathBuf>,
+        /// 出力形式 (CI向けに sarif / junit)
+        #[arg(long, value_enum, default_value = "text")]
+        output: ci::OutputFormat,
+    },
 }
 
 #[derive(Subcommand)]
@@ -61,24 +218,182 @@ enum AlertsAction {
         /// 未確認のみ表示
         #[arg(short, long)]
         unacknowledged: bool,
+        /// ATT&CK テクニックで絞り込み (例: T1110、サブテクニックも含む)
+        #[arg(long, value_parser = attack::technique_arg)]
+        technique: Option<String>,
     },
     /// アラート統計を表示
     Count,
+    /// アラートの推移を表示
+    Stats {
+        /// 集計期間 (例: 24h, 7d)
+        #[arg(long, default_value = "7d", value_parser = util::parse_duration)]
+        since: chrono::Duration,
+        /// 集計単位 (例: 1h, 1d)
+        #[arg(long, default_value = "1d", value_parser = util::parse_duration)]
+        bucket: chrono::Duration,
+        /// 上位表示件数
+        #[arg(long, default_value_t = 5)]
+        top: usize,
+    },
     /// すべてのアラートを確認済みにする
     AckAll,
 }
 
+#[derive(Subcommand)]
+enum RulesAction {
+    /// ルール一覧を表示
+    List {
+        /// 重要度で絞り込み
+        #[arg(long, value_parser = rules::SEVERITIES)]
+        severity: Option<String>,
+        /// カテゴリで絞り込み
+        #[arg(long)]
+        category: Option<String>,
+        /// 有効なルールのみ表示
+        #[arg(long, conflicts_with = "disabled")]
+        enabled: bool,
+        /// 無効なルールのみ表示
+        #[arg(long)]
+        disabled: bool,
+    },
+    /// ルールの詳細を表示
+    Show {
+        /// ルールID（前方一致可）または名前
+        rule: String,
+    },
+    /// ルールを有効化
+    Enable {
+        /// ルールID（前方一致可）または名前
+        rule: String,
+    },
+    /// ルールを無効化
+    Disable {
+        /// ルールID（前方一致可）または名前
+        rule: String,
+    },
+    /// ルール統計を表示
+    Stats,
+    /// すべてのルールを1ルール1ファイルのYAMLに書き出す
+    Export {
+        /// 出力先ディレクトリ
+        #[arg(short, long, default_value = "./rules")]
+        dir: PathBuf,
+    },
+    /// YAMLディレクトリとサーバーの差分を表示
+    Diff {
+        /// ルール定義のディレクトリ
+        dir: PathBuf,
+        /// ディレクトリに存在しないルールを無効化対象に含める
+        #[arg(long)]
+        prune: bool,
+    },
+    /// YAMLディレクトリの内容をサーバーに適用
+    Apply {
+        /// ルール定義のディレクトリ
+        dir: PathBuf,
+        /// ディレクトリに存在しないルールを無効化
+        #[arg(long)]
+        prune: bool,
+    },
+    /// ルールの条件式を記録済みイベントに対してローカルで評価
+    Test {
+        /// ルール定義ファイル (YAML)
+        rule: PathBuf,
+        /// イベントファイル (NDJSON、`-` で標準入力)
+        #[arg(short, long)]
+        events: String,
+        /// 一致したイベントのみ表示
+        #[arg(long)]
+        matched_only: bool,
+    },
+    /// Sigmaルールを変換して取り込む
+    ImportSigma {
+        /// Sigmaルールのファイルまたはディレクトリ
+        path: PathBuf,
+        /// 変換結果と変更内容を表示するだけで適用しない
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// 有効なルールのATT&CKカバレッジを表示
+    Coverage {
+        /// 出力形式
+        #[arg(long, value_enum, default_value = "text")]
+        output: attack::CoverageFormat,
+        /// 出力先ファイル（省略時は標準出力）
+        #[arg(long)]
+        out_file: Option<PathBuf>,
+    },
+    /// ルール定義を検査 (エラーがあれば終了コード1)
+    Lint {
+        /// YAMLディレクトリ（省略時はサーバーのルール）
+        dir: Option<PathBuf>,
+        /// 許可するカテゴリ（指定時のみカテゴリを検査して警告、複数指定可）
+        #[arg(long = "allow-category")]
+        allow_categories: Vec<String>,
+        /// 警告も失敗として扱う
+        #[arg(long)]
+        strict: bool,
+        /// 出力形式 (CI向けに sarif / junit)
+        #[arg(long, value_enum, default_value = "text")]
+        output: ci::OutputFormat,
+    },
+}
+
 #[derive(Subcommand)]
 enum CryptoAction {
     /// ターゲットを監査
     Audit {
-        /// 監査対象のホスト名
-        target: String,
+        /// 監査対象のホスト名 (host / host:port)
+        #[arg(required_unless_present = "file", conflicts_with = "file")]
+        target: Option<String>,
+        /// ターゲット一覧ファイル（1行1件、CIDR可、`-` で標準入力）
+        #[arg(short, long)]
+        file: Option<String>,
+        /// 一括監査の同時実行数
+        #[arg(long, default_value_t = 10)]
+        concurrency: usize,
+        /// サーバーを経由せずCLIから直接TLS接続して監査
+        #[arg(long)]
+        local: bool,
+        /// 暗号ポリシー (TOML)。違反があれば終了コード1
+        #[arg(long)]
+        policy: Option<PathBuf>,
+        /// 出力形式 (CI向けに sarif / junit)
+        #[arg(long, value_enum, default_value = "text")]
+        output: ci::OutputFormat,
     },
     /// 監査結果を表示
     Results,
+    /// ローカル履歴から監査結果の変化を表示
+    Diff {
+        /// 比較する期間 (例: 7d)
+        #[arg(long, default_value = "7d", value_parser = util::parse_duration)]
+        since: chrono::Duration,
+    },
+    /// 監査済みターゲットの証明書を一覧表示
+    Certs {
+        /// 証明書を取得するターゲット（省略時はサーバーの監査結果）
+        targets: Vec<String>,
+        /// ターゲット一覧ファイル（1行1件、CIDR可、`-` で標準入力）
+        #[arg(short, long, conflicts_with = "targets")]
+        file: Option<String>,
+        /// 指定期間内に期限切れとなる証明書があれば終了コード1 (例: 30d)
+        #[arg(long, value_parser = util::parse_duration)]
+        expiring_within: Option<chrono::Duration>,
+        /// 証明書取得の同時実行数
+        #[arg(long, default_value_t = 10)]
+        concurrency: usize,
+    },
     /// 監査レポートを表示
-    Report,
+    Report {
+        /// 出力形式
+        #[arg(long, value_enum, default_value = "text")]
+        output: crypto::report::ReportFormat,
+        /// 出力先ファイル（省略時は標準出力）
+        #[arg(long)]
+        out_file: Option<PathBuf>,
+    },
 }
 
 // ==================== APIレスポンス ====================
@@ -94,6 +409,55 @@ struct ApiResponse<T> {
 struct HealthResponse {
     status: String,
     version: String,
+    #[serde(default)]
+    uptime_secs: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct UserInfo {
+    username: String,
+    #[serde(default)]
+    roles: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct StorageSettings {
+    primary_db_info: DatabaseInfo,
+    timeseries: Option<TimeseriesSettings>,
+    cache: Option<CacheSettings>,
+}
+
+#[derive(Deserialize)]
+struct DatabaseInfo {
+    db_type: String,
+    host: String,
+    port: u16,
+    database: String,
+    connected: bool,
+}
+
+#[derive(Deserialize)]
+struct TimeseriesSettings {
+    enabled: bool,
+    db_type: String,
+    host: String,
+    port: u16,
+}
+
+#[derive(Deserialize)]
+struct CacheSettings {
+    enabled: bool,
+    cache_type: String,
+    host: String,
+    port: u16,
+}
+
+#[derive(Deserialize)]
+struct SecurityMetrics {
+    total_events: u64,
+    attacks_blocked: u64,
+    anomalies_detected: u64,
+    active_alerts: u64,
 }
 
 #[derive(Deserialize)]
@@ -102,6 +466,15 @@ struct MetricsSummary {
     total_defenses: u64,
     total_anomalies: u64,
     defense_rate: f64,
+    current_attack_count: u64,
+    current_defense_count: u64,
+    current_anomaly_count: u64,
+    #[serde(default)]
+    attack_types: BTreeMap<String, u64>,
+    #[serde(default)]
+    defense_types: BTreeMap<String, u64>,
+    #[serde(default)]
+    anomaly_types: BTreeMap<String, u64>,
 }
 
 #[derive(Deserialize)]
@@ -110,8 +483,26 @@ struct Alert {
     level: String,
     title: String,
     message: String,
+    #[serde(default)]
+    source: Option<String>,
     created_at: String,
     acknowledged: bool,
+    /// 確認日時（サーバーが返さない場合はなし）
+    #[serde(default)]
+    acknowledged_at: Option<String>,
+    /// ATT&CK タクティク（サーバーが返さない場合はなし）
+    #[serde(default)]
+    tactics: Option<Vec<String>>,
+    /// ATT&CK テクニックID（サーバーが返さない場合はなし）
+    #[serde(default)]
+    techniques: Option<Vec<String>>,
+}
+
+impl Alert {
+    /// サーバーがATT&CKタグの項目を返したか
+    fn has_attack_tags(&self) -> bool {
+        self.tactics.is_some() || self.techniques.is_some()
+    }
 }
 
 #[derive(Deserialize)]
@@ -123,13 +514,86 @@ struct AlertCount {
     critical: usize,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DetectionRule {
+    id: String,
+    name: String,
+    #[serde(default)]
+    description: String,
+    severity: String,
+    category: String,
+    enabled: bool,
+    #[serde(default)]
+    conditions: String,
+    created_at: String,
+    updated_at: String,
+    #[serde(default)]
+    triggered_count: u64,
+    /// ATT&CK タクティク（サーバーが返さない場合はなし）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tactics: Option<Vec<String>>,
+    /// ATT&CK テクニックID（サーバーが返さない場合はなし）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    techniques: Option<Vec<String>>,
+}
+
+impl DetectionRule {
+    /// サーバーがATT&CKタグの項目を返したか
+    fn has_attack_tags(&self) -> bool {
+        self.tactics.is_some() || self.techniques.is_some()
+    }
+}
+
 #[derive(Deserialize)]
+struct DetectionRuleStats {
+    total: u64,
+    enabled: u64,
+    critical: u64,
+    #[serde(default)]
+    by_category: BTreeMap<String, u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AgentInfo {
+    agent_id: String,
+    hostname: String,
+    ip_address: Option<String>,
+    status: String,
+    last_seen: String,
+    version: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ServiceInfo {
+    name: String,
+    port: u16,
+    status: String,
+    protocol: String,
+    #[serde(default)]
+    bind_address: Option<String>,
+    last_checked: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ServiceWithAgent {
+    agent_id: String,
+    hostname: String,
+    service: ServiceInfo,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct CryptoAuditResult {
     target: String,
     tls_version: String,
     cipher_suite: String,
     is_secure: bool,
     security_score: u8,
+    #[serde(default)]
+    certificates: Vec<crypto::CertificateInfo>,
+    #[serde(default)]
+    chain_error: Option<String>,
+    #[serde(default)]
+    hsts: Option<crypto::Hsts>,
 }
 
 // ==================== テーブル表示用 ====================
@@ -144,6 +608,8 @@ struct AlertRow {
     message: String,
     #[tabled(rename = "確認")]
     acknowledged: String,
+    #[tabled(rename = "ATT&CK")]
+    techniques: String,
 }
 
 #[derive(Tabled)]
@@ -162,6 +628,7 @@ struct CryptoRow {
 
 // ==================== API クライアント ====================
 
+#[derive(Clone)]
 struct ApiClient {
     base_url: String,
     client: reqwest::Client,
@@ -170,27 +637,53 @@ struct ApiClient {
 impl ApiClient {
     fn new(base_url: &str) -> Self {
         Self {
-            base_url: base_url.to_string(),
+            base_url: base_url.trim_end_matches('/').to_string(),
             client: reqwest::Client::new(),
         }
     }
 
+    async fn parse<T: DeserializeOwned>(response: reqwest::Response) -> Result<T> {
+        let status = response.status();
+        if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+            anyhow::bail!("認証に失敗しました ({})", status);
+        }
+
+        let response: ApiResponse<T> = response.json().await.context("レスポンスのパース失敗")?;
+
+        if response.success {
+            response.data.context("データがありません")
+        } else {
+            anyhow::bail!(response.error.unwrap_or_else(|| "不明なエラー".to_string()))
+        }
+    }
+
     async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
         let url = format!("{}/api{}", self.base_url, path);
-        let response: ApiResponse<T> = self
+        let response = self
             .client
             .get(&url)
             .send()
             .await
-            .context("APIリクエスト失敗")?
-            .json()
+            .context("APIリクエスト失敗")?;
+
+        Self::parse(response).await
+    }
+
+    /// サーバーが未対応のエンドポイント（404/405/501）の場合はなしを返す
+    async fn get_if_supported<T: DeserializeOwned>(&self, path: &str) -> Result<Option<T>> {
+        let url = format!("{}/api{}", self.base_url, path);
+        let response = self
+            .client
+            .get(&url)
+            .send()
             .await
-            .context("レスポンスのパース失敗")?;
+            .context("APIリクエスト失敗")?;
 
-        if response.success {
-            response.data.context("データがありません")
-        } else {
-            anyhow::bail!(response.error.unwrap_or_else(|| "不明なエラー".to_string()))
+        match response.status() {
+            reqwest::StatusCode::NOT_FOUND
+            | reqwest::StatusCode::METHOD_NOT_ALLOWED
+            | reqwest::StatusCode::NOT_IMPLEMENTED => Ok(None),
+            _ => Self::parse(response).await.map(Some),
         }
     }
 
@@ -204,36 +697,28 @@ impl ApiClient {
                 .body(body.to_string());
         }
 
-        let response: ApiResponse<T> = request
+        let response = request.send().await.context("APIリクエスト失敗")?;
+
+        Self::parse(response).await
+    }
+
+    async fn put<T: DeserializeOwned>(&self, path: &str, body: &str) -> Result<T> {
+        let url = format!("{}/api{}", self.base_url, path);
+        let response = self
+            .client
+            .put(&url)
+            .header("Content-Type", "application/json")
+            .body(body.to_string())
             .send()
             .await
-            .context("APIリクエスト失敗")?
-            .json()
-            .await
-            .context("レスポンスのパース失敗")?;
+            .context("APIリクエスト失敗")?;
 
-        if response.success {
-            response.data.context("データがありません")
-        } else {
-            anyhow::bail!(response.error.unwrap_or_else(|| "不明なエラー".to_string()))
-        }
+        Self::parse(response).await
     }
 }
 
 // ==================== コマンド実行 ====================
 
-async fn cmd_status(client: &ApiClient) -> Result<()> {
-    let health: HealthResponse = client.get("/health").await?;
-
-    println!("\n{}", "🛡️ Ghost Security Monitor".bold());
-    println!("{}", "=".repeat(40));
-    println!("ステータス: {}", health.status.green());
-    println!("バージョン: {}", health.version);
-    println!();
-
-    Ok(())
-}
-
 async fn cmd_metrics(client: &ApiClient, summary: bool) -> Result<()> {
     if summary {
         let data: MetricsSummary = client.get("/metrics/summary").await?;
@@ -244,24 +729,66 @@ async fn cmd_metrics(client: &ApiClient, summary: bool) -> Result<()> {
         println!("防御成功:   {}", data.total_defenses.to_string().green());
         println!("異常検知:   {}", data.total_anomalies.to_string().yellow());
         println!("防御率:     {}%", format!("{:.1}", data.defense_rate).cyan());
+        println!(
+            "現在:       攻撃 {} / 防御 {} / 異常 {}",
+            data.current_attack_count.to_string().red(),
+            data.current_defense_count.to_string().green(),
+            data.current_anomaly_count.to_string().yellow()
+        );
         println!();
     } else {
-        let data: serde_json::Value = client.get("/metrics").await?;
+        let current: SecurityMetrics = client.get("/metrics").await?;
+        let data: MetricsSummary = client.get("/metrics/summary").await?;
+
         println!("\n{}", "📊 現在のメトリクス".bold());
-        println!("{}", serde_json::to_string_pretty(&data)?);
+        println!("{}", "=".repeat(40));
+        println!("総イベント:       {}", current.total_events);
+        println!(
+            "ブロックした攻撃: {}",
+            current.attacks_blocked.to_string().green()
+        );
+        println!(
+            "検知した異常:     {}",
+            current.anomalies_detected.to_string().yellow()
+        );
+        println!(
+            "アクティブアラート: {}",
+            current.active_alerts.to_string().red()
+        );
+
+        metrics::print_breakdowns(&data);
+        println!();
     }
 
     Ok(())
 }
 
-async fn cmd_alerts_list(client: &ApiClient, unacknowledged_only: bool) -> Result<()> {
+async fn cmd_alerts_list(
+    client: &ApiClient,
+    unacknowledged_only: bool,
+    technique: Option<&str>,
+) -> Result<()> {
     let path = if unacknowledged_only {
         "/alerts?unacknowledged_only=true"
     } else {
         "/alerts"
     };
 
-    let alerts: Vec<Alert> = client.get(path).await?;
+    let mut alerts: Vec<Alert> = client.get(path).await?;
+    if let Some(technique) = technique {
+        if !alerts.is_empty() && !alerts.iter().any(Alert::has_attack_tags) {
+            eprintln!(
+                "{} サーバーがアラートのATT&CKタグを返していないため、テクニックで絞り込めません",
+                "⚠".yellow()
+            );
+        }
+        alerts.retain(|a| {
+            a.techniques
+                .iter()
+                .flatten()
+                .any(|t| attack::technique_matches(technique, t))
+        });
+    }
 
     println!("\n{}", "🚨 アラート一覧".bold());
 
@@ -287,6 +814,15 @@ async fn cmd_alerts_list(client: &ApiClient, unacknowledged_only: bool) -> Resul
                     a.message
                 },
                 acknowledged: if a.acknowledged { "✓" } else { "-" }.to_string(),
+                // テクニックがなければタクティクを表示
+                techniques: match (
+                    a.techniques.unwrap_or_default(),
+                    a.tactics.unwrap_or_default(),
+                ) {
+                    (techniques, _) if !techniques.is_empty() => techniques.join(", "),
+                    (_, tactics) if !tactics.is_empty() => tactics.join(", "),
+                    _ => "-".to_string(),
+                },
             }
         })
         .collect();
@@ -329,140 +865,6 @@ async fn cmd_alerts_ack_all(client: &ApiClient) -> Result<()> {
     Ok(())
 }
 
-async fn cmd_crypto_audit(client: &ApiClient, target: &str) -> Result<()> {
-    let body = format!(r#"{{"target": "{}"}}"#, target);
-    let result: CryptoAuditResult = client.post("/crypto/audit", Some(&body)).await?;
-
-    println!("\n{}", "🔐 暗号監査結果".bold());
-    println!("{}", "=".repeat(40));
-    println!("ターゲット: {}", result.target);
-    println!("TLSバージョン: {}", result.tls_version);
-    println!("暗号方式: {}", result.cipher_suite);
-    println!(
-        "セキュリティスコア: {}",
-        if result.security_score >= 80 {
-            result.security_score.to_string().green()
-        } else if result.security_score >= 50 {
-            result.security_score.to_string().yellow()
-        } else {
-            result.security_score.to_string().red()
-        }
-    );
-    println!(
-        "状態: {}",
-        if result.is_secure {
-            "安全".green()
-        } else {
-            "要改善".red()
-        }
-    );
-    println!();
-
-    Ok(())
-}
-
-async fn cmd_crypto_results(client: &ApiClient) -> Result<()> {
-    let results: Vec<CryptoAuditResult> = client.get("/crypto/results").await?;
-
-    println!("\n{}", "🔐 暗号監査結果一覧".bold());
-
-    if results.is_empty() {
-        println!("監査結果がありません");
-        return Ok(());
-    }
-
-    let rows: Vec<CryptoRow> = results
-        .into_iter()
-        .map(|r| CryptoRow {
-            target: r.target,
-            tls_version: r.tls_version,
-            cipher_suite: if r.cipher_suite.len() > 20 {
-                format!("{}...", &r.cipher_suite[..20])
-            } else {
-                r.cipher_suite
-            },
-            security_score: format!("{}点", r.security_score),
-            status: if r.is_secure {
-                "安全".to_string()
-            } else {
-                "要改善".to_string()
-            },
-        })
-        .collect();
-
-    let table = Table::new(rows).to_string();
-    println!("{}", table);
-
-    Ok(())
-}
-
-async fn cmd_detect(client: &ApiClient) -> Result<()> {
-    let alerts: Vec<Alert> = client.post("/detector/check", None).await?;
-
-    println!("\n{}", "🔍 異常検知結果".bold());
-
-    if alerts.is_empty() {
-        println!("{}", "異常は検出されませんでした".green());
-    } else {
-        println!(
-            "{}",
-            format!("{}件の異常を検出しました", alerts.len()).yellow()
-        );
-        for alert in alerts {
-            let prefix = match alert.level.as_str() {
-                "critical" => "🚨".to_string(),
-                "warning" => "⚠️".to_string(),
-                _ => "ℹ️".to_string(),
-            };
-            println!("  {} {}: {}", prefix, alert.title, alert.message);
-        }
-    }
-    println!();
-
-    Ok(())
-}
-
-async fn cmd_report(client: &ApiClient) -> Result<()> {
-    let report: serde_json::Value = client.get("/report/daily").await?;
-
-    println!("\n{}", "📄 日次セキュリティレポート".bold());
-    println!("{}", "=".repeat(50));
-
-    if let Some(title) = report.get("title").and_then(|v| v.as_str()) {
-        println!("{}", title);
-    }
-
-    if let Some(summary) = report.get("metrics_summary") {
-        println!("\n--- メトリクス ---");
-        if let Some(attacks) = summary.get("total_attacks").and_then(|v| v.as_u64()) {
-            println!("総攻撃数: {}", attacks);
-        }
-        if let Some(defenses) = summary.get("total_defenses").and_then(|v| v.as_u64()) {
-            println!("総防御数: {}", defenses);
-        }
-        if let Some(rate) = summary.get("defense_rate").and_then(|v| v.as_f64()) {
-            println!("防御率: {:.1}%", rate);
-        }
-    }
-
-    if let Some(recommendations) = report.get("recommendations").and_then(|v| v.as_array()) {
-        if !recommendations.is_empty() {
-            println!("\n--- 推奨事項 ---");
-            for rec in recommendations.iter().take(5) {
-                if let (Some(category), Some(title)) = (
-                    rec.get("category").and_then(|v| v.as_str()),
-                    rec.get("title").and_then(|v| v.as_str()),
-                ) {
-                    println!("  • [{}] {}", category, title);
-                }
-            }
-        }
-    }
-
-    println!();
-    Ok(())
-}
-
 async fn cmd_demo(client: &ApiClient) -> Result<()> {
     let result: serde_json::Value = client.post("/demo/generate", None).await?;
 
@@ -490,28 +892,199 @@ async fn cmd_demo(client: &ApiClient) -> Result<()> {
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let cli = Cli::parse();
-    let client = ApiClient::new(&cli.server);
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        Err(e) => {
+            // 監視プラグインとして実行される check では引数の誤りを CRITICAL (2) ではなく UNKNOWN (3) とする
+            let args: Vec<String> = std::env::args().collect();
+            if e.use_stderr() && check::is_check_invocation(&args) {
+                let _ = e.print();
+                check::exit_unknown("invalid arguments");
+            }
+            e.exit()
+        }
+    };
+    // doctor は設定ファイルが壊れていても診断を続ける
+    let (config, config_error) = match (config::Config::load(), &cli.command) {
+        (Ok(config), _) => (config, None),
+        (Err(e), Commands::Doctor { .. }) => (config::Config::default(), Some(e)),
+        (Err(e), Commands::Check { .. }) => check::exit_unknown(&format!("{:#}", e)),
+        (Err(e), _) => return Err(e),
+    };
+    let server = cli
+        .server
+        .or(config.server.url)
+        .unwrap_or_else(|| DEFAULT_SERVER.to_string());
+    let client = ApiClient::new(&server);
 
     match cli.command {
-        Commands::Status => cmd_status(&client).await,
-        Commands::Metrics { summary } => cmd_metrics(&client, summary).await,
+        Commands::Status => status::cmd_status(&client).await,
+        Commands::Metrics { summary, action } => match action {
+            Some(MetricsAction::Watch {
+                interval,
+                threshold,
+            }) => metrics::cmd_metrics_watch(&client, interval, threshold).await,
+            None => cmd_metrics(&client, summary).await,
+        },
+        Commands::Agents { action } => match action {
+            AgentsAction::List { status, outdated } => {
+                let filter = agents::Filter {
+                    statuses: status,
+                    outdated,
+                };
+                agents::cmd_agents_list(&client, filter).await
+            }
+            AgentsAction::Show { agent } => agents::cmd_agents_show(&client, &agent).await,
+            AgentsAction::Stale {
+                threshold,
+                watch: true,
+                interval,
+            } => agents::stale::cmd_agents_stale_watch(&client, threshold, interval).await,
+            AgentsAction::Stale { threshold, .. } => {
+                agents::stale::cmd_agents_stale(&client, threshold).await
+            }
+        },
         Commands::Alerts { action } => match action {
-            AlertsAction::List { unacknowledged } => cmd_alerts_list(&client, unacknowledged).await,
+            AlertsAction::List {
+                unacknowledged,
+                technique,
+            } => cmd_alerts_list(&client, unacknowledged, technique.as_deref()).await,
             AlertsAction::Count => cmd_alerts_count(&client).await,
+            AlertsAction::Stats { since, bucket, top } => {
+                alerts::cmd_alerts_stats(&client, since, bucket, top).await
+            }
             AlertsAction::AckAll => cmd_alerts_ack_all(&client).await,
         },
         Commands::Crypto { action } => match action {
-            CryptoAction::Audit { target } => cmd_crypto_audit(&client, &target).await,
-            CryptoAction::Results => cmd_crypto_results(&client).await,
-            CryptoAction::Report => {
-                let report: serde_json::Value = client.get("/crypto/report").await?;
-                println!("{}", serde_json::to_string_pretty(&report)?);
-                Ok(())
+            CryptoAction::Audit {
+                target,
+                file,
+                concurrency,
+                local,
+                policy,
+                output,
+            } => {
+                let policy = policy
+                    .as_deref()
+                    .map(crypto::policy::Policy::load)
+                    .transpose()?;
+                let options = crypto::AuditOptions {
+                    local,
+                    policy: policy.as_ref(),
+                    output,
+                };
+                match (target, file) {
+                    (_, Some(file)) => {
+                        crypto::cmd_crypto_audit_bulk(&client, &file, concurrency, options).await
+                    }
+                    (Some(target), None) => {
+                        crypto::cmd_crypto_audit(&client, &target, options).await
+                    }
+                    (None, None) => unreachable!("clap requires target or --file"),
+                }
+            }
+            CryptoAction::Results => crypto::cmd_crypto_results(&client).await,
+            CryptoAction::Diff { since } => crypto::history::cmd_crypto_diff(since),
+            CryptoAction::Certs {
+                targets,
+                file,
+                expiring_within,
+                concurrency,
+            } => {
+                crypto::certs::cmd_crypto_certs(
+                    &client,
+                    targets,
+                    file.as_deref(),
+                    expiring_within,
+                    concurrency,
+                )
+                .await
+            }
+            CryptoAction::Report { output, out_file } => {
+                crypto::report::cmd_crypto_report(&client, output, out_file.as_deref()).await
             }
         },
-        Commands::Detect => cmd_detect(&client).await,
-        Commands::Report => cmd_report(&client).await,
+        Commands::Rules { action } => match action {
+            RulesAction::List {
+                severity,
+                category,
+                enabled,
+                disabled,
+            } => {
+                let filter = rules::Filter {
+                    severity,
+                    category,
+                    enabled: match (enabled, disabled) {
+                        (true, _) => Some(true),
+                        (_, true) => Some(false),
+                        _ => None,
+                    },
+                };
+                rules::cmd_rules_list(&client, filter).await
+            }
+            RulesAction::Show { rule } => rules::cmd_rules_show(&client, &rule).await,
+            RulesAction::Enable { rule } => {
+                rules::cmd_rules_set_enabled(&client, &rule, true).await
+            }
+            RulesAction::Disable { rule } => {
+                rules::cmd_rules_set_enabled(&client, &rule, false).await
+            }
+            RulesAction::Stats => rules::cmd_rules_stats(&client).await,
+            RulesAction::Export { dir } => rules::sync::cmd_rules_export(&client, &dir).await,
+            RulesAction::Diff { dir, prune } => {
+                rules::sync::cmd_rules_diff(&client, &dir, prune).await
+            }
+            RulesAction::Apply { dir, prune } => {
+                rules::sync::cmd_rules_apply(&client, &dir, prune).await
+            }
+            RulesAction::Test {
+                rule,
+                events,
+                matched_only,
+            } => rules::tester::cmd_rules_test(&rule, &events, matched_only),
+            RulesAction::ImportSigma { path, dry_run } => {
+                rules::sigma::cmd_rules_import_sigma(&client, &path, dry_run).await
+            }
+            RulesAction::Coverage { output, out_file } => {
+                attack::cmd_rules_coverage(&client, output, out_file.as_deref()).await
+            }
+            RulesAction::Lint {
+                dir,
+                allow_categories,
+                strict,
+                output,
+            } => {
+                rules::lint::cmd_rules_lint(
+                    &client,
+                    dir.as_deref(),
+                    &allow_categories,
+                    strict,
+                    output,
+                )
+                .await
+            }
+        },
+        Commands::Services { action } => match action {
+            ServicesAction::Audit { allow, output } => {
+                services::cmd_services_audit(&client, allow.as_deref(), output).await
+            }
+        },
+        Commands::Detect { output } => detect::cmd_detect(&client, output).await,
+        Commands::Report {
+            report_type,
+            from,
+            to,
+        } => report::cmd_report(&client, report_type, from, to).await,
         Commands::Demo => cmd_demo(&client).await,
+        Commands::Doctor { verbose, register } => {
+            doctor::cmd_doctor(&client, config_error, verbose, register).await
+        }
+        Commands::Check {
+            kind,
+            warn,
+            crit,
+            timeout,
+        } => check::cmd_check(&client, kind, warn, crit, timeout).await,
+        Commands::Exporter { listen } => exporter::cmd_exporter(&client, &listen).await,
     }
 }
@@ -0,0 +1,67 @@
+//! メッセージコードモジュール
+//!
+//! `messages.json` のメッセージコードから表示言語に応じたタイトル・対処方法を取得
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use serde::Deserialize;
+
+/// Webクライアントと共通のメッセージ定義
+const MESSAGES_JSON: &str = include_str!("../../../messages.json");
+
+/// 既定の表示言語
+const DEFAULT_LANGUAGE: &str = "ja";
+
+/// 1言語分のメッセージ
+#[derive(Debug, Clone, Deserialize)]
+pub struct Message {
+    pub title: String,
+    pub action: Option<String>,
+}
+
+type Catalog = HashMap<String, HashMap<String, Message>>;
+
+fn catalog() -> &'static Catalog {
+    static CATALOG: OnceLock<Catalog> = OnceLock::new();
+    CATALOG.get_or_init(|| {
+        let raw: HashMap<String, serde_json::Value> =
+            serde_json::from_str(MESSAGES_JSON).unwrap_or_default();
+        raw.into_iter()
+            .filter(|(code, _)| !code.starts_with('_'))
+            .filter_map(|(code, value)| Some((code, serde_json::from_value(value).ok()?)))
+            .collect()
+    })
+}
+
+/// 表示言語（`GHOST_LANG` → `LANG` の順に参照）
+pub fn language() -> String {
+    std::env::var("GHOST_LANG")
+        .or_else(|_| std::env::var("LANG"))
+        .ok()
+        .and_then(|lang| lang.get(..2).map(str::to_lowercase))
+        .filter(|lang| lang != "c" && lang != "po")
+        .unwrap_or_else(|| DEFAULT_LANGUAGE.to_string())
+}
+
+/// メッセージコードから表示言語のメッセージを取得（未対応言語は日本語）
+pub fn lookup(code: &str) -> Option<&'static Message> {
+    let entry = catalog().get(code)?;
+    entry
+        .get(&language())
+        .or_else(|| entry.get(DEFAULT_LANGUAGE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog() {
+        let entry = catalog().get("E20A0002").unwrap();
+        assert_eq!(entry["en"].title, "API Connection Failed");
+        assert!(entry["ja"].action.is_some());
+        assert!(catalog().get("I20A0004").unwrap()["ja"].action.is_none());
+        assert!(!catalog().contains_key("_meta"));
+    }
+}
@@ -0,0 +1,284 @@
+//! メトリクス表示モジュール
+//!
+//! 攻撃・防御・異常の種別ごとの内訳表示と、自動更新によるライブ表示
+
+use std::collections::{BTreeMap, VecDeque};
+
+use anyhow::Result;
+use chrono::{Duration, Local};
+use colored::*;
+use tabled::{Table, Tabled};
+
+use crate::util::{bar, format_duration, sparkline};
+use crate::{ApiClient, MetricsSummary};
+
+/// スパークラインに保持する更新回数
+const HISTORY_LEN: usize = 30;
+
+/// 種別ごとの件数と割合
+#[derive(Debug, Clone, PartialEq)]
+pub struct BreakdownEntry {
+    pub name: String,
+    pub count: u64,
+    pub percent: f64,
+}
+
+/// 種別マップを件数の多い順に並べ、割合を計算
+pub fn breakdown(types: &BTreeMap<String, u64>) -> Vec<BreakdownEntry> {
+    let total: u64 = types.values().sum();
+    let mut entries: Vec<BreakdownEntry> = types
+        .iter()
+        .map(|(name, &count)| BreakdownEntry {
+            name: name.clone(),
+            count,
+            percent: if total == 0 {
+                0.0
+            } else {
+                count as f64 * 100.0 / total as f64
+            },
+        })
+        .collect();
+    entries.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+    entries
+}
+
+#[derive(Tabled)]
+struct BreakdownRow {
+    #[tabled(rename = "順位")]
+    rank: usize,
+    #[tabled(rename = "種別")]
+    name: String,
+    #[tabled(rename = "件数")]
+    count: u64,
+    #[tabled(rename = "割合")]
+    percent: String,
+    #[tabled(rename = "グラフ")]
+    graph: String,
+}
+
+/// 攻撃・防御・異常の内訳テーブルを表示
+pub fn print_breakdowns(summary: &MetricsSummary) {
+    for (label, types) in [
+        ("攻撃種別", &summary.attack_types),
+        ("防御種別", &summary.defense_types),
+        ("異常種別", &summary.anomaly_types),
+    ] {
+        println!("\n--- {} ---", label);
+
+        let entries = breakdown(types);
+        if entries.is_empty() {
+            println!("{}", "データがありません".dimmed());
+            continue;
+        }
+
+        let max = entries[0].count;
+        let rows: Vec<BreakdownRow> = entries
+            .into_iter()
+            .enumerate()
+            .map(|(i, e)| BreakdownRow {
+                rank: i + 1,
+                graph: bar(e.count, max, 20),
+                name: e.name,
+                count: e.count,
+                percent: format!("{:.1}%", e.percent),
+            })
+            .collect();
+        println!("{}", Table::new(rows));
+    }
+}
+
+// ==================== ライブ表示 ====================
+
+/// 前回更新からの増分
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Deltas {
+    pub attacks: i64,
+    pub defenses: i64,
+    pub anomalies: i64,
+}
+
+/// 自動更新の状態（前回値と増分の履歴）
+#[derive(Default)]
+pub struct WatchState {
+    previous: Option<(u64, u64, u64)>,
+    pub attacks: VecDeque<u64>,
+    pub defenses: VecDeque<u64>,
+    pub anomalies: VecDeque<u64>,
+}
+
+impl WatchState {
+    /// 新しい値を取り込み、前回からの増分を返す
+    pub fn update(&mut self, summary: &MetricsSummary) -> Deltas {
+        let current = (
+            summary.total_attacks,
+            summary.total_defenses,
+            summary.total_anomalies,
+        );
+        let deltas = match self.previous {
+            Some(prev) => Deltas {
+                attacks: current.0 as i64 - prev.0 as i64,
+                defenses: current.1 as i64 - prev.1 as i64,
+                anomalies: current.2 as i64 - prev.2 as i64,
+            },
+            None => Deltas::default(),
+        };
+        self.previous = Some(current);
+
+        for (history, delta) in [
+            (&mut self.attacks, deltas.attacks),
+            (&mut self.defenses, deltas.defenses),
+            (&mut self.anomalies, deltas.anomalies),
+        ] {
+            history.push_back(delta.max(0) as u64);
+            if history.len() > HISTORY_LEN {
+                history.pop_front();
+            }
+        }
+
+        deltas
+    }
+}
+
+fn format_delta(delta: i64) -> ColoredString {
+    match delta {
+        d if d > 0 => format!("+{}", d).bold(),
+        d if d < 0 => d.to_string().normal(),
+        _ => "±0".dimmed(),
+    }
+}
+
+fn history_line(history: &VecDeque<u64>) -> String {
+    sparkline(&history.iter().copied().collect::<Vec<_>>())
+}
+
+pub async fn cmd_metrics_watch(
+    client: &ApiClient,
+    interval: Duration,
+    threshold: f64,
+) -> Result<()> {
+    let mut state = WatchState::default();
+    let mut ticker = tokio::time::interval(interval.to_std()?);
+    // 取得中の Ctrl+C も取りこぼさないようループの外で1つだけ待ち受ける
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = &mut ctrl_c => {
+                println!();
+                return Ok(());
+            }
+        }
+
+        let result: Result<MetricsSummary> = tokio::select! {
+            result = client.get("/metrics/summary") => result,
+            _ = &mut ctrl_c => {
+                println!();
+                return Ok(());
+            }
+        };
+
+        // 画面をクリアしてカーソルを先頭へ
+        print!("\x1B[2J\x1B[H");
+        println!("\n{}", "📊 メトリクス（ライブ）".bold());
+        println!("{}", "=".repeat(50));
+        println!(
+            "{} {}ごと / 最終更新 {} / Ctrl+Cで終了",
+            "🔄 自動更新有効".cyan(),
+            format_duration(interval),
+            Local::now().format("%H:%M:%S")
+        );
+        println!();
+
+        let data = match result {
+            Ok(data) => data,
+            Err(e) => {
+                println!("{} {:#}", "APIリクエスト失敗:".red(), e);
+                continue;
+            }
+        };
+        let deltas = state.update(&data);
+
+        println!(
+            "攻撃検知:   {:>8} ({:>5})  {}",
+            data.total_attacks.to_string().red(),
+            format_delta(deltas.attacks),
+            history_line(&state.attacks).red()
+        );
+        println!(
+            "防御成功:   {:>8} ({:>5})  {}",
+            data.total_defenses.to_string().green(),
+            format_delta(deltas.defenses),
+            history_line(&state.defenses).green()
+        );
+        println!(
+            "異常検知:   {:>8} ({:>5})  {}",
+            data.total_anomalies.to_string().yellow(),
+            format_delta(deltas.anomalies),
+            history_line(&state.anomalies).yellow()
+        );
+
+        let rate = format!("{:.1}%", data.defense_rate);
+        if data.defense_rate < threshold {
+            println!(
+                "防御率:     {}  {}",
+                rate.white().on_red().bold(),
+                format!("⚠️ しきい値 {:.1}% を下回っています", threshold).red()
+            );
+        } else {
+            println!("防御率:     {}", rate.cyan());
+        }
+        println!();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breakdown() {
+        let types: BTreeMap<String, u64> = [
+            ("port_scan".to_string(), 1),
+            ("brute_force".to_string(), 3),
+            ("sql_injection".to_string(), 0),
+        ]
+        .into_iter()
+        .collect();
+
+        let entries = breakdown(&types);
+
+        assert_eq!(entries[0].name, "brute_force");
+        assert_eq!(entries[0].percent, 75.0);
+        assert_eq!(entries[1].name, "port_scan");
+        assert_eq!(entries[2].count, 0);
+        assert!(breakdown(&BTreeMap::new()).is_empty());
+    }
+
+    #[test]
+    fn test_watch_state_deltas() {
+        let summary = |attacks, defenses, anomalies| MetricsSummary {
+            total_attacks: attacks,
+            total_defenses: defenses,
+            total_anomalies: anomalies,
+            defense_rate: 0.0,
+            current_attack_count: 0,
+            current_defense_count: 0,
+            current_anomaly_count: 0,
+            attack_types: BTreeMap::new(),
+            defense_types: BTreeMap::new(),
+            anomaly_types: BTreeMap::new(),
+        };
+        let mut state = WatchState::default();
+
+        assert_eq!(state.update(&summary(10, 5, 1)), Deltas::default());
+        let deltas = state.update(&summary(13, 5, 0));
+
+        assert_eq!(deltas.attacks, 3);
+        assert_eq!(deltas.defenses, 0);
+        assert_eq!(deltas.anomalies, -1);
+        assert_eq!(state.attacks, [0, 3]);
+        assert_eq!(state.anomalies, [0, 0]);
+    }
+}
@@ -0,0 +1,158 @@
+//! 接続プローブモジュール
+//!
+//! DNS解決・TCP接続・TLSハンドシェイクを段階ごとに実行し、所要時間とTLSの情報を取得
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use rustls::pki_types::ServerName;
+use rustls::{ClientConfig, RootCertStore};
+use tokio::net::TcpStream;
+use tokio_rustls::TlsConnector;
+
+/// 接続先
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoint {
+    pub host: String,
+    pub port: u16,
+    pub tls: bool,
+}
+
+impl Endpoint {
+    /// サーバーURLから接続先を取得
+    pub fn from_url(url: &str) -> Result<Self> {
+        let parsed = reqwest::Url::parse(url).with_context(|| format!("URLが不正です: {}", url))?;
+        let host = parsed
+            .host_str()
+            .context("URLにホスト名がありません")?
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_string();
+        let port = parsed
+            .port_or_known_default()
+            .context("ポート番号を特定できません")?;
+
+        Ok(Self {
+            host,
+            port,
+            tls: parsed.scheme() == "https",
+        })
+    }
+}
+
+/// TLSハンドシェイクの結果
+#[derive(Debug, Clone)]
+pub struct TlsInfo {
+    pub handshake: Duration,
+    pub version: String,
+    pub cipher_suite: String,
+}
+
+/// 段階ごとの所要時間
+#[derive(Debug, Clone)]
+pub struct ConnectTimings {
+    pub addr: SocketAddr,
+    pub dns: Duration,
+    pub tcp: Duration,
+    pub tls: Option<TlsInfo>,
+}
+
+/// Mozillaのルート証明書で検証するTLS設定
+pub fn tls_config() -> Result<Arc<ClientConfig>> {
+    let roots = RootCertStore {
+        roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+    };
+    let config =
+        ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .context("TLS設定の初期化に失敗")?
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+
+    Ok(Arc::new(config))
+}
+
+/// TLSバージョンを表示用の文字列に変換
+pub fn protocol_name(version: rustls::ProtocolVersion) -> String {
+    match version {
+        rustls::ProtocolVersion::TLSv1_3 => "TLSv1.3".to_string(),
+        rustls::ProtocolVersion::TLSv1_2 => "TLSv1.2".to_string(),
+        other => format!("{:?}", other),
+    }
+}
+
+/// 接続先に対してDNS→TCP→TLSの順に接続し、所要時間を計測
+pub async fn probe(endpoint: &Endpoint) -> Result<ConnectTimings> {
+    let started = Instant::now();
+    let addr = tokio::net::lookup_host((endpoint.host.as_str(), endpoint.port))
+        .await
+        .with_context(|| format!("DNS解決に失敗: {}", endpoint.host))?
+        .next()
+        .with_context(|| format!("アドレスが見つかりません: {}", endpoint.host))?;
+    let dns = started.elapsed();
+
+    let started = Instant::now();
+    let stream = TcpStream::connect(addr)
+        .await
+        .with_context(|| format!("TCP接続に失敗: {}", addr))?;
+    let tcp = started.elapsed();
+
+    let tls = if endpoint.tls {
+        let server_name = ServerName::try_from(endpoint.host.clone())
+            .with_context(|| format!("サーバー名が不正です: {}", endpoint.host))?;
+        let connector = TlsConnector::from(tls_config()?);
+
+        let started = Instant::now();
+        let stream = connector
+            .connect(server_name, stream)
+            .await
+            .context("TLSハンドシェイクに失敗")?;
+        let handshake = started.elapsed();
+
+        let (_, session) = stream.get_ref();
+        Some(TlsInfo {
+            handshake,
+            version: session
+                .protocol_version()
+                .map(protocol_name)
+                .unwrap_or_else(|| "不明".to_string()),
+            cipher_suite: session
+                .negotiated_cipher_suite()
+                .map(|s| format!("{:?}", s.suite()))
+                .unwrap_or_else(|| "不明".to_string()),
+        })
+    } else {
+        None
+    };
+
+    Ok(ConnectTimings {
+        addr,
+        dns,
+        tcp,
+        tls,
+    })
+}
+
+/// 所要時間をミリ秒表記に変換
+pub fn format_millis(duration: Duration) -> String {
+    format!("{:.1}ms", duration.as_secs_f64() * 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_from_url() {
+        let endpoint = Endpoint::from_url("https://ghost.example.com").unwrap();
+        assert_eq!(endpoint.port, 443);
+        assert!(endpoint.tls);
+
+        let endpoint = Endpoint::from_url("http://[::1]:6661").unwrap();
+        assert_eq!(endpoint.host, "::1");
+        assert_eq!(endpoint.port, 6661);
+        assert!(!endpoint.tls);
+    }
+}
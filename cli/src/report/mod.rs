@@ -0,0 +1,541 @@
+//! セキュリティレポートモジュール
+//!
+//! 日次・週次・月次・期間指定のレポートをサーバーから取得して表示する。
+//! サーバーが対象期間に対応していない場合のみ、アラートとメトリクスからローカルで集計する
+
+use anyhow::Result;
+use chrono::{DateTime, Days, Local, Months, NaiveDate, SecondsFormat, TimeZone, Utc};
+use clap::ValueEnum;
+use colored::*;
+use serde::Deserialize;
+
+use crate::alerts::parse_timestamp;
+use crate::util::format_duration;
+use crate::{Alert, ApiClient, MetricsSummary};
+
+/// 推奨事項の表示件数
+const MAX_RECOMMENDATIONS: usize = 5;
+
+/// ローカル集計で推奨事項を出す防御率（累計）のしきい値（%）
+const DEFENSE_RATE_WARNING: f64 = 80.0;
+
+/// レポートの種類（期間指定は `--from` / `--to`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportType {
+    /// 過去24時間
+    Daily,
+    /// 過去7日間
+    Weekly,
+    /// 過去1か月
+    Monthly,
+}
+
+/// レポートの対象期間
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Period {
+    /// `daily` / `weekly` / `monthly` / `custom`
+    pub kind: &'static str,
+    pub start: DateTime<Utc>,
+    /// 終了時刻（この時刻を含まない）
+    pub end: DateTime<Utc>,
+}
+
+impl Period {
+    /// 現在時刻までの直近の期間
+    pub fn recent(report_type: ReportType, now: DateTime<Utc>) -> Self {
+        let (kind, start) = match report_type {
+            ReportType::Daily => ("daily", now - Days::new(1)),
+            ReportType::Weekly => ("weekly", now - Days::new(7)),
+            ReportType::Monthly => ("monthly", now - Months::new(1)),
+        };
+        Self {
+            kind,
+            start,
+            end: now,
+        }
+    }
+
+    /// 日付指定の期間（`to` の日の終わりまで、未指定時は今日まで）
+    pub fn custom<Tz: TimeZone>(from: NaiveDate, to: NaiveDate, tz: &Tz) -> Result<Self> {
+        anyhow::ensure!(
+            from <= to,
+            "開始日 {} が終了日 {} より後になっています",
+            from,
+            to
+        );
+        let midnight = |date: NaiveDate| {
+            tz.from_local_datetime(&date.and_time(chrono::NaiveTime::MIN))
+                .earliest()
+                .map(|dt| dt.with_timezone(&Utc))
+                .ok_or_else(|| anyhow::anyhow!("日付を変換できません: {}", date))
+        };
+        Ok(Self {
+            kind: "custom",
+            start: midnight(from)?,
+            end: midnight(to + Days::new(1))?,
+        })
+    }
+
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        self.start <= at && at < self.end
+    }
+
+    fn label(&self) -> &'static str {
+        match self.kind {
+            "daily" => "日次",
+            "weekly" => "週次",
+            "monthly" => "月次",
+            _ => "期間指定",
+        }
+    }
+
+    /// サーバーのレポートAPIのパス
+    fn path(&self) -> String {
+        match self.kind {
+            "custom" => format!(
+                "/report/custom?from={}&to={}",
+                self.start.to_rfc3339_opts(SecondsFormat::Secs, true),
+                self.end.to_rfc3339_opts(SecondsFormat::Secs, true)
+            ),
+            kind => format!("/report/{}", kind),
+        }
+    }
+}
+
+/// レポートのメトリクス（サーバーにより項目が欠けることがある）
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ReportMetrics {
+    pub total_attacks: u64,
+    pub total_defenses: u64,
+    pub total_anomalies: u64,
+    pub defense_rate: f64,
+}
+
+impl From<MetricsSummary> for ReportMetrics {
+    fn from(summary: MetricsSummary) -> Self {
+        Self {
+            total_attacks: summary.total_attacks,
+            total_defenses: summary.total_defenses,
+            total_anomalies: summary.total_anomalies,
+            defense_rate: summary.defense_rate,
+        }
+    }
+}
+
+/// レポートのアラート件数
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct ReportAlerts {
+    pub total: usize,
+    pub unacknowledged: usize,
+    pub info: usize,
+    pub warning: usize,
+    pub critical: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Recommendation {
+    #[serde(default)]
+    pub priority: u32,
+    pub category: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub impact: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Report {
+    pub report_type: String,
+    #[serde(default)]
+    pub title: String,
+    pub generated_at: String,
+    pub period_start: String,
+    pub period_end: String,
+    #[serde(default)]
+    pub metrics_summary: Option<ReportMetrics>,
+    #[serde(default)]
+    pub alert_count: Option<ReportAlerts>,
+    #[serde(default)]
+    pub recommendations: Vec<Recommendation>,
+    #[serde(default)]
+    pub executive_summary: String,
+    /// CLIでの集計結果か
+    #[serde(skip)]
+    pub local: bool,
+}
+
+/// 期間内のアラートを件数に集計
+pub fn count_alerts(alerts: &[Alert], period: &Period) -> ReportAlerts {
+    let mut count = ReportAlerts::default();
+    for alert in alerts {
+        if !parse_timestamp(&alert.created_at).is_some_and(|at| period.contains(at)) {
+            continue;
+        }
+        count.total += 1;
+        match alert.level.as_str() {
+            "critical" => count.critical += 1,
+            "warning" => count.warning += 1,
+            _ => count.info += 1,
+        }
+        if !alert.acknowledged {
+            count.unacknowledged += 1;
+        }
+    }
+    count
+}
+
+/// アラートとメトリクスからレポートを組み立てる
+pub fn aggregate(
+    period: &Period,
+    alerts: &[Alert],
+    metrics: Option<ReportMetrics>,
+    now: DateTime<Utc>,
+) -> Report {
+    let count = count_alerts(alerts, period);
+
+    let mut summary = format!(
+        "期間中のアラートは{}件 (重大 {}件 / 未確認 {}件) でした。",
+        count.total, count.critical, count.unacknowledged
+    );
+    if let Some(metrics) = &metrics {
+        summary.push_str(&format!(
+            "累計の防御率は{:.1}%です (期間内の値ではありません)。",
+            metrics.defense_rate
+        ));
+    }
+
+    let mut recommendations = Vec::new();
+    if count.unacknowledged > 0 {
+        recommendations.push(Recommendation {
+            priority: 1,
+            category: "Alerts".to_string(),
+            title: format!(
+                "未確認のアラート{}件を確認してください",
+                count.unacknowledged
+            ),
+            description: String::new(),
+            impact: if count.critical > 0 {
+                "critical"
+            } else {
+                "high"
+            }
+            .to_string(),
+        });
+    }
+    if let Some(metrics) = metrics
+        .as_ref()
+        .filter(|m| m.defense_rate < DEFENSE_RATE_WARNING)
+    {
+        recommendations.push(Recommendation {
+            priority: 2,
+            category: "Defense".to_string(),
+            title: format!(
+                "累計の防御率が{:.1}%です。検出ルールと防御設定を見直してください",
+                metrics.defense_rate
+            ),
+            description: "防御率は対象期間ではなく累計の値です".to_string(),
+            impact: "high".to_string(),
+        });
+    }
+
+    Report {
+        report_type: period.kind.to_string(),
+        title: format!("{}セキュリティレポート", period.label()),
+        generated_at: now.to_rfc3339(),
+        period_start: period.start.to_rfc3339(),
+        period_end: period.end.to_rfc3339(),
+        metrics_summary: metrics,
+        alert_count: Some(count),
+        recommendations,
+        executive_summary: summary,
+        local: true,
+    }
+}
+
+/// サーバーでレポートを生成し、対応していなければローカルで集計
+///
+/// 認証エラーやサーバーエラーなどは未対応とみなさずエラーにする
+async fn fetch(client: &ApiClient, period: &Period) -> Result<Report> {
+    match client.get_if_supported::<Report>(&period.path()).await? {
+        Some(report) if report.report_type == period.kind => return Ok(report),
+        Some(report) => eprintln!(
+            "{} サーバーが{}レポートではなく {} を返しました。ローカルで集計します",
+            "⚠".yellow(),
+            period.label(),
+            report.report_type
+        ),
+        None => eprintln!(
+            "{} サーバーが{}レポートに対応していません。ローカルで集計します",
+            "⚠".yellow(),
+            period.label()
+        ),
+    }
+
+    let (alerts, metrics) = tokio::join!(
+        client.get::<Vec<Alert>>("/alerts"),
+        client.get::<MetricsSummary>("/metrics/summary"),
+    );
+    Ok(aggregate(
+        period,
+        &alerts?,
+        metrics.ok().map(ReportMetrics::from),
+        Utc::now(),
+    ))
+}
+
+/// RFC 3339 の日時をローカル時刻で表示
+fn local_time(value: &str) -> String {
+    parse_timestamp(value)
+        .map(|at| {
+            at.with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_else(|| value.to_string())
+}
+
+fn impact_colored(impact: &str) -> ColoredString {
+    match impact {
+        "critical" => impact.red().bold(),
+        "high" => impact.red(),
+        "medium" => impact.yellow(),
+        _ => impact.normal(),
+    }
+}
+
+fn print_report(report: &Report, period: &Period) {
+    println!(
+        "\n{}",
+        format!("📄 {}セキュリティレポート", period.label()).bold()
+    );
+    println!("{}", "=".repeat(50));
+
+    // ローカル集計のタイトルは見出しと同じため省略
+    if !report.local && !report.title.is_empty() {
+        println!("{}", report.title);
+    }
+    let length = match (
+        parse_timestamp(&report.period_start),
+        parse_timestamp(&report.period_end),
+    ) {
+        (Some(start), Some(end)) => format!(" ({})", format_duration(end - start)),
+        _ => String::new(),
+    };
+    println!(
+        "期間: {} 〜 {}{}",
+        local_time(&report.period_start),
+        local_time(&report.period_end),
+        length
+    );
+    println!(
+        "生成: {}{}",
+        local_time(&report.generated_at),
+        if report.local {
+            " (ローカル集計)".dimmed().to_string()
+        } else {
+            String::new()
+        }
+    );
+
+    println!("\n--- 概要 ---");
+    if report.executive_summary.is_empty() {
+        println!("{}", "データなし".dimmed());
+    } else {
+        println!("{}", report.executive_summary);
+    }
+
+    println!("\n--- メトリクス ---");
+    match &report.metrics_summary {
+        Some(metrics) => {
+            println!("総攻撃数: {}", metrics.total_attacks);
+            println!("総防御数: {}", metrics.total_defenses);
+            println!("異常検知: {}", metrics.total_anomalies);
+            println!("防御率: {:.1}%", metrics.defense_rate);
+            if report.local {
+                println!("{}", "※ メトリクスは現在の累計値です".dimmed());
+            }
+        }
+        None => println!("{}", "データなし".dimmed()),
+    }
+
+    println!("\n--- アラート ---");
+    match &report.alert_count {
+        Some(count) => {
+            println!(
+                "合計: {}件 (重大: {} / 警告: {} / 情報: {})",
+                count.total,
+                count.critical.to_string().red(),
+                count.warning.to_string().yellow(),
+                count.info
+            );
+            println!(
+                "未確認: {}",
+                if count.unacknowledged > 0 {
+                    format!("{}件", count.unacknowledged).red()
+                } else {
+                    "0件".green()
+                }
+            );
+            if report.local {
+                println!(
+                    "{}",
+                    "※ サーバーが返したアラートのみの集計です (保持期間や取得件数の上限により不足する場合があります)"
+                        .dimmed()
+                );
+            }
+        }
+        None => println!("{}", "データなし".dimmed()),
+    }
+
+    println!("\n--- 推奨事項 ---");
+    if report.recommendations.is_empty() {
+        println!("{}", "推奨事項はありません".dimmed());
+    } else {
+        let mut recommendations: Vec<&Recommendation> = report.recommendations.iter().collect();
+        recommendations.sort_by_key(|r| r.priority);
+        for rec in recommendations.iter().take(MAX_RECOMMENDATIONS) {
+            if rec.impact.is_empty() {
+                println!("  • [{}] {}", rec.category, rec.title);
+            } else {
+                println!(
+                    "  • [{}] {} ({})",
+                    rec.category,
+                    rec.title,
+                    impact_colored(&rec.impact)
+                );
+            }
+            if !rec.description.is_empty() {
+                println!("      {}", rec.description.dimmed());
+            }
+        }
+        if recommendations.len() > MAX_RECOMMENDATIONS {
+            println!("  他{}件", recommendations.len() - MAX_RECOMMENDATIONS);
+        }
+    }
+
+    println!();
+}
+
+pub async fn cmd_report(
+    client: &ApiClient,
+    report_type: ReportType,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Result<()> {
+    let period = match from {
+        Some(from) => Period::custom(
+            from,
+            to.unwrap_or_else(|| Local::now().date_naive()),
+            &Local,
+        )?,
+        None => Period::recent(report_type, Utc::now()),
+    };
+
+    let report = fetch(client, &period).await?;
+    print_report(&report, &period);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_period() {
+        let now = parse_timestamp("2026-10-18T12:00:00Z").unwrap();
+
+        let weekly = Period::recent(ReportType::Weekly, now);
+        assert_eq!(
+            weekly.start,
+            parse_timestamp("2026-10-11T12:00:00Z").unwrap()
+        );
+        assert_eq!(weekly.path(), "/report/weekly");
+        let monthly = Period::recent(ReportType::Monthly, now);
+        assert_eq!(
+            monthly.start,
+            parse_timestamp("2026-09-18T12:00:00Z").unwrap()
+        );
+
+        let date = |s: &str| s.parse::<NaiveDate>().unwrap();
+        let custom = Period::custom(date("2026-09-01"), date("2026-09-30"), &Utc).unwrap();
+        assert_eq!(
+            custom.start,
+            parse_timestamp("2026-09-01T00:00:00Z").unwrap()
+        );
+        assert_eq!(custom.end, parse_timestamp("2026-10-01T00:00:00Z").unwrap());
+        assert!(custom.contains(parse_timestamp("2026-09-30T23:59:59Z").unwrap()));
+        assert!(!custom.contains(custom.end));
+        assert_eq!(
+            custom.path(),
+            "/report/custom?from=2026-09-01T00:00:00Z&to=2026-10-01T00:00:00Z"
+        );
+
+        assert!(Period::custom(date("2026-09-30"), date("2026-09-01"), &Utc).is_err());
+    }
+
+    #[test]
+    fn test_aggregate() {
+        let alerts: Vec<Alert> = serde_json::from_value(json!([
+            { "id": "1", "level": "critical", "title": "a", "message": "",
+              "created_at": "2026-09-02T00:00:00Z", "acknowledged": false },
+            { "id": "2", "level": "warning", "title": "b", "message": "",
+              "created_at": "2026-09-10T00:00:00Z", "acknowledged": true },
+            { "id": "3", "level": "info", "title": "c", "message": "",
+              "created_at": "2026-10-02T00:00:00Z", "acknowledged": false },
+        ]))
+        .unwrap();
+        let period = Period::custom(
+            "2026-09-01".parse().unwrap(),
+            "2026-09-30".parse().unwrap(),
+            &Utc,
+        )
+        .unwrap();
+        let metrics = ReportMetrics {
+            defense_rate: 72.5,
+            ..Default::default()
+        };
+
+        let report = aggregate(&period, &alerts, Some(metrics), Utc::now());
+        assert_eq!(
+            report.alert_count,
+            Some(ReportAlerts {
+                total: 2,
+                unacknowledged: 1,
+                info: 0,
+                warning: 1,
+                critical: 1,
+            })
+        );
+        assert_eq!(report.report_type, "custom");
+        let impacts: Vec<&str> = report
+            .recommendations
+            .iter()
+            .map(|r| r.impact.as_str())
+            .collect();
+        assert_eq!(impacts, vec!["critical", "high"]);
+        assert!(report.executive_summary.contains("累計の防御率は72.5%"));
+
+        // サーバーのレポート（項目の欠けたメトリクスも受け付ける）
+        let report: Report = serde_json::from_value(json!({
+            "id": "r1",
+            "report_type": "weekly",
+            "title": "Weekly",
+            "generated_at": "2026-10-18T00:00:00Z",
+            "period_start": "2026-10-11T00:00:00Z",
+            "period_end": "2026-10-18T00:00:00Z",
+            "metrics_summary": { "total_attacks": 10, "defense_rate": 90.0 },
+            "alert_count": null,
+            "recommendations": [{ "priority": 1, "category": "Network", "title": "t",
+                "description": "d", "action_items": [], "impact": "low" }],
+            "executive_summary": "ok",
+        }))
+        .unwrap();
+        assert!(!report.local);
+        assert_eq!(report.metrics_summary.unwrap().total_attacks, 10);
+    }
+}
@@ -0,0 +1,609 @@
+//! 検出ルール条件式の評価
+//!
+//! `conditions` をローカルでパース・評価する。サポートする構文:
+//!
+//! - 比較: `field == "value"`, `!=`, `>`, `>=`, `<`, `<=`
+//! - 文字列: `field contains "x"`, `startswith`, `endswith`
+//! - 集合: `field in ["a", "b"]`
+//! - 論理: `AND` / `OR` / `NOT`（`&&` / `||` / `!` も可）と括弧
+//! - フィールド単体: 値が存在し、false/0/空文字でなければ真
+//!
+//! フィールドは `.` 区切りでネストしたJSONを参照する（例: `source.ip`）。
+//! 存在しないフィールドとの比較は `!=` を含めて常に偽になる。
+
+use std::fmt;
+
+use anyhow::Result;
+use serde_json::Value;
+
+/// 比較演算子
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Contains,
+    StartsWith,
+    EndsWith,
+    In,
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Op::Eq => "==",
+            Op::Ne => "!=",
+            Op::Gt => ">",
+            Op::Ge => ">=",
+            Op::Lt => "<",
+            Op::Le => "<=",
+            Op::Contains => "contains",
+            Op::StartsWith => "startswith",
+            Op::EndsWith => "endswith",
+            Op::In => "in",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// 条件式の構文木
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare { field: String, op: Op, value: Value },
+    Truthy(String),
+}
+
+impl Expr {
+    /// 結合の強さ（括弧の要否の判定用）
+    fn precedence(&self) -> u8 {
+        match self {
+            Expr::Or(..) => 0,
+            Expr::And(..) => 1,
+            _ => 2,
+        }
+    }
+
+    fn fmt_operand(&self, f: &mut fmt::Formatter<'_>, parent: u8) -> fmt::Result {
+        if self.precedence() < parent {
+            write!(f, "({})", self)
+        } else {
+            write!(f, "{}", self)
+        }
+    }
+}
+
+/// 再パース可能な条件式として表示（括弧は必要な箇所のみ）
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::And(a, b) | Expr::Or(a, b) => {
+                let keyword = if matches!(self, Expr::And(..)) {
+                    "AND"
+                } else {
+                    "OR"
+                };
+                a.fmt_operand(f, self.precedence())?;
+                write!(f, " {} ", keyword)?;
+                b.fmt_operand(f, self.precedence())
+            }
+            Expr::Not(e) => {
+                write!(f, "NOT ")?;
+                e.fmt_operand(f, 2)
+            }
+            Expr::Compare { field, op, value } => write!(f, "{} {} {}", field, op, value),
+            Expr::Truthy(field) => write!(f, "{}", field),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Num(f64),
+    Op(Op),
+    And,
+    Or,
+    Not,
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+}
+
+/// 字句解析（トークンと入力中の文字位置の組）
+fn tokenize(input: &str) -> Result<Vec<(Token, usize)>> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        let two: String = chars[i..chars.len().min(i + 2)].iter().collect();
+        let token = match two.as_str() {
+            "==" => Some(Token::Op(Op::Eq)),
+            "!=" => Some(Token::Op(Op::Ne)),
+            ">=" => Some(Token::Op(Op::Ge)),
+            "<=" => Some(Token::Op(Op::Le)),
+            "&&" => Some(Token::And),
+            "||" => Some(Token::Or),
+            _ => None,
+        };
+        if let Some(token) = token {
+            tokens.push((token, start));
+            i += 2;
+            continue;
+        }
+
+        let token = match c {
+            '>' => Token::Op(Op::Gt),
+            '<' => Token::Op(Op::Lt),
+            '!' => Token::Not,
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            '[' => Token::LBracket,
+            ']' => Token::RBracket,
+            ',' => Token::Comma,
+            '"' | '\'' => {
+                let quote = c;
+                let mut s = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None => anyhow::bail!("文字列が閉じられていません (位置 {})", start + 1),
+                        Some('\\') => {
+                            match chars.get(i + 1) {
+                                Some(escaped) => s.push(*escaped),
+                                None => {
+                                    anyhow::bail!("文字列が閉じられていません (位置 {})", start + 1)
+                                }
+                            }
+                            i += 2;
+                        }
+                        Some(ch) if *ch == quote => break,
+                        Some(ch) => {
+                            s.push(*ch);
+                            i += 1;
+                        }
+                    }
+                }
+                Token::Str(s)
+            }
+            c if c.is_ascii_digit()
+                || (c == '-' && chars.get(i + 1).is_some_and(|d| d.is_ascii_digit())) =>
+            {
+                let mut end = i + 1;
+                while end < chars.len() && (chars[end].is_ascii_digit() || chars[end] == '.') {
+                    end += 1;
+                }
+                let text: String = chars[i..end].iter().collect();
+                let num = text.parse().map_err(|_| {
+                    anyhow::anyhow!("数値が不正です: {} (位置 {})", text, start + 1)
+                })?;
+                i = end - 1;
+                Token::Num(num)
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut end = i + 1;
+                while end < chars.len()
+                    && (chars[end].is_alphanumeric() || matches!(chars[end], '_' | '.' | '-'))
+                {
+                    end += 1;
+                }
+                let word: String = chars[i..end].iter().collect();
+                i = end - 1;
+                match word.to_ascii_lowercase().as_str() {
+                    "and" => Token::And,
+                    "or" => Token::Or,
+                    "not" => Token::Not,
+                    "contains" => Token::Op(Op::Contains),
+                    "startswith" => Token::Op(Op::StartsWith),
+                    "endswith" => Token::Op(Op::EndsWith),
+                    "in" => Token::Op(Op::In),
+                    _ => Token::Ident(word),
+                }
+            }
+            c => anyhow::bail!("不正な文字です: '{}' (位置 {})", c, start + 1),
+        };
+        tokens.push((token, start));
+        i += 1;
+    }
+
+    Ok(tokens)
+}
+
+/// 再帰下降パーサ（優先順位: NOT > AND > OR）
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    len: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(t, _)| t)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).map(|(t, _)| t.clone());
+        self.pos += 1;
+        token
+    }
+
+    /// 現在位置（エラー表示用、1始まり）
+    fn position(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .map(|(_, p)| *p)
+            .unwrap_or(self.len)
+            + 1
+    }
+
+    fn unexpected(&self, expected: &str) -> anyhow::Error {
+        match self.tokens.get(self.pos) {
+            Some((token, p)) => anyhow::anyhow!(
+                "{}が必要ですが {:?} があります (位置 {})",
+                expected,
+                token,
+                p + 1
+            ),
+            None => anyhow::anyhow!("{}が必要ですが式が終了しました", expected),
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Expr> {
+        let mut left = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            left = Expr::Or(Box::new(left), Box::new(self.parse_and()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr> {
+        let mut left = self.parse_not()?;
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            left = Expr::And(Box::new(left), Box::new(self.parse_not()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_not(&mut self) -> Result<Expr> {
+        if self.peek() == Some(&Token::Not) {
+            self.pos += 1;
+            return Ok(Expr::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr> {
+        match self.peek() {
+            Some(Token::LParen) => {
+                self.pos += 1;
+                let expr = self.parse_or()?;
+                if self.next() != Some(Token::RParen) {
+                    self.pos -= 1;
+                    return Err(self.unexpected("')'"));
+                }
+                Ok(expr)
+            }
+            Some(Token::Ident(_)) => {
+                let Some(Token::Ident(field)) = self.next() else {
+                    unreachable!()
+                };
+                let op = match self.peek() {
+                    Some(Token::Op(op)) => *op,
+                    _ => return Ok(Expr::Truthy(field)),
+                };
+                self.pos += 1;
+                let value = if op == Op::In {
+                    self.parse_list()?
+                } else {
+                    self.parse_value()?
+                };
+                Ok(Expr::Compare { field, op, value })
+            }
+            _ => Err(self.unexpected("条件")),
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Value> {
+        let value = match self.peek() {
+            Some(Token::Str(s)) => Value::String(s.clone()),
+            Some(Token::Num(n)) if n.fract() == 0.0 && n.abs() < i64::MAX as f64 => {
+                Value::from(*n as i64)
+            }
+            Some(Token::Num(n)) => Value::from(*n),
+            Some(Token::Ident(word)) => match word.to_ascii_lowercase().as_str() {
+                "true" => Value::Bool(true),
+                "false" => Value::Bool(false),
+                "null" => Value::Null,
+                _ => return Err(self.unexpected("値")),
+            },
+            _ => return Err(self.unexpected("値")),
+        };
+        self.pos += 1;
+        Ok(value)
+    }
+
+    fn parse_list(&mut self) -> Result<Value> {
+        if self.next() != Some(Token::LBracket) {
+            self.pos -= 1;
+            return Err(self.unexpected("'['"));
+        }
+        let mut items = Vec::new();
+        if self.peek() == Some(&Token::RBracket) {
+            self.pos += 1;
+            return Ok(Value::Array(items));
+        }
+        loop {
+            items.push(self.parse_value()?);
+            match self.next() {
+                Some(Token::Comma) => continue,
+                Some(Token::RBracket) => break,
+                _ => {
+                    self.pos -= 1;
+                    return Err(self.unexpected("',' または ']'"));
+                }
+            }
+        }
+        Ok(Value::Array(items))
+    }
+}
+
+/// 条件式をパース
+pub fn parse(input: &str) -> Result<Expr> {
+    let tokens = tokenize(input)?;
+    if tokens.is_empty() {
+        anyhow::bail!("条件式が空です");
+    }
+
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        len: input.chars().count(),
+    };
+    let expr = parser.parse_or()?;
+    if parser.pos < parser.tokens.len() {
+        anyhow::bail!("余分なトークンがあります (位置 {})", parser.position());
+    }
+
+    Ok(expr)
+}
+
+/// `.` 区切りのパスでイベントのフィールドを取得
+pub fn lookup<'a>(event: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .try_fold(event, |value, key| value.get(key))
+        .filter(|v| !v.is_null())
+}
+
+fn as_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn as_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// 値の等価比較（数値は文字列表現とも比較する）
+fn equals(actual: &Value, expected: &Value) -> bool {
+    match (actual, expected) {
+        (_, Value::Number(_)) | (Value::Number(_), _) => {
+            match (as_number(actual), as_number(expected)) {
+                (Some(a), Some(b)) => a == b,
+                _ => false,
+            }
+        }
+        (Value::String(a), Value::String(b)) => a == b,
+        _ => actual == expected,
+    }
+}
+
+fn compare(actual: &Value, op: Op, expected: &Value) -> bool {
+    match op {
+        Op::Eq => equals(actual, expected),
+        Op::Ne => !equals(actual, expected),
+        Op::Gt | Op::Ge | Op::Lt | Op::Le => {
+            let (Some(a), Some(b)) = (as_number(actual), as_number(expected)) else {
+                return false;
+            };
+            match op {
+                Op::Gt => a > b,
+                Op::Ge => a >= b,
+                Op::Lt => a < b,
+                _ => a <= b,
+            }
+        }
+        Op::Contains => match actual {
+            Value::Array(items) => items.iter().any(|item| equals(item, expected)),
+            other => as_text(other).contains(&as_text(expected)),
+        },
+        Op::StartsWith => as_text(actual).starts_with(&as_text(expected)),
+        Op::EndsWith => as_text(actual).ends_with(&as_text(expected)),
+        Op::In => expected
+            .as_array()
+            .is_some_and(|items| items.iter().any(|item| equals(actual, item))),
+    }
+}
+
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().is_some_and(|n| n != 0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(_) => true,
+        Value::Null => false,
+    }
+}
+
+impl Expr {
+    /// イベント（JSONオブジェクト）に対して評価
+    pub fn eval(&self, event: &Value) -> bool {
+        match self {
+            Expr::And(a, b) => a.eval(event) && b.eval(event),
+            Expr::Or(a, b) => a.eval(event) || b.eval(event),
+            Expr::Not(e) => !e.eval(event),
+            Expr::Compare { field, op, value } => {
+                lookup(event, field).is_some_and(|actual| compare(actual, *op, value))
+            }
+            Expr::Truthy(field) => lookup(event, field).is_some_and(truthy),
+        }
+    }
+
+    /// 最上位の AND で区切った各条件
+    pub fn clauses(&self) -> Vec<&Expr> {
+        match self {
+            Expr::And(a, b) => {
+                let mut clauses = a.clauses();
+                clauses.extend(b.clauses());
+                clauses
+            }
+            other => vec![other],
+        }
+    }
+
+    /// 式中で参照しているフィールド名（重複なし、出現順）
+    pub fn fields(&self) -> Vec<&str> {
+        let mut fields = Vec::new();
+        self.collect_fields(&mut fields);
+        fields
+    }
+
+    fn collect_fields<'a>(&'a self, fields: &mut Vec<&'a str>) {
+        match self {
+            Expr::And(a, b) | Expr::Or(a, b) => {
+                a.collect_fields(fields);
+                b.collect_fields(fields);
+            }
+            Expr::Not(e) => e.collect_fields(fields),
+            Expr::Compare { field, .. } | Expr::Truthy(field) => {
+                if !fields.contains(&field.as_str()) {
+                    fields.push(field);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn matches(condition: &str, event: &Value) -> bool {
+        parse(condition).unwrap().eval(event)
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("a == 1 OR b == 2 AND NOT c").unwrap(),
+            Expr::Or(
+                Box::new(Expr::Compare {
+                    field: "a".to_string(),
+                    op: Op::Eq,
+                    value: json!(1),
+                }),
+                Box::new(Expr::And(
+                    Box::new(Expr::Compare {
+                        field: "b".to_string(),
+                        op: Op::Eq,
+                        value: json!(2),
+                    }),
+                    Box::new(Expr::Not(Box::new(Expr::Truthy("c".to_string())))),
+                )),
+            )
+        );
+        assert_eq!(
+            parse("(x in ['a', \"b\"]) && y != null").unwrap().fields(),
+            vec!["x", "y"]
+        );
+
+        assert!(parse("").is_err());
+        assert!(parse("   ").is_err());
+        assert!(parse("a ==").is_err());
+        assert!(parse("a == \"open").is_err());
+        assert!(parse("(a == 1").is_err());
+        assert!(parse("a == 1 b == 2").is_err());
+        assert!(parse("a in [1, 2").is_err());
+        assert!(parse("a == 1 AND").is_err());
+        assert!(parse("a = 1").is_err());
+
+        // 表示結果は再パースで同じ構文木になる
+        for input in [
+            "a == 1 OR b == 2 AND NOT c",
+            "(a == 1 OR b == 2) AND NOT (c OR d in ['x', 'y\\z'])",
+            "NOT NOT a contains \"q\\\"uote\"",
+        ] {
+            let expr = parse(input).unwrap();
+            assert_eq!(parse(&expr.to_string()).unwrap(), expr, "{}", expr);
+        }
+        assert_eq!(
+            parse("(a OR b) AND (c AND d)").unwrap().to_string(),
+            "(a OR b) AND c AND d"
+        );
+    }
+
+    #[test]
+    fn test_eval() {
+        let event = json!({
+            "event_type": "auth_failure",
+            "service": "ssh",
+            "count": 7,
+            "port": "22",
+            "source": { "ip": "10.0.0.5", "country": "JP" },
+            "tags": ["bruteforce", "external"],
+            "blocked": false,
+        });
+
+        assert!(matches(
+            "event_type == \"auth_failure\" AND service == \"ssh\" AND count > 5",
+            &event
+        ));
+        assert!(!matches("count > 7", &event));
+        assert!(matches("count >= 7 and count <= 7", &event));
+        assert!(matches("port == 22", &event));
+        assert!(matches(
+            "source.ip startswith '10.' && source.country in ['JP', 'US']",
+            &event
+        ));
+        assert!(matches("tags contains 'external'", &event));
+        assert!(matches("event_type contains 'fail'", &event));
+        assert!(matches("NOT blocked", &event));
+        assert!(matches(
+            "service == 'rdp' OR (count > 5 AND !blocked)",
+            &event
+        ));
+
+        // 存在しないフィールドは != でも偽
+        assert!(!matches("missing != 'x'", &event));
+        assert!(!matches("missing", &event));
+        assert!(matches("NOT missing", &event));
+        // 文字列と数値の大小比較はしない
+        assert!(!matches("service > 1", &event));
+        assert!(matches("source.ip != 10.5 AND count < 7.5", &event));
+    }
+}
@@ -0,0 +1,448 @@
+//! 検出ルールのLint
+//!
+//! サーバーまたはYAMLディレクトリのルール定義を検査し、CI向けの終了コードで結果を返す
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::Result;
+use colored::*;
+
+use super::sync::{load_spec, spec_paths, RuleSpec};
+use super::{condition, short_id, SEVERITIES};
+use crate::attack;
+use crate::ci::{self, CiReport, Level, OutputFormat};
+use crate::{ApiClient, DetectionRule};
+
+/// 検知数が多すぎると判定する最小値
+const NOISY_MIN: u64 = 100;
+
+/// 検知数の中央値に対して何倍を超えたら多すぎると判定するか
+const NOISY_FACTOR: u64 = 10;
+
+/// 検査項目
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Check {
+    InvalidFile,
+    DuplicateName,
+    EmptyCondition,
+    InvalidCondition,
+    MissingDescription,
+    InvalidSeverity,
+    UnknownCategory,
+    InvalidAttackTag,
+    NoisyRule,
+    NeverTriggered,
+}
+
+impl Check {
+    pub fn id(self) -> &'static str {
+        match self {
+            Check::InvalidFile => "rules/invalid-file",
+            Check::DuplicateName => "rules/duplicate-name",
+            Check::EmptyCondition => "rules/empty-condition",
+            Check::InvalidCondition => "rules/invalid-condition",
+            Check::MissingDescription => "rules/missing-description",
+            Check::InvalidSeverity => "rules/invalid-severity",
+            Check::UnknownCategory => "rules/unknown-category",
+            Check::InvalidAttackTag => "rules/invalid-attack-tag",
+            Check::NoisyRule => "rules/noisy",
+            Check::NeverTriggered => "rules/never-triggered",
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Check::InvalidFile => "ルールファイルを読み込めない",
+            Check::DuplicateName => "ルール名の重複",
+            Check::EmptyCondition => "条件式が空",
+            Check::InvalidCondition => "条件式の構文エラー",
+            Check::MissingDescription => "説明がない",
+            Check::InvalidSeverity => "不正な重要度",
+            Check::UnknownCategory => "未知のカテゴリ",
+            Check::InvalidAttackTag => "不正なATT&CKタグ",
+            Check::NoisyRule => "検知数が突出",
+            Check::NeverTriggered => "検知実績なし",
+        }
+    }
+
+    pub fn level(self) -> Level {
+        match self {
+            Check::InvalidFile
+            | Check::DuplicateName
+            | Check::EmptyCondition
+            | Check::InvalidCondition
+            | Check::InvalidSeverity => Level::Error,
+            Check::MissingDescription
+            | Check::UnknownCategory
+            | Check::InvalidAttackTag
+            | Check::NoisyRule => Level::Warning,
+            Check::NeverTriggered => Level::Note,
+        }
+    }
+}
+
+/// 検査対象のルール
+pub struct Subject {
+    /// 表示用の所在（ファイルパスまたはルールID）
+    pub location: String,
+    pub spec: RuleSpec,
+    /// サーバー上の検知数（YAMLの場合はなし）
+    pub triggered_count: Option<u64>,
+}
+
+impl From<&DetectionRule> for Subject {
+    fn from(rule: &DetectionRule) -> Self {
+        Self {
+            location: short_id(&rule.id).to_string(),
+            spec: RuleSpec::from(rule),
+            triggered_count: Some(rule.triggered_count),
+        }
+    }
+}
+
+/// 1件の指摘
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    pub check: Check,
+    pub message: String,
+}
+
+fn median(values: &mut [u64]) -> u64 {
+    values.sort_unstable();
+    values.get(values.len() / 2).copied().unwrap_or(0)
+}
+
+/// すべてのルールを検査（結果は対象と同じ順）
+///
+/// サーバーはカテゴリを列挙していないため、`categories` が空ならカテゴリは検査しない
+pub fn lint(subjects: &[Subject], categories: &[String]) -> Vec<Vec<Issue>> {
+    let mut names: HashMap<String, Vec<&str>> = HashMap::new();
+    for subject in subjects {
+        names
+            .entry(subject.spec.name.trim().to_lowercase())
+            .or_default()
+            .push(&subject.location);
+    }
+
+    let mut counts: Vec<u64> = subjects
+        .iter()
+        .filter(|s| s.spec.enabled)
+        .filter_map(|s| s.triggered_count)
+        .collect();
+    let noisy_threshold = (median(&mut counts) * NOISY_FACTOR).max(NOISY_MIN);
+
+    subjects
+        .iter()
+        .map(|subject| {
+            let spec = &subject.spec;
+            let mut issues = Vec::new();
+            let mut issue = |check: Check, message: String| issues.push(Issue { check, message });
+
+            if let Some(others) = names.get(&spec.name.trim().to_lowercase()) {
+                if others.len() > 1 {
+                    issue(
+                        Check::DuplicateName,
+                        format!(
+                            "ルール名「{}」が重複しています ({})",
+                            spec.name,
+                            others.join(", ")
+                        ),
+                    );
+                }
+            }
+
+            if spec.conditions.trim().is_empty() {
+                issue(Check::EmptyCondition, "条件式が空です".to_string());
+            } else if let Err(e) = condition::parse(&spec.conditions) {
+                issue(
+                    Check::InvalidCondition,
+                    format!("条件式を解釈できません: {:#}", e),
+                );
+            }
+
+            if spec.description.trim().is_empty() {
+                issue(Check::MissingDescription, "説明がありません".to_string());
+            }
+
+            if !SEVERITIES.contains(&spec.severity.as_str()) {
+                issue(
+                    Check::InvalidSeverity,
+                    format!(
+                        "重要度「{}」は {} のいずれでもありません",
+                        spec.severity,
+                        SEVERITIES.join("|")
+                    ),
+                );
+            }
+
+            if !categories.is_empty() && !categories.iter().any(|c| c == &spec.category) {
+                issue(
+                    Check::UnknownCategory,
+                    format!(
+                        "カテゴリ「{}」は {} のいずれでもありません",
+                        spec.category,
+                        categories.join("|")
+                    ),
+                );
+            }
+
+            let invalid: Vec<&str> = spec
+                .tactics
+                .iter()
+                .filter(|t| attack::find_tactic(t).is_none())
+                .chain(
+                    spec.techniques
+                        .iter()
+                        .filter(|t| attack::parse_technique(t).is_none()),
+                )
+                .map(String::as_str)
+                .collect();
+            if !invalid.is_empty() {
+                issue(
+                    Check::InvalidAttackTag,
+                    format!(
+                        "ATT&CKのタクティク/テクニックとして認識できません: {}",
+                        invalid.join(", ")
+                    ),
+                );
+            }
+
+            match subject.triggered_count {
+                Some(count) if count > noisy_threshold => issue(
+                    Check::NoisyRule,
+                    format!(
+                        "検知数 {} がしきい値 {} を超えています (条件が広すぎる可能性があります)",
+                        count, noisy_threshold
+                    ),
+                ),
+                Some(0) if spec.enabled => issue(
+                    Check::NeverTriggered,
+                    "有効ですが一度も検知していません".to_string(),
+                ),
+                _ => {}
+            }
+
+            issues
+        })
+        .collect()
+}
+
+fn level_colored(level: Level) -> ColoredString {
+    match level {
+        Level::Error => "error  ".red().bold(),
+        Level::Warning => "warning".yellow(),
+        Level::Note => "note   ".dimmed(),
+    }
+}
+
+pub async fn cmd_rules_lint(
+    client: &ApiClient,
+    dir: Option<&Path>,
+    allow_categories: &[String],
+    strict: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    // 読み込めなかったファイルは指摘として報告し、残りのファイルの検査を続ける
+    let mut invalid: Vec<(String, String, Vec<Issue>)> = Vec::new();
+    let (source, subjects): (String, Vec<Subject>) = match dir {
+        Some(dir) => {
+            let mut subjects = Vec::new();
+            for path in spec_paths(dir)? {
+                let location = path.display().to_string();
+                match load_spec(&path) {
+                    Ok(spec) => subjects.push(Subject {
+                        location,
+                        spec,
+                        triggered_count: None,
+                    }),
+                    Err(e) => invalid.push((
+                        path.file_name()
+                            .map(|n| n.to_string_lossy().into_owned())
+                            .unwrap_or_default(),
+                        location,
+                        vec![Issue {
+                            check: Check::InvalidFile,
+                            message: format!("{:#}", e),
+                        }],
+                    )),
+                }
+            }
+            (dir.display().to_string(), subjects)
+        }
+        None => {
+            let rules: Vec<DetectionRule> = client.get("/v1/rules").await?;
+            (
+                "サーバー".to_string(),
+                rules.iter().map(Subject::from).collect(),
+            )
+        }
+    };
+
+    let results = lint(&subjects, allow_categories);
+
+    // (ルール名, 所在, 指摘) 読み込めなかったファイルはファイル名をルール名の代わりに使う
+    let entries: Vec<(&str, &str, &[Issue])> =
+        subjects
+            .iter()
+            .zip(&results)
+            .map(|(s, issues)| (s.spec.name.as_str(), s.location.as_str(), issues.as_slice()))
+            .chain(invalid.iter().map(|(file, location, issues)| {
+                (file.as_str(), location.as_str(), issues.as_slice())
+            }))
+            .collect();
+
+    let count = |level: Level| {
+        entries
+            .iter()
+            .flat_map(|(_, _, issues)| issues.iter())
+            .filter(|i| i.check.level() == level)
+            .count()
+    };
+    let (errors, warnings, notes) = (
+        count(Level::Error),
+        count(Level::Warning),
+        count(Level::Note),
+    );
+
+    if output != OutputFormat::Text {
+        let cases = entries
+            .iter()
+            .map(|(name, location, issues)| ci::Case {
+                name: name.to_string(),
+                classname: format!("ghost.rules.{}", location),
+                findings: issues
+                    .iter()
+                    .filter(|i| i.check.level() > Level::Note)
+                    .map(|i| ci::Finding {
+                        rule_id: i.check.id().to_string(),
+                        rule_name: i.check.title().to_string(),
+                        level: i.check.level(),
+                        message: i.message.clone(),
+                    })
+                    .collect(),
+                error: None,
+            })
+            .collect();
+        CiReport::new("ghost rules lint", cases).print(output);
+    } else {
+        println!("\n{} ({})", "🧹 検出ルールのLint".bold(), source);
+
+        for (name, location, issues) in &entries {
+            if issues.is_empty() {
+                continue;
+            }
+            let worst = issues.iter().map(|i| i.check.level()).max();
+            let mark = match worst {
+                Some(Level::Error) => "✗".red(),
+                Some(Level::Warning) => "⚠".yellow(),
+                _ => "·".dimmed(),
+            };
+            println!("\n{} {} ({})", mark, name.bold(), location);
+            for issue in issues.iter() {
+                println!(
+                    "    {}  {:<26}  {}",
+                    level_colored(issue.check.level()),
+                    issue.check.id(),
+                    issue.message
+                );
+            }
+        }
+
+        println!(
+            "\n{}件を検査: エラー {} / 警告 {} / 情報 {}",
+            entries.len(),
+            if errors > 0 {
+                errors.to_string().red()
+            } else {
+                errors.to_string().green()
+            },
+            warnings,
+            notes
+        );
+        if errors == 0 && warnings == 0 {
+            println!("{} 問題は見つかりませんでした", "✓".green());
+        }
+        println!();
+    }
+
+    if errors > 0 || (strict && warnings > 0) {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subject(name: &str, conditions: &str, triggered_count: Option<u64>) -> Subject {
+        Subject {
+            location: name.to_string(),
+            spec: RuleSpec {
+                id: None,
+                name: name.to_string(),
+                description: "説明".to_string(),
+                severity: "high".to_string(),
+                category: "Authentication".to_string(),
+                enabled: true,
+                conditions: conditions.to_string(),
+                tactics: Vec::new(),
+                techniques: Vec::new(),
+            },
+            triggered_count,
+        }
+    }
+
+    fn checks(issues: &[Issue]) -> Vec<Check> {
+        issues.iter().map(|i| i.check).collect()
+    }
+
+    #[test]
+    fn test_lint() {
+        let mut subjects = vec![
+            subject("ok", "a == 1", Some(10)),
+            subject("Dup", "a == 1", Some(12)),
+            subject("dup ", "a ==", Some(8)),
+            subject("empty", "  ", Some(11)),
+            subject("noisy", "a == 1", Some(5000)),
+            subject("silent", "a == 1", Some(0)),
+        ];
+        subjects[0].spec.description = String::new();
+        subjects[0].spec.severity = "urgent".to_string();
+        subjects[0].spec.category = "Custom".to_string();
+
+        let results = lint(&subjects, &["Authentication".to_string()]);
+        assert_eq!(
+            checks(&results[0]),
+            vec![
+                Check::MissingDescription,
+                Check::InvalidSeverity,
+                Check::UnknownCategory
+            ]
+        );
+        assert_eq!(checks(&results[1]), vec![Check::DuplicateName]);
+        assert_eq!(
+            checks(&results[2]),
+            vec![Check::DuplicateName, Check::InvalidCondition]
+        );
+        assert_eq!(checks(&results[3]), vec![Check::EmptyCondition]);
+        assert_eq!(checks(&results[4]), vec![Check::NoisyRule]);
+        assert_eq!(checks(&results[5]), vec![Check::NeverTriggered]);
+
+        subjects[1].spec.tactics = vec!["credential-access".to_string(), "hacking".to_string()];
+        subjects[1].spec.techniques = vec!["T1110".to_string()];
+        let results = lint(&subjects, &["Authentication".to_string()]);
+        assert_eq!(results[1][1].check, Check::InvalidAttackTag);
+        assert!(results[1][1].message.ends_with(": hacking"));
+
+        // 許可したカテゴリは指摘しない
+        let results = lint(&subjects[..1], &["Custom".to_string()]);
+        assert!(!checks(&results[0]).contains(&Check::UnknownCategory));
+
+        // カテゴリを指定しなければ検査しない
+        let results = lint(&subjects[..1], &[]);
+        assert!(!checks(&results[0]).contains(&Check::UnknownCategory));
+    }
+}
@@ -0,0 +1,303 @@
+//! 検出ルールモジュール
+//!
+//! `/v1/rules` の検出ルールを一覧・詳細表示し、有効/無効の切り替えやYAMLとの同期、ローカルでのテストやLintを行う
+
+pub mod condition;
+pub mod lint;
+pub mod sigma;
+pub mod sync;
+pub mod tester;
+
+use anyhow::Result;
+use colored::*;
+use tabled::{Table, Tabled};
+
+use crate::util::bar;
+use crate::{ApiClient, DetectionRule, DetectionRuleStats};
+
+/// 重要度（高い順）
+pub const SEVERITIES: [&str; 5] = ["critical", "high", "medium", "low", "info"];
+
+/// 統計で表示する検知数上位のルール数
+const TOP_TRIGGERED: usize = 5;
+
+/// 重要度の並び順（未知の値は最後）
+pub fn severity_rank(severity: &str) -> usize {
+    SEVERITIES
+        .iter()
+        .position(|s| *s == severity)
+        .unwrap_or(SEVERITIES.len())
+}
+
+/// 重要度を色分け
+pub fn severity_colored(severity: &str) -> ColoredString {
+    match severity {
+        "critical" => severity.red().bold(),
+        "high" => severity.red(),
+        "medium" => severity.yellow(),
+        "low" => severity.cyan(),
+        _ => severity.normal(),
+    }
+}
+
+/// 一覧の絞り込み条件
+#[derive(Debug, Default)]
+pub struct Filter {
+    pub severity: Option<String>,
+    pub category: Option<String>,
+    pub enabled: Option<bool>,
+}
+
+impl Filter {
+    pub fn matches(&self, rule: &DetectionRule) -> bool {
+        self.severity.as_ref().is_none_or(|s| &rule.severity == s)
+            && self
+                .category
+                .as_ref()
+                .is_none_or(|c| rule.category.eq_ignore_ascii_case(c))
+            && self.enabled.is_none_or(|e| rule.enabled == e)
+    }
+}
+
+/// ID・ID前方一致・名前でルールを特定
+pub fn find_rule<'a>(rules: &'a [DetectionRule], key: &str) -> Result<&'a DetectionRule> {
+    if let Some(rule) = rules.iter().find(|r| r.id == key || r.name == key) {
+        return Ok(rule);
+    }
+
+    let candidates: Vec<&DetectionRule> = rules.iter().filter(|r| r.id.starts_with(key)).collect();
+    match candidates.as_slice() {
+        [rule] => Ok(rule),
+        [] => anyhow::bail!("ルールが見つかりません: {}", key),
+        _ => anyhow::bail!(
+            "複数のルールに一致します: {} ({})",
+            key,
+            candidates
+                .iter()
+                .map(|r| r.id.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// 一覧表示用にIDを短縮
+pub fn short_id(id: &str) -> &str {
+    id.get(..8).unwrap_or(id)
+}
+
+#[derive(Tabled)]
+struct RuleRow {
+    #[tabled(rename = "ID")]
+    id: String,
+    #[tabled(rename = "名前")]
+    name: String,
+    #[tabled(rename = "重要度")]
+    severity: String,
+    #[tabled(rename = "カテゴリ")]
+    category: String,
+    #[tabled(rename = "状態")]
+    enabled: String,
+    #[tabled(rename = "検知数")]
+    triggered_count: u64,
+}
+
+pub async fn cmd_rules_list(client: &ApiClient, filter: Filter) -> Result<()> {
+    let rules: Vec<DetectionRule> = client.get("/v1/rules").await?;
+    let total = rules.len();
+    let mut rules: Vec<DetectionRule> = rules.into_iter().filter(|r| filter.matches(r)).collect();
+    rules.sort_by(|a, b| {
+        severity_rank(&a.severity)
+            .cmp(&severity_rank(&b.severity))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    println!("\n{}", "🛡️ 検出ルール一覧".bold());
+
+    if rules.is_empty() {
+        println!("条件に一致するルールがありません");
+        return Ok(());
+    }
+
+    let shown = rules.len();
+    let rows: Vec<RuleRow> = rules
+        .into_iter()
+        .map(|r| RuleRow {
+            id: short_id(&r.id).to_string(),
+            severity: severity_colored(&r.severity).to_string(),
+            enabled: if r.enabled {
+                "有効".green().to_string()
+            } else {
+                "無効".dimmed().to_string()
+            },
+            name: r.name,
+            category: r.category,
+            triggered_count: r.triggered_count,
+        })
+        .collect();
+
+    println!("{}", Table::new(rows));
+    println!("{}件 / 全{}件", shown, total);
+
+    Ok(())
+}
+
+pub async fn cmd_rules_show(client: &ApiClient, key: &str) -> Result<()> {
+    let rules: Vec<DetectionRule> = client.get("/v1/rules").await?;
+    let rule = find_rule(&rules, key)?;
+
+    println!("\n{}", format!("🛡️ {}", rule.name).bold());
+    println!("{}", "=".repeat(40));
+    println!("ID:         {}", rule.id);
+    println!("重要度:     {}", severity_colored(&rule.severity));
+    println!("カテゴリ:   {}", rule.category);
+    println!(
+        "状態:       {}",
+        if rule.enabled {
+            "有効".green()
+        } else {
+            "無効".dimmed()
+        }
+    );
+    println!("検知数:     {}", rule.triggered_count);
+    println!("作成日時:   {}", rule.created_at);
+    println!("更新日時:   {}", rule.updated_at);
+    if !rule.description.is_empty() {
+        println!("\n--- 説明 ---\n{}", rule.description);
+    }
+    println!("\n--- 条件 ---\n{}", rule.conditions);
+    println!();
+
+    Ok(())
+}
+
+/// ルールの有効/無効を切り替え
+pub async fn cmd_rules_set_enabled(client: &ApiClient, key: &str, enabled: bool) -> Result<()> {
+    let rules: Vec<DetectionRule> = client.get("/v1/rules").await?;
+    let rule = find_rule(&rules, key)?;
+    let label = if enabled { "有効" } else { "無効" };
+
+    if rule.enabled == enabled {
+        println!("ルール「{}」は既に{}です", rule.name, label);
+        return Ok(());
+    }
+
+    let body = serde_json::json!({ "enabled": enabled }).to_string();
+    let _: serde_json::Value = client.put(&format!("/v1/rules/{}", rule.id), &body).await?;
+
+    println!(
+        "{} ルール「{}」を{}にしました",
+        "✓".green(),
+        rule.name,
+        label
+    );
+
+    Ok(())
+}
+
+pub async fn cmd_rules_stats(client: &ApiClient) -> Result<()> {
+    let (stats, rules) = tokio::try_join!(
+        client.get::<DetectionRuleStats>("/v1/rules/stats"),
+        client.get::<Vec<DetectionRule>>("/v1/rules"),
+    )?;
+
+    println!("\n{}", "📊 検出ルール統計".bold());
+    println!("{}", "=".repeat(40));
+    println!("ルール数:   {}", stats.total);
+    println!(
+        "有効:       {} / 無効: {}",
+        stats.enabled.to_string().green(),
+        stats.total.saturating_sub(stats.enabled)
+    );
+    println!("critical:   {}", stats.critical.to_string().red());
+
+    if !stats.by_category.is_empty() {
+        println!("\n--- カテゴリ別 ---");
+        let mut categories: Vec<_> = stats.by_category.iter().collect();
+        categories.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        let width = categories.iter().map(|(c, _)| c.len()).max().unwrap_or(0);
+        let max = categories.first().map(|(_, n)| **n).unwrap_or(0);
+        for (category, count) in categories {
+            println!(
+                "  {:<width$}  {:>4}  {}",
+                category,
+                count,
+                bar(*count, max, 30).cyan(),
+                width = width
+            );
+        }
+    }
+
+    let mut triggered: Vec<&DetectionRule> =
+        rules.iter().filter(|r| r.triggered_count > 0).collect();
+    if !triggered.is_empty() {
+        triggered.sort_by_key(|r| std::cmp::Reverse(r.triggered_count));
+        println!("\n--- 検知数上位 ---");
+        for rule in triggered.into_iter().take(TOP_TRIGGERED) {
+            println!(
+                "  {:>6}  {} [{}]",
+                rule.triggered_count,
+                rule.name,
+                severity_colored(&rule.severity)
+            );
+        }
+    }
+    println!();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(id: &str, name: &str, severity: &str, enabled: bool) -> DetectionRule {
+        DetectionRule {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: String::new(),
+            severity: severity.to_string(),
+            category: "Authentication".to_string(),
+            enabled,
+            conditions: String::new(),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            updated_at: "2024-01-01T00:00:00Z".to_string(),
+            triggered_count: 0,
+            tactics: None,
+            techniques: None,
+        }
+    }
+
+    #[test]
+    fn test_find_rule() {
+        let rules = vec![
+            rule("3f2a9c10-aaaa", "SSH brute force", "high", true),
+            rule("3f2b0000-bbbb", "Port scan", "medium", false),
+        ];
+
+        assert_eq!(find_rule(&rules, "Port scan").unwrap().id, "3f2b0000-bbbb");
+        assert_eq!(find_rule(&rules, "3f2a").unwrap().name, "SSH brute force");
+        assert!(find_rule(&rules, "3f2").is_err());
+        assert!(find_rule(&rules, "unknown").is_err());
+    }
+
+    #[test]
+    fn test_filter() {
+        let r = rule("1", "SSH brute force", "high", true);
+
+        assert!(Filter::default().matches(&r));
+        assert!(Filter {
+            severity: Some("high".to_string()),
+            category: Some("authentication".to_string()),
+            enabled: Some(true),
+        }
+        .matches(&r));
+        assert!(!Filter {
+            enabled: Some(false),
+            ..Default::default()
+        }
+        .matches(&r));
+        assert_eq!(severity_rank("critical"), 0);
+        assert_eq!(severity_rank("unknown"), SEVERITIES.len());
+    }
+}
@@ -0,0 +1,770 @@
+//! Sigmaルールの取り込み
+//!
+//! Sigma形式のYAMLをGhostの検出ルール（[`RuleSpec`]）に変換する。
+//! 変換できない修飾子・ワイルドカード・キーワード検索などを省略したルールは
+//! 本来より広く一致するため、無効状態で取り込む。集計条件を含むルールは変換しない。
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use colored::*;
+use serde::Deserialize;
+use serde_json::Value;
+use serde_yaml::{Mapping, Value as Yaml};
+
+use super::condition::{self, Expr, Op};
+use super::severity_colored;
+use super::sync::{self, RuleSpec};
+use crate::attack::{self, SigmaTag};
+use crate::{ApiClient, DetectionRule};
+
+/// 変換時に無視してよいメタデータ項目
+const IGNORED_KEYS: [&str; 12] = [
+    "title",
+    "id",
+    "status",
+    "description",
+    "references",
+    "author",
+    "date",
+    "modified",
+    "license",
+    "level",
+    "logsource",
+    "tags",
+];
+
+/// 変換結果
+#[derive(Debug)]
+pub struct Translation {
+    pub spec: RuleSpec,
+    /// 検出条件以外で変換できなかった内容や注意点
+    pub warnings: Vec<String>,
+    /// 変換できずに省略した検出条件（空でなければルールは無効状態）
+    pub dropped: Vec<String>,
+}
+
+/// 検出条件の変換中に記録する内容
+#[derive(Debug, Default)]
+struct Notes {
+    /// 省略した検出条件
+    dropped: Vec<String>,
+    /// 文字列を比較するフィールド（Sigmaは大文字小文字を区別しないが、条件式は区別する）
+    string_fields: BTreeSet<String>,
+}
+
+/// Sigmaの level を重要度に変換
+fn map_level(level: Option<&str>, warnings: &mut Vec<String>) -> String {
+    match level {
+        Some("informational") => "info".to_string(),
+        Some(level @ ("low" | "medium" | "high" | "critical")) => level.to_string(),
+        Some(other) => {
+            warnings.push(format!("level: 未知の値 \"{}\" (medium とします)", other));
+            "medium".to_string()
+        }
+        None => {
+            warnings.push("level: 未指定 (medium とします)".to_string());
+            "medium".to_string()
+        }
+    }
+}
+
+/// logsource からカテゴリを決定（category → service → product の順に参照）
+fn map_category(logsource: Option<&Yaml>, warnings: &mut Vec<String>) -> String {
+    let field = |key: &str| {
+        logsource
+            .and_then(|l| l.get(key))
+            .and_then(Yaml::as_str)
+            .map(str::to_string)
+    };
+
+    let Some(source) = field("category")
+        .or_else(|| field("service"))
+        .or_else(|| field("product"))
+    else {
+        warnings.push("logsource: カテゴリを特定できません (Sigma とします)".to_string());
+        return "Sigma".to_string();
+    };
+
+    match source.as_str() {
+        "authentication" | "sshd" | "auth" => "Authentication",
+        "network_connection" | "firewall" | "dns" | "dns_query" | "proxy" | "zeek" => "Network",
+        "process_creation" | "process_access" | "image_load" | "create_remote_thread" => "Process",
+        "file_event" | "file_access" | "file_change" | "file_delete" | "file_rename" => "File",
+        "webserver" => "Web",
+        other => other,
+    }
+    .to_string()
+}
+
+/// `attack.*` タグをタクティク/テクニックに変換
+fn map_tags(tags: Option<&Yaml>, warnings: &mut Vec<String>) -> (Vec<String>, Vec<String>) {
+    let mut tactics = Vec::new();
+    let mut techniques = Vec::new();
+    let mut unmapped = Vec::new();
+
+    for tag in tags
+        .and_then(Yaml::as_sequence)
+        .into_iter()
+        .flatten()
+        .filter_map(Yaml::as_str)
+    {
+        match attack::parse_sigma_tag(tag) {
+            Some(SigmaTag::Tactic(tactic)) => tactics.push(tactic.shortname.to_string()),
+            Some(SigmaTag::Technique(technique)) => techniques.push(technique),
+            None => unmapped.push(tag),
+        }
+    }
+    if !unmapped.is_empty() {
+        warnings.push(format!("tags: 未対応のタグ {}", unmapped.join(", ")));
+    }
+
+    (tactics, techniques)
+}
+
+fn to_json(value: &Yaml) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+/// 条件式のフィールド名として使えるか
+fn is_field_name(name: &str) -> bool {
+    name.chars()
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '.' | '-'))
+        && !matches!(
+            name.to_ascii_lowercase().as_str(),
+            "and" | "or" | "not" | "contains" | "startswith" | "endswith" | "in"
+        )
+}
+
+fn combine(exprs: Vec<Expr>, and: bool) -> Option<Expr> {
+    exprs.into_iter().reduce(|a, b| {
+        if and {
+            Expr::And(Box::new(a), Box::new(b))
+        } else {
+            Expr::Or(Box::new(a), Box::new(b))
+        }
+    })
+}
+
+/// 修飾子なしの文字列値のワイルドカードを演算子に変換
+fn wildcard(value: &str) -> Option<(Op, String)> {
+    if value == "*" {
+        return None;
+    }
+    let leading = value.starts_with('*');
+    let trailing = value.len() > 1 && value.ends_with('*');
+    let inner = &value[usize::from(leading)..value.len() - usize::from(trailing)];
+    if inner.contains(['*', '?']) {
+        return None;
+    }
+    let op = match (leading, trailing) {
+        (true, true) => Op::Contains,
+        (true, false) => Op::EndsWith,
+        (false, true) => Op::StartsWith,
+        (false, false) => Op::Eq,
+    };
+    Some((op, inner.to_string()))
+}
+
+/// 1つのフィールド条件（`Field|modifier: value`）を変換
+/// フィールドが存在し null でない（空文字列や `0` / `false` も存在とみなす）
+fn present(field: &str) -> Expr {
+    Expr::Compare {
+        field: field.to_string(),
+        op: Op::Ne,
+        value: Value::Null,
+    }
+}
+
+fn translate_field(key: &str, value: &Yaml, notes: &mut Notes) -> Option<Expr> {
+    let mut parts = key.split('|');
+    let field = parts.next().unwrap_or_default().to_string();
+    if !is_field_name(&field) {
+        notes
+            .dropped
+            .push(format!("{}: フィールド名を条件式で表現できません", key));
+        return None;
+    }
+
+    let mut op = None;
+    let mut all = false;
+    for modifier in parts {
+        match modifier {
+            "contains" => op = Some(Op::Contains),
+            "startswith" => op = Some(Op::StartsWith),
+            "endswith" => op = Some(Op::EndsWith),
+            "gt" => op = Some(Op::Gt),
+            "gte" => op = Some(Op::Ge),
+            "lt" => op = Some(Op::Lt),
+            "lte" => op = Some(Op::Le),
+            "all" => all = true,
+            "exists" => {
+                let exists = present(&field);
+                return Some(if value.as_bool() == Some(false) {
+                    Expr::Not(Box::new(exists))
+                } else {
+                    exists
+                });
+            }
+            other => {
+                notes
+                    .dropped
+                    .push(format!("{}: 修飾子 \"{}\" は未対応です", key, other));
+                return None;
+            }
+        }
+    }
+
+    let values: Vec<&Yaml> = match value {
+        Yaml::Sequence(items) => items.iter().collect(),
+        other => vec![other],
+    };
+
+    let mut exprs = Vec::new();
+    let mut equals = Vec::new();
+    for value in values {
+        // Sigma の null はフィールドがない（または null）ことを表す
+        if value.is_null() {
+            exprs.push(Expr::Not(Box::new(present(&field))));
+            continue;
+        }
+        let (value_op, value) = match (op, value.as_str()) {
+            (None, Some(text)) => match wildcard(text) {
+                Some((op, text)) => (op, Value::String(text)),
+                None => {
+                    notes
+                        .dropped
+                        .push(format!("{}: ワイルドカード \"{}\" は未対応です", key, text));
+                    continue;
+                }
+            },
+            (None, None) => (Op::Eq, to_json(value)),
+            (Some(op), _) => (op, to_json(value)),
+        };
+        if value.is_string() {
+            notes.string_fields.insert(field.clone());
+        }
+        if value_op == Op::Eq && !all {
+            equals.push(value);
+        } else {
+            exprs.push(Expr::Compare {
+                field: field.clone(),
+                op: value_op,
+                value,
+            });
+        }
+    }
+
+    match equals.len() {
+        0 => {}
+        1 => exprs.push(Expr::Compare {
+            field: field.clone(),
+            op: Op::Eq,
+            value: equals.remove(0),
+        }),
+        _ => exprs.push(Expr::Compare {
+            field: field.clone(),
+            op: Op::In,
+            value: Value::Array(equals),
+        }),
+    }
+
+    combine(exprs, all)
+}
+
+/// 検出項目（selection など）を変換
+fn translate_selection(name: &str, value: &Yaml, notes: &mut Notes) -> Option<Expr> {
+    match value {
+        Yaml::Mapping(map) => {
+            let mut exprs = Vec::new();
+            for (key, value) in map {
+                let Some(key) = key.as_str() else {
+                    notes
+                        .dropped
+                        .push(format!("{}: フィールド名が文字列ではありません", name));
+                    continue;
+                };
+                if let Some(expr) = translate_field(key, value, notes) {
+                    exprs.push(expr);
+                }
+            }
+            combine(exprs, true)
+        }
+        Yaml::Sequence(items) if items.iter().all(Yaml::is_mapping) => {
+            let exprs: Vec<Expr> = items
+                .iter()
+                .filter_map(|item| translate_selection(name, item, notes))
+                .collect();
+            combine(exprs, false)
+        }
+        _ => {
+            notes
+                .dropped
+                .push(format!("{}: キーワード検索は未対応です", name));
+            None
+        }
+    }
+}
+
+/// Sigmaの condition を変換（優先順位: not > and > or）
+struct ConditionParser<'a> {
+    tokens: Vec<String>,
+    pos: usize,
+    selections: &'a BTreeMap<String, Expr>,
+}
+
+impl ConditionParser<'_> {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.pos).map(String::as_str)
+    }
+
+    fn next(&mut self) -> Result<String> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .context("condition が途中で終了しています")?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn keyword(&self, keyword: &str) -> bool {
+        self.peek().is_some_and(|t| t.eq_ignore_ascii_case(keyword))
+    }
+
+    fn parse_or(&mut self) -> Result<Expr> {
+        let mut left = self.parse_and()?;
+        while self.keyword("or") {
+            self.pos += 1;
+            left = Expr::Or(Box::new(left), Box::new(self.parse_and()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr> {
+        let mut left = self.parse_not()?;
+        while self.keyword("and") {
+            self.pos += 1;
+            left = Expr::And(Box::new(left), Box::new(self.parse_not()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_not(&mut self) -> Result<Expr> {
+        if self.keyword("not") {
+            self.pos += 1;
+            return Ok(Expr::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr> {
+        let token = self.next()?;
+        match token.as_str() {
+            "(" => {
+                let expr = self.parse_or()?;
+                if self.next()? != ")" {
+                    anyhow::bail!("condition の括弧が閉じられていません");
+                }
+                Ok(expr)
+            }
+            "|" => anyhow::bail!("集計条件 (| count() など) は変換できません"),
+            quantifier if quantifier == "1" || quantifier.eq_ignore_ascii_case("all") => {
+                if !self.keyword("of") {
+                    anyhow::bail!("condition を解釈できません: {}", quantifier);
+                }
+                self.pos += 1;
+                let pattern = self.next()?;
+                let exprs: Vec<Expr> = self
+                    .selections
+                    .iter()
+                    .filter(|(name, _)| matches_pattern(&pattern, name))
+                    .map(|(_, expr)| expr.clone())
+                    .collect();
+                if exprs.is_empty() {
+                    anyhow::bail!("\"{}\" に一致する検出項目がありません", pattern);
+                }
+                Ok(combine(exprs, quantifier != "1").unwrap())
+            }
+            name => self
+                .selections
+                .get(name)
+                .cloned()
+                .with_context(|| format!("検出項目 \"{}\" を変換できません", name)),
+        }
+    }
+}
+
+/// `1 of selection_*` / `all of them` のパターン照合
+fn matches_pattern(pattern: &str, name: &str) -> bool {
+    if pattern == "them" {
+        return !name.starts_with('_');
+    }
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => pattern == name,
+    }
+}
+
+fn translate_condition(condition: &str, selections: &BTreeMap<String, Expr>) -> Result<Expr> {
+    let tokens: Vec<String> = condition
+        .replace('(', " ( ")
+        .replace(')', " ) ")
+        .replace('|', " | ")
+        .split_whitespace()
+        .map(str::to_string)
+        .collect();
+
+    let mut parser = ConditionParser {
+        tokens,
+        pos: 0,
+        selections,
+    };
+    let expr = parser.parse_or()?;
+    if let Some(token) = parser.peek() {
+        if token == "|" {
+            anyhow::bail!("集計条件 (| count() など) は変換できません");
+        }
+        anyhow::bail!("condition を解釈できません: {}", condition);
+    }
+
+    Ok(expr)
+}
+
+/// Sigmaルール1件を変換
+pub fn translate(text: &str) -> Result<Translation> {
+    let mut documents = serde_yaml::Deserializer::from_str(text);
+    let document = documents.next().context("YAMLが空です")?;
+    if documents.next().is_some() {
+        anyhow::bail!("複数ドキュメントのSigmaルール (ルールコレクション) は未対応です");
+    }
+    let rule = Mapping::deserialize(document).context("Sigmaルールの形式が不正です")?;
+    let get_str = |key: &str| rule.get(key).and_then(Yaml::as_str);
+
+    let name = get_str("title").context("title がありません")?.to_string();
+    let detection = rule
+        .get("detection")
+        .and_then(Yaml::as_mapping)
+        .context("detection がありません")?;
+
+    let mut warnings = Vec::new();
+    let mut notes = Notes::default();
+    let severity = map_level(get_str("level"), &mut warnings);
+    let category = map_category(rule.get("logsource"), &mut warnings);
+
+    let mut selections = BTreeMap::new();
+    let mut conditions = Vec::new();
+    for (key, value) in detection {
+        let Some(key) = key.as_str() else {
+            continue;
+        };
+        if key == "condition" {
+            match value {
+                Yaml::String(condition) => conditions.push(condition.clone()),
+                Yaml::Sequence(items) => {
+                    conditions.extend(items.iter().filter_map(Yaml::as_str).map(str::to_string))
+                }
+                _ => anyhow::bail!("condition の形式が不正です"),
+            }
+        } else if key == "timeframe" {
+            notes
+                .dropped
+                .push("timeframe: 時間窓は未対応です".to_string());
+        } else if let Some(expr) = translate_selection(key, value, &mut notes) {
+            selections.insert(key.to_string(), expr);
+        }
+    }
+    if conditions.is_empty() {
+        anyhow::bail!("condition がありません");
+    }
+
+    let exprs = conditions
+        .iter()
+        .map(|c| translate_condition(c, &selections))
+        .collect::<Result<Vec<_>>>()?;
+    let expr = combine(exprs, false).context("condition がありません")?;
+    let conditions = expr.to_string();
+    // 生成した条件式がGhostの構文として正しいことを確認
+    condition::parse(&conditions).context("生成した条件式を解釈できません")?;
+
+    let (tactics, techniques) = map_tags(rule.get("tags"), &mut warnings);
+
+    for key in rule.keys().filter_map(Yaml::as_str) {
+        if !IGNORED_KEYS.contains(&key) && key != "detection" {
+            warnings.push(format!("{}: 対応する項目がありません", key));
+        }
+    }
+    if !notes.string_fields.is_empty() {
+        warnings.push(format!(
+            "大文字小文字を区別して比較します (Sigmaは区別しません): {}",
+            notes
+                .string_fields
+                .into_iter()
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+
+    Ok(Translation {
+        spec: RuleSpec {
+            id: None,
+            name,
+            description: get_str("description")
+                .unwrap_or_default()
+                .trim()
+                .to_string(),
+            severity,
+            category,
+            // 条件を省略したルールは本来より広く一致するため有効化しない
+            enabled: notes.dropped.is_empty(),
+            conditions,
+            tactics,
+            techniques,
+        },
+        warnings,
+        dropped: notes.dropped,
+    })
+}
+
+/// ファイル、またはディレクトリ配下（再帰）の `*.yml` / `*.yaml` を列挙
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    if path.is_file() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(path)
+        .with_context(|| format!("ディレクトリの読み込みに失敗: {}", path.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .collect();
+    entries.sort();
+    for entry in entries {
+        if entry.is_dir() {
+            collect_files(&entry, files)?;
+        } else if entry
+            .extension()
+            .is_some_and(|ext| ext == "yml" || ext == "yaml")
+        {
+            files.push(entry);
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn cmd_rules_import_sigma(client: &ApiClient, path: &Path, dry_run: bool) -> Result<()> {
+    let mut files = Vec::new();
+    collect_files(path, &mut files)?;
+
+    println!(
+        "\n{} ({}件のファイル{})",
+        "📥 Sigmaルールの取り込み".bold(),
+        files.len(),
+        if dry_run { "、ドライラン" } else { "" }
+    );
+
+    let mut local: Vec<(PathBuf, RuleSpec)> = Vec::new();
+    let mut lossy = HashSet::new();
+    let mut failed = 0;
+    for file in files {
+        let result = std::fs::read_to_string(&file)
+            .with_context(|| format!("ファイルの読み込みに失敗: {}", file.display()))
+            .and_then(|text| translate(&text));
+
+        match result {
+            Ok(translation) => {
+                let spec = translation.spec;
+                println!(
+                    "\n{} {} → {} [{} / {}]",
+                    "✓".green(),
+                    file.display(),
+                    spec.name.bold(),
+                    severity_colored(&spec.severity),
+                    spec.category
+                );
+                println!("    条件: {}", spec.conditions);
+                for warning in &translation.warnings {
+                    println!("    {} {}", "⚠".yellow(), warning);
+                }
+                for dropped in &translation.dropped {
+                    println!("    {} 省略: {}", "✗".red(), dropped);
+                }
+                if !translation.dropped.is_empty() {
+                    println!(
+                        "    {}",
+                        "検出条件の一部を省略したため、無効状態で取り込みます".yellow()
+                    );
+                }
+                if let Some((other, _)) = local.iter().find(|(_, s)| s.name == spec.name) {
+                    println!(
+                        "    {} 同名のルールがあるためスキップします: {}",
+                        "⚠".yellow(),
+                        other.display()
+                    );
+                    continue;
+                }
+                if !translation.dropped.is_empty() {
+                    lossy.insert(spec.name.clone());
+                }
+                local.push((file, spec));
+            }
+            Err(e) => {
+                failed += 1;
+                println!("\n{} {}: {:#}", "✗".red(), file.display(), e);
+            }
+        }
+    }
+
+    println!();
+    if !local.is_empty() {
+        let remote: Vec<DetectionRule> = client.get("/v1/rules").await?;
+        // 既存ルールの有効/無効はサーバー側の設定を維持する（条件を省略したルールは無効のまま）
+        for (_, spec) in local.iter_mut().filter(|(_, s)| !lossy.contains(&s.name)) {
+            if let Some(rule) = remote.iter().find(|r| r.name == spec.name) {
+                spec.enabled = rule.enabled;
+            }
+        }
+        let tags = sync::tags_supported(&local, &remote);
+        let actions = sync::plan(&local, &remote, false, tags)?;
+        if actions.is_empty() {
+            println!("{} サーバーは最新です", "✓".green());
+        } else {
+            sync::print_plan(&actions);
+            let (create, update, _) = sync::summarize(&actions);
+            println!("\n作成: {} / 更新: {}", create, update);
+            if dry_run {
+                println!("(ドライランのため適用していません)");
+            } else {
+                sync::execute(client, &actions, tags).await?;
+            }
+        }
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{}件のSigmaルールを変換できませんでした", failed);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SSH_BRUTE_FORCE: &str = r#"
+title: SSH Brute Force
+id: 6b5c8e9a-0000-4000-8000-000000000001
+description: Multiple failed SSH logins
+logsource:
+  product: linux
+  service: sshd
+detection:
+  selection:
+    event_type: auth_failure
+    message|contains:
+      - 'Failed password'
+      - 'Invalid user'
+  filter_internal:
+    source.ip|startswith: '10.'
+  filter_null:
+    user: null
+  condition: selection and not 1 of filter_*
+falsepositives:
+  - Misconfigured clients
+tags:
+  - attack.credential_access
+  - attack.t1110.001
+  - car.2013-04-002
+level: high
+"#;
+
+    #[test]
+    fn test_translate() {
+        let translation = translate(SSH_BRUTE_FORCE).unwrap();
+        let spec = translation.spec;
+
+        assert_eq!(spec.name, "SSH Brute Force");
+        assert_eq!(spec.severity, "high");
+        assert_eq!(spec.category, "Authentication");
+        assert_eq!(
+            spec.conditions,
+            "event_type == \"auth_failure\" AND (message contains \"Failed password\" \
+             OR message contains \"Invalid user\") AND NOT (source.ip startswith \"10.\" \
+             OR NOT user != null)"
+        );
+        assert_eq!(spec.tactics, vec!["credential-access"]);
+        assert_eq!(spec.techniques, vec!["T1110.001"]);
+        assert_eq!(
+            translation.warnings,
+            vec![
+                "tags: 未対応のタグ car.2013-04-002",
+                "falsepositives: 対応する項目がありません",
+                "大文字小文字を区別して比較します (Sigmaは区別しません): event_type, message, source.ip"
+            ]
+        );
+        assert!(translation.dropped.is_empty());
+        assert!(spec.enabled);
+
+        let expr = condition::parse(&spec.conditions).unwrap();
+        let event = serde_json::json!({
+            "event_type": "auth_failure",
+            "message": "Failed password for root",
+            "source": { "ip": "203.0.113.9" },
+            "user": "root",
+        });
+        assert!(expr.eval(&event));
+
+        // null は値がないことのみを表し、空文字列とは区別する
+        let mut event = event;
+        event["user"] = serde_json::json!("");
+        assert!(expr.eval(&event));
+        event["user"] = serde_json::Value::Null;
+        assert!(!expr.eval(&event));
+        event.as_object_mut().unwrap().remove("user");
+        assert!(!expr.eval(&event));
+    }
+
+    #[test]
+    fn test_translate_unsupported() {
+        let translation = translate(
+            r#"
+title: Suspicious process
+logsource:
+  category: process_creation
+detection:
+  selection:
+    Image|endswith: '\cmd.exe'
+    CommandLine|re: '.*whoami.*'
+    ParentImage:
+      - '*\explorer.exe'
+      - 'C:\Windows\*\x.exe'
+  condition: all of them
+level: informational
+"#,
+        )
+        .unwrap();
+        assert_eq!(translation.spec.severity, "info");
+        assert_eq!(translation.spec.category, "Process");
+        assert_eq!(
+            translation.spec.conditions,
+            "Image endswith \"\\\\cmd.exe\" AND ParentImage endswith \"\\\\explorer.exe\""
+        );
+        // 省略した条件があるため無効状態で取り込む
+        assert_eq!(translation.dropped.len(), 2);
+        assert!(!translation.spec.enabled);
+
+        assert!(translate(
+            "title: x\ndetection:\n  keywords:\n    - evil\n  condition: keywords\n"
+        )
+        .is_err());
+        assert!(translate(
+            "title: x\ndetection:\n  sel:\n    a: 1\n  condition: sel | count() > 5\n"
+        )
+        .is_err());
+    }
+}
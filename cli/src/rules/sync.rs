@@ -0,0 +1,590 @@
+//! 検出ルールの同期
+//!
+//! ルールを1ファイル1ルールのYAMLとして書き出し、ディレクトリの内容とサーバーの差分適用を行う
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use colored::*;
+use serde::{Deserialize, Serialize};
+
+use super::short_id;
+use crate::util::slug;
+use crate::{ApiClient, DetectionRule};
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// YAMLファイルに保存するルール定義（サーバー管理の項目は含めない）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleSpec {
+    /// サーバー上のルールID（新規作成時は省略）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub severity: String,
+    pub category: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub conditions: String,
+    /// ATT&CK タクティク
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tactics: Vec<String>,
+    /// ATT&CK テクニックID
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub techniques: Vec<String>,
+}
+
+impl From<&DetectionRule> for RuleSpec {
+    fn from(rule: &DetectionRule) -> Self {
+        Self {
+            id: Some(rule.id.clone()),
+            name: rule.name.clone(),
+            description: rule.description.clone(),
+            severity: rule.severity.clone(),
+            category: rule.category.clone(),
+            enabled: rule.enabled,
+            conditions: rule.conditions.clone(),
+            tactics: rule.tactics.clone().unwrap_or_default(),
+            techniques: rule.techniques.clone().unwrap_or_default(),
+        }
+    }
+}
+
+impl RuleSpec {
+    /// サーバーへ送信するリクエストボディ（IDは含めない）
+    ///
+    /// `tags` が真ならタグを空でも送信してクリアし、偽ならタグを送信しない
+    fn body(&self, tags: bool) -> String {
+        let mut spec = self.clone();
+        spec.id = None;
+        let mut body = serde_json::to_value(&spec).unwrap_or_default();
+        if tags {
+            body["tactics"] = serde_json::json!(spec.tactics);
+            body["techniques"] = serde_json::json!(spec.techniques);
+        } else if let Some(body) = body.as_object_mut() {
+            body.remove("tactics");
+            body.remove("techniques");
+        }
+        body.to_string()
+    }
+}
+
+/// YAMLファイルからルール定義を読み込む
+pub fn load_spec(path: &Path) -> Result<RuleSpec> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("ルールファイルの読み込みに失敗: {}", path.display()))?;
+    serde_yaml::from_str(&text)
+        .with_context(|| format!("ルールファイルの形式が不正です: {}", path.display()))
+}
+
+/// ディレクトリ内の `*.yaml` / `*.yml` のパス（ファイル名順）
+pub fn spec_paths(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .with_context(|| format!("ディレクトリの読み込みに失敗: {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext == "yaml" || ext == "yml")
+        })
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+/// ディレクトリ内の `*.yaml` / `*.yml` をすべて読み込む（ファイル名順）
+pub fn load_specs(dir: &Path) -> Result<Vec<(PathBuf, RuleSpec)>> {
+    spec_paths(dir)?
+        .into_iter()
+        .map(|path| load_spec(&path).map(|spec| (path, spec)))
+        .collect()
+}
+
+/// 同期用にルール定義を読み込む（ルール名の重複はエラー）
+pub fn load_dir(dir: &Path) -> Result<Vec<(PathBuf, RuleSpec)>> {
+    let specs = load_specs(dir)?;
+
+    let mut names: HashMap<&str, &Path> = HashMap::new();
+    for (path, spec) in &specs {
+        if let Some(other) = names.insert(&spec.name, path) {
+            anyhow::bail!(
+                "ルール名が重複しています: {} ({}, {})",
+                spec.name,
+                other.display(),
+                path.display()
+            );
+        }
+    }
+
+    Ok(specs)
+}
+
+/// サーバーがルールのATT&CKタグを返すか（返さなければタグは比較・送信しない）
+///
+/// ローカルにタグがあるのに同期できない場合は警告する
+pub fn tags_supported(local: &[(PathBuf, RuleSpec)], remote: &[DetectionRule]) -> bool {
+    let supported = remote.iter().any(DetectionRule::has_attack_tags);
+    let tagged = local
+        .iter()
+        .any(|(_, spec)| !spec.tactics.is_empty() || !spec.techniques.is_empty());
+    if !supported && tagged {
+        eprintln!(
+            "{} サーバーがATT&CKタグを返さないため、tactics / techniques は同期しません",
+            "⚠".yellow()
+        );
+    }
+    supported
+}
+
+/// 項目ごとの変更内容
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub before: String,
+    pub after: String,
+}
+
+/// サーバーに対する操作
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    Create(RuleSpec),
+    Update {
+        id: String,
+        spec: RuleSpec,
+        changes: Vec<FieldChange>,
+    },
+    /// ローカルに存在しないルールを無効化（`--prune`）
+    Disable {
+        id: String,
+        name: String,
+    },
+}
+
+fn field_changes(rule: &DetectionRule, spec: &RuleSpec, tags: bool) -> Vec<FieldChange> {
+    let mut fields = vec![
+        ("name", rule.name.clone(), spec.name.clone()),
+        (
+            "description",
+            rule.description.clone(),
+            spec.description.clone(),
+        ),
+        ("severity", rule.severity.clone(), spec.severity.clone()),
+        ("category", rule.category.clone(), spec.category.clone()),
+        (
+            "enabled",
+            rule.enabled.to_string(),
+            spec.enabled.to_string(),
+        ),
+        (
+            "conditions",
+            rule.conditions.trim_end().to_string(),
+            spec.conditions.trim_end().to_string(),
+        ),
+    ];
+    if tags {
+        let join = |tags: &Option<Vec<String>>| tags.as_deref().unwrap_or_default().join(", ");
+        fields.push(("tactics", join(&rule.tactics), spec.tactics.join(", ")));
+        fields.push((
+            "techniques",
+            join(&rule.techniques),
+            spec.techniques.join(", "),
+        ));
+    }
+
+    fields
+        .into_iter()
+        .filter(|(_, before, after)| before != after)
+        .map(|(field, before, after)| FieldChange {
+            field,
+            before,
+            after,
+        })
+        .collect()
+}
+
+/// ローカル定義とサーバーの差分から操作一覧を作成
+///
+/// ルールはIDで対応付け、IDがないかサーバーに存在しない場合は名前で対応付ける。
+/// 複数のファイルが同じルールに対応する場合はエラー。`tags` が偽ならタグの差分は無視する
+pub fn plan(
+    local: &[(PathBuf, RuleSpec)],
+    remote: &[DetectionRule],
+    prune: bool,
+    tags: bool,
+) -> Result<Vec<Action>> {
+    let by_id: HashMap<&str, &DetectionRule> = remote.iter().map(|r| (r.id.as_str(), r)).collect();
+    let by_name: HashMap<&str, &DetectionRule> =
+        remote.iter().map(|r| (r.name.as_str(), r)).collect();
+
+    let mut actions = Vec::new();
+    let mut matched: HashMap<&str, &Path> = HashMap::new();
+
+    for (path, spec) in local {
+        let existing = spec
+            .id
+            .as_deref()
+            .and_then(|id| by_id.get(id))
+            .or_else(|| by_name.get(spec.name.as_str()));
+
+        match existing {
+            Some(rule) => {
+                if let Some(other) = matched.insert(rule.id.as_str(), path) {
+                    anyhow::bail!(
+                        "複数のファイルが同じルールに対応しています: {} ({}) ({}, {})",
+                        rule.name,
+                        short_id(&rule.id),
+                        other.display(),
+                        path.display()
+                    );
+                }
+                let changes = field_changes(rule, spec, tags);
+                if !changes.is_empty() {
+                    actions.push(Action::Update {
+                        id: rule.id.clone(),
+                        spec: spec.clone(),
+                        changes,
+                    });
+                }
+            }
+            None => actions.push(Action::Create(spec.clone())),
+        }
+    }
+
+    if prune {
+        for rule in remote {
+            if rule.enabled && !matched.contains_key(rule.id.as_str()) {
+                actions.push(Action::Disable {
+                    id: rule.id.clone(),
+                    name: rule.name.clone(),
+                });
+            }
+        }
+    }
+
+    Ok(actions)
+}
+
+/// 操作一覧を差分形式で表示
+pub fn print_plan(actions: &[Action]) {
+    for action in actions {
+        match action {
+            Action::Create(spec) => {
+                println!(
+                    "{} {} [{} / {}]",
+                    "+ 作成  ".green().bold(),
+                    spec.name,
+                    spec.severity,
+                    spec.category
+                );
+            }
+            Action::Update { spec, changes, .. } => {
+                println!("{} {}", "~ 更新  ".yellow().bold(), spec.name);
+                for change in changes {
+                    if change.before.contains('\n') || change.after.contains('\n') {
+                        println!("    {}:", change.field);
+                        for line in change.before.lines() {
+                            println!("      {}", format!("- {}", line).red());
+                        }
+                        for line in change.after.lines() {
+                            println!("      {}", format!("+ {}", line).green());
+                        }
+                    } else {
+                        println!(
+                            "    {}: {} → {}",
+                            change.field,
+                            change.before.red(),
+                            change.after.green()
+                        );
+                    }
+                }
+            }
+            Action::Disable { name, .. } => {
+                println!("{} {}", "- 無効化".red().bold(), name);
+            }
+        }
+    }
+}
+
+pub fn summarize(actions: &[Action]) -> (usize, usize, usize) {
+    actions
+        .iter()
+        .fold((0, 0, 0), |(c, u, d), action| match action {
+            Action::Create(_) => (c + 1, u, d),
+            Action::Update { .. } => (c, u + 1, d),
+            Action::Disable { .. } => (c, u, d + 1),
+        })
+}
+
+/// すべてのルールをYAMLファイルに書き出す
+pub async fn cmd_rules_export(client: &ApiClient, dir: &Path) -> Result<()> {
+    let rules: Vec<DetectionRule> = client.get("/v1/rules").await?;
+
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("ディレクトリの作成に失敗: {}", dir.display()))?;
+
+    let mut used = HashSet::new();
+    for rule in &rules {
+        let mut stem = slug(&rule.name, short_id(&rule.id));
+        if !used.insert(stem.clone()) {
+            stem = format!("{}-{}", stem, short_id(&rule.id));
+            used.insert(stem.clone());
+        }
+        let path = dir.join(format!("{}.yaml", stem));
+        let yaml = serde_yaml::to_string(&RuleSpec::from(rule))?;
+        std::fs::write(&path, yaml)
+            .with_context(|| format!("ルールファイルの書き込みに失敗: {}", path.display()))?;
+    }
+
+    println!(
+        "{} {}件のルールを書き出しました: {}",
+        "✓".green(),
+        rules.len(),
+        dir.display()
+    );
+
+    Ok(())
+}
+
+/// 操作を順に実行（失敗しても残りを続行し、最後にまとめてエラーにする）
+pub async fn execute(client: &ApiClient, actions: &[Action], tags: bool) -> Result<()> {
+    let mut failed = 0;
+    for action in actions {
+        let (label, result) = match action {
+            Action::Create(spec) => (
+                spec.name.as_str(),
+                client
+                    .post::<serde_json::Value>("/v1/rules", Some(&spec.body(tags)))
+                    .await,
+            ),
+            Action::Update { id, spec, .. } => (
+                spec.name.as_str(),
+                client
+                    .put::<serde_json::Value>(&format!("/v1/rules/{}", id), &spec.body(tags))
+                    .await,
+            ),
+            Action::Disable { id, name } => (
+                name.as_str(),
+                client
+                    .put::<serde_json::Value>(
+                        &format!("/v1/rules/{}", id),
+                        &serde_json::json!({ "enabled": false }).to_string(),
+                    )
+                    .await,
+            ),
+        };
+        if let Err(e) = result {
+            failed += 1;
+            println!("{} {}: {:#}", "✗".red(), label, e);
+        }
+    }
+
+    let (create, update, disable) = summarize(actions);
+    if failed > 0 {
+        anyhow::bail!("{}件の操作に失敗しました", failed);
+    }
+    println!(
+        "{} 適用しました (作成: {} / 更新: {} / 無効化: {})",
+        "✓".green(),
+        create,
+        update,
+        disable
+    );
+
+    Ok(())
+}
+
+pub async fn cmd_rules_diff(client: &ApiClient, dir: &Path, prune: bool) -> Result<()> {
+    let local = load_dir(dir)?;
+    let remote: Vec<DetectionRule> = client.get("/v1/rules").await?;
+    let tags = tags_supported(&local, &remote);
+    let actions = plan(&local, &remote, prune, tags)?;
+
+    println!(
+        "\n{} ({} ⇔ サーバー)",
+        "🛡️ 検出ルールの差分".bold(),
+        dir.display()
+    );
+
+    if actions.is_empty() {
+        println!("{} 差分はありません", "✓".green());
+        return Ok(());
+    }
+
+    print_plan(&actions);
+    let (create, update, disable) = summarize(&actions);
+    println!(
+        "\n作成: {} / 更新: {} / 無効化: {}",
+        create, update, disable
+    );
+
+    Ok(())
+}
+
+pub async fn cmd_rules_apply(client: &ApiClient, dir: &Path, prune: bool) -> Result<()> {
+    let local = load_dir(dir)?;
+    let remote: Vec<DetectionRule> = client.get("/v1/rules").await?;
+    let tags = tags_supported(&local, &remote);
+    let actions = plan(&local, &remote, prune, tags)?;
+
+    println!("\n{} ({})", "🛡️ 検出ルールの適用".bold(), dir.display());
+
+    if actions.is_empty() {
+        println!("{} サーバーは最新です", "✓".green());
+        return Ok(());
+    }
+
+    print_plan(&actions);
+    println!();
+
+    execute(client, &actions, tags).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remote(id: &str, name: &str, severity: &str, enabled: bool) -> DetectionRule {
+        DetectionRule {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: String::new(),
+            severity: severity.to_string(),
+            category: "Authentication".to_string(),
+            enabled,
+            conditions: "event_type == \"auth_failure\"".to_string(),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            updated_at: "2024-01-01T00:00:00Z".to_string(),
+            triggered_count: 7,
+            tactics: None,
+            techniques: None,
+        }
+    }
+
+    #[test]
+    fn test_yaml_round_trip() {
+        let spec = RuleSpec::from(&remote("r1", "SSH brute force", "high", true));
+        let yaml = serde_yaml::to_string(&spec).unwrap();
+
+        assert!(yaml.contains("id: r1"));
+        assert!(!yaml.contains("triggered_count"));
+        assert_eq!(serde_yaml::from_str::<RuleSpec>(&yaml).unwrap(), spec);
+
+        let minimal: RuleSpec = serde_yaml::from_str(
+            "name: New\nseverity: low\ncategory: Network\nconditions: |\n  port == 23\n",
+        )
+        .unwrap();
+        assert!(minimal.enabled);
+        assert!(minimal.id.is_none());
+        assert!(serde_yaml::from_str::<RuleSpec>("name: x\nunknown: 1\n").is_err());
+    }
+
+    #[test]
+    fn test_spec_paths() {
+        let dir = std::env::temp_dir().join(format!("ghost-spec-paths-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["b.yml", "a.yaml", "notes.txt"] {
+            std::fs::write(dir.join(name), "name: [broken\n").unwrap();
+        }
+
+        let paths = spec_paths(&dir).unwrap();
+        let names: Vec<_> = paths.iter().map(|p| p.file_name().unwrap()).collect();
+        assert_eq!(names, ["a.yaml", "b.yml"]);
+        assert!(load_spec(&paths[0]).is_err());
+        assert!(load_specs(&dir).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_plan() {
+        let remote = vec![
+            remote("r1", "SSH brute force", "high", true),
+            remote("r2", "Port scan", "medium", true),
+            remote("r3", "Legacy", "low", true),
+            remote("r4", "Already disabled", "low", false),
+        ];
+
+        let mut renamed = RuleSpec::from(&remote[0]);
+        renamed.name = "SSH brute force (v2)".to_string();
+        renamed.severity = "critical".to_string();
+        // IDなしでも名前で対応付け
+        let mut by_name = RuleSpec::from(&remote[1]);
+        by_name.id = None;
+        let mut created = RuleSpec::from(&remote[1]);
+        created.id = None;
+        created.name = "Root login".to_string();
+
+        let local: Vec<(PathBuf, RuleSpec)> = [renamed, by_name, created]
+            .into_iter()
+            .map(|spec| (PathBuf::from("x.yaml"), spec))
+            .collect();
+
+        let actions = plan(&local, &remote, false, true).unwrap();
+        assert_eq!(actions.len(), 2);
+        match &actions[0] {
+            Action::Update { id, changes, .. } => {
+                assert_eq!(id, "r1");
+                let fields: Vec<&str> = changes.iter().map(|c| c.field).collect();
+                assert_eq!(fields, vec!["name", "severity"]);
+            }
+            other => panic!("unexpected action: {:?}", other),
+        }
+        assert!(matches!(&actions[1], Action::Create(spec) if spec.name == "Root login"));
+
+        let actions = plan(&local, &remote, true, true).unwrap();
+        assert_eq!(
+            actions.last(),
+            Some(&Action::Disable {
+                id: "r3".to_string(),
+                name: "Legacy".to_string()
+            })
+        );
+        assert_eq!(summarize(&actions), (1, 1, 1));
+    }
+
+    #[test]
+    fn test_tags_unsupported() {
+        let remote = vec![remote("r1", "SSH brute force", "high", true)];
+        let mut spec = RuleSpec::from(&remote[0]);
+        spec.tactics = vec!["credential-access".to_string()];
+        let local = vec![(PathBuf::from("a.yaml"), spec.clone())];
+
+        // タグを返さないサーバーではタグの差分で更新し続けない
+        assert!(!tags_supported(&local, &remote));
+        assert!(plan(&local, &remote, false, false).unwrap().is_empty());
+        assert!(!spec.body(false).contains("tactics"));
+
+        let mut tagged = remote.clone();
+        tagged[0].tactics = Some(Vec::new());
+        assert!(tags_supported(&local, &tagged));
+        assert_eq!(plan(&local, &tagged, false, true).unwrap().len(), 1);
+        assert!(spec.body(true).contains("\"techniques\":[]"));
+    }
+
+    #[test]
+    fn test_plan_rejects_duplicate_targets() {
+        let remote = vec![
+            remote("r1", "SSH brute force", "high", true),
+            remote("r2", "Port scan", "medium", true),
+        ];
+
+        // 一方はIDで、もう一方は名前で r1 に対応する
+        let mut by_id = RuleSpec::from(&remote[0]);
+        by_id.name = "SSH brute force (v2)".to_string();
+        let mut by_name = RuleSpec::from(&remote[0]);
+        by_name.id = None;
+
+        let local = vec![
+            (PathBuf::from("a.yaml"), by_id),
+            (PathBuf::from("b.yaml"), by_name),
+        ];
+        let err = plan(&local, &remote, false, true).unwrap_err().to_string();
+        assert!(err.contains("a.yaml"));
+        assert!(err.contains("b.yaml"));
+    }
+}
@@ -0,0 +1,200 @@
+//! 検出ルールのオフラインテスト
+//!
+//! ルール定義（YAML）の条件式を記録済みイベント（NDJSON）に対してローカルで評価する
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use colored::*;
+use serde_json::Value;
+
+use super::condition::{self, Expr};
+use super::severity_colored;
+use super::sync::load_spec;
+
+/// 一覧表示でイベントを要約する最大文字数
+const SUMMARY_WIDTH: usize = 100;
+
+/// NDJSONの1行分のイベント
+pub struct Event {
+    pub line: usize,
+    pub value: Value,
+}
+
+/// NDJSONを読み込む（空行は無視、不正な行は行番号付きでエラーとして返す）
+pub fn parse_events(text: &str) -> (Vec<Event>, Vec<(usize, String)>) {
+    let mut events = Vec::new();
+    let mut errors = Vec::new();
+
+    for (i, line) in text.lines().enumerate() {
+        let line_no = i + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<Value>(line) {
+            Ok(value) if value.is_object() => events.push(Event {
+                line: line_no,
+                value,
+            }),
+            Ok(_) => errors.push((line_no, "JSONオブジェクトではありません".to_string())),
+            Err(e) => errors.push((line_no, e.to_string())),
+        }
+    }
+
+    (events, errors)
+}
+
+/// 条件式で参照しているフィールドだけを並べてイベントを要約
+fn summarize(event: &Value, fields: &[&str]) -> String {
+    let parts: Vec<String> = fields
+        .iter()
+        .filter_map(|field| {
+            condition::lookup(event, field).map(|v| match v {
+                Value::String(s) => format!("{}={}", field, s),
+                other => format!("{}={}", field, other),
+            })
+        })
+        .collect();
+
+    let summary = if parts.is_empty() {
+        event.to_string()
+    } else {
+        parts.join(" ")
+    };
+
+    if summary.chars().count() > SUMMARY_WIDTH {
+        let truncated: String = summary.chars().take(SUMMARY_WIDTH - 1).collect();
+        format!("{}…", truncated)
+    } else {
+        summary
+    }
+}
+
+/// 各条件（最上位のAND項）に一致したイベント数
+pub fn clause_hits<'a>(expr: &'a Expr, events: &[Event]) -> Vec<(&'a Expr, usize)> {
+    expr.clauses()
+        .into_iter()
+        .map(|clause| {
+            let hits = events.iter().filter(|e| clause.eval(&e.value)).count();
+            (clause, hits)
+        })
+        .collect()
+}
+
+pub fn cmd_rules_test(rule_path: &Path, events_path: &str, matched_only: bool) -> Result<()> {
+    let spec = load_spec(rule_path)?;
+    let expr = condition::parse(&spec.conditions)
+        .with_context(|| format!("条件式を解釈できません: {}", rule_path.display()))?;
+
+    let text = if events_path == "-" {
+        std::io::read_to_string(std::io::stdin()).context("標準入力の読み込みに失敗")?
+    } else {
+        std::fs::read_to_string(events_path)
+            .with_context(|| format!("イベントファイルの読み込みに失敗: {}", events_path))?
+    };
+    let (events, errors) = parse_events(&text);
+    let fields = expr.fields();
+
+    println!("\n{}", format!("🧪 ルールテスト: {}", spec.name).bold());
+    println!("{}", "=".repeat(40));
+    println!("条件:   {}", spec.conditions.trim());
+    println!("イベント: {}件 ({})", events.len(), events_path);
+
+    let (matched, unmatched): (Vec<&Event>, Vec<&Event>) =
+        events.iter().partition(|e| expr.eval(&e.value));
+
+    println!("\n--- 一致したイベント ({}件) ---", matched.len());
+    for event in &matched {
+        println!(
+            "  {} {:>5}行目  {}",
+            "✓".green(),
+            event.line,
+            summarize(&event.value, &fields)
+        );
+    }
+
+    if !matched_only {
+        println!("\n--- 一致しなかったイベント ({}件) ---", unmatched.len());
+        for event in &unmatched {
+            println!(
+                "  {} {:>5}行目  {}",
+                "·".dimmed(),
+                event.line,
+                summarize(&event.value, &fields).dimmed()
+            );
+        }
+    }
+
+    let clauses = clause_hits(&expr, &events);
+    if clauses.len() > 1 {
+        println!("\n--- 条件別の一致数 ---");
+        for (clause, hits) in clauses {
+            println!("  {:>5}  {}", hits, clause);
+        }
+    }
+
+    if !errors.is_empty() {
+        println!("\n--- 読み込めなかった行 ({}件) ---", errors.len());
+        for (line, error) in &errors {
+            println!("  {} {:>5}行目  {}", "✗".red(), line, error);
+        }
+    }
+
+    let rate = if events.is_empty() {
+        0.0
+    } else {
+        matched.len() as f64 / events.len() as f64 * 100.0
+    };
+    println!(
+        "\n一致: {} / {}件 ({:.1}%)",
+        matched.len().to_string().bold(),
+        events.len(),
+        rate
+    );
+    if matched.is_empty() {
+        println!("アラートは発生しません");
+    } else {
+        println!(
+            "発生するアラート: {} × {}件",
+            severity_colored(&spec.severity),
+            matched.len()
+        );
+        if !spec.enabled {
+            println!(
+                "{} ルールは無効 (enabled: false) のため、サーバーでは検知されません",
+                "⚠".yellow()
+            );
+        }
+    }
+    println!();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_events() {
+        let text = "{\"a\": 1}\n\n[1, 2]\nnot json\n{\"a\": 2}\n";
+        let (events, errors) = parse_events(text);
+
+        assert_eq!(
+            events.iter().map(|e| e.line).collect::<Vec<_>>(),
+            vec![1, 5]
+        );
+        assert_eq!(
+            errors.iter().map(|(line, _)| *line).collect::<Vec<_>>(),
+            vec![3, 4]
+        );
+
+        let expr = condition::parse("a >= 1 AND a < 2").unwrap();
+        let hits: Vec<usize> = clause_hits(&expr, &events)
+            .into_iter()
+            .map(|(_, n)| n)
+            .collect();
+        assert_eq!(hits, vec![2, 1]);
+        assert_eq!(summarize(&events[1].value, &["a", "missing"]), "a=2");
+    }
+}
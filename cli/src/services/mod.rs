@@ -0,0 +1,301 @@
+//! サービス公開監査モジュール
+//!
+//! `/v1/services` から全インターフェース（`0.0.0.0` / `::`）で待ち受けるサービスを抽出し、
+//! 許可リストとの差分と前回実行時からの公開状態の変化を報告する
+
+pub mod policy;
+pub mod snapshot;
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::Result;
+use chrono::Utc;
+use colored::*;
+use serde::{Deserialize, Serialize};
+
+use crate::agents::is_wildcard_bind;
+use crate::ci::{self, CiReport, Level, OutputFormat};
+use crate::util::format_duration;
+use crate::{ApiClient, ServiceWithAgent};
+use policy::Policy;
+
+/// 全インターフェースで待ち受けているサービス
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Exposure {
+    pub agent_id: String,
+    pub hostname: String,
+    pub name: String,
+    pub port: u16,
+    pub protocol: String,
+    pub bind_address: String,
+}
+
+impl Exposure {
+    /// 同一の公開とみなすキー
+    fn key(&self) -> (String, u16, String, String) {
+        (
+            self.agent_id.clone(),
+            self.port,
+            self.protocol.to_lowercase(),
+            self.bind_address.clone(),
+        )
+    }
+
+    fn label(&self) -> String {
+        format!(
+            "{}/{} {} ({})",
+            self.port, self.protocol, self.name, self.bind_address
+        )
+    }
+}
+
+/// 停止中のサービスを除き、全インターフェースで待ち受けるものを抽出
+pub fn exposures(services: Vec<ServiceWithAgent>) -> Vec<Exposure> {
+    let mut exposures: Vec<Exposure> = services
+        .into_iter()
+        .filter(|s| s.service.status != "stopped")
+        .filter_map(|s| {
+            let bind_address = s.service.bind_address.filter(|b| is_wildcard_bind(b))?;
+            Some(Exposure {
+                agent_id: s.agent_id,
+                hostname: s.hostname,
+                name: s.service.name,
+                port: s.service.port,
+                protocol: s.service.protocol,
+                bind_address,
+            })
+        })
+        .collect();
+    // 同一の公開が隣り合うようにキー全体で並べてから重複を除く
+    exposures.sort_by_key(Exposure::key);
+    exposures.dedup_by(|a, b| a.key() == b.key());
+    exposures
+}
+
+pub async fn cmd_services_audit(
+    client: &ApiClient,
+    allow: Option<&Path>,
+    output: OutputFormat,
+) -> Result<()> {
+    let policy = allow.map(Policy::load).transpose()?.unwrap_or_default();
+    let services: Vec<ServiceWithAgent> = client.get("/v1/services").await?;
+    let now = Utc::now();
+
+    let exposures = exposures(services);
+    let previous = snapshot::load(&client.base_url);
+    let drift = previous
+        .as_ref()
+        .map(|p| snapshot::drift(&p.exposures, &exposures));
+    let is_new = |exposure: &Exposure| {
+        drift
+            .as_ref()
+            .is_some_and(|d| d.opened.iter().any(|e| e.key() == exposure.key()))
+    };
+
+    let unexpected = exposures
+        .iter()
+        .filter(|e| policy.find(e).is_none())
+        .count();
+
+    let mut by_host: BTreeMap<(&str, &str), Vec<&Exposure>> = BTreeMap::new();
+    for exposure in &exposures {
+        by_host
+            .entry((&exposure.hostname, &exposure.agent_id))
+            .or_default()
+            .push(exposure);
+    }
+
+    if output != OutputFormat::Text {
+        let cases = by_host
+            .iter()
+            .map(|((hostname, agent_id), exposures)| ci::Case {
+                name: format!("{} ({})", hostname, agent_id),
+                classname: format!("ghost.services.{}", hostname),
+                findings: exposures
+                    .iter()
+                    .filter(|e| policy.find(e).is_none())
+                    .map(|e| ci::Finding {
+                        rule_id: "services/unexpected-exposure".to_string(),
+                        rule_name: "許可リスト外の公開サービス".to_string(),
+                        level: Level::Error,
+                        message: format!(
+                            "{} が全インターフェースで待ち受けています{}",
+                            e.label(),
+                            if is_new(e) {
+                                " (前回から新規)"
+                            } else {
+                                ""
+                            }
+                        ),
+                    })
+                    .collect(),
+                error: None,
+            })
+            .collect();
+        CiReport::new("ghost services audit", cases).print(output);
+    } else {
+        println!("\n{}", "🌐 サービス公開監査".bold());
+        match allow {
+            Some(path) => println!(
+                "許可リスト: {} ({}件)",
+                path.display(),
+                policy.entries.len()
+            ),
+            None => println!(
+                "{}",
+                "許可リスト未指定: すべての公開サービスを許可外として扱います".dimmed()
+            ),
+        }
+        if let Some(previous) = &previous {
+            println!(
+                "前回の監査: {} ({}前)",
+                previous.recorded_at.format("%Y-%m-%d %H:%M"),
+                format_duration(now - previous.recorded_at)
+            );
+        }
+
+        if exposures.is_empty() {
+            println!(
+                "{}",
+                "全インターフェースで待ち受けるサービスはありません".green()
+            );
+        }
+
+        for ((hostname, agent_id), exposures) in &by_host {
+            println!("\n{} ({})", hostname.bold(), agent_id);
+            for exposure in exposures {
+                let new = if is_new(exposure) {
+                    " [新規]".yellow().to_string()
+                } else {
+                    String::new()
+                };
+                let port = format!("{}/{}", exposure.port, exposure.protocol);
+                match policy.find(exposure) {
+                    Some(entry) => println!(
+                        "  {} {:<10} {:<16} {:<8} {}{}",
+                        "✓".green(),
+                        port,
+                        exposure.name,
+                        exposure.bind_address,
+                        format!("許可: {}", entry.comment.as_deref().unwrap_or("-")).dimmed(),
+                        new
+                    ),
+                    None => println!(
+                        "  {} {:<10} {:<16} {:<8} {}{}",
+                        "✗".red(),
+                        port,
+                        exposure.name,
+                        exposure.bind_address,
+                        "許可リストにありません".red(),
+                        new
+                    ),
+                }
+            }
+        }
+
+        match &drift {
+            Some(drift) if drift.is_empty() => {
+                println!("\n{}", "前回から公開状態の変化はありません".dimmed())
+            }
+            Some(drift) => {
+                println!("\n--- 前回からの変化 ---");
+                for exposure in &drift.opened {
+                    let status = if policy.find(exposure).is_some() {
+                        "許可".green()
+                    } else {
+                        "許可外".red()
+                    };
+                    println!(
+                        "  {} {} {} [{}]",
+                        "+".red().bold(),
+                        exposure.hostname,
+                        exposure.label(),
+                        status
+                    );
+                }
+                for exposure in &drift.closed {
+                    println!(
+                        "  {} {} {}",
+                        "-".green().bold(),
+                        exposure.hostname,
+                        exposure.label()
+                    );
+                }
+            }
+            None => println!(
+                "\n{}",
+                "前回の監査結果がないため、今回の結果を基準として保存します".dimmed()
+            ),
+        }
+
+        println!(
+            "\n公開サービス {}件 / ホスト {}台 / 許可外 {}",
+            exposures.len(),
+            by_host.len(),
+            if unexpected > 0 {
+                unexpected.to_string().red()
+            } else {
+                unexpected.to_string().green()
+            }
+        );
+        println!();
+    }
+
+    snapshot::save_or_warn(&client.base_url, &exposures, now);
+
+    if unexpected > 0 {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_exposures() {
+        let services: Vec<ServiceWithAgent> = serde_json::from_value(json!([
+            { "agent_id": "ag2", "hostname": "web-02", "service": {
+                "name": "redis", "port": 6379, "status": "running", "protocol": "tcp",
+                "bind_address": "0.0.0.0", "last_checked": "2026-10-01T00:00:00Z" } },
+            { "agent_id": "ag1", "hostname": "web-01", "service": {
+                "name": "nginx", "port": 443, "status": "unknown", "protocol": "tcp",
+                "bind_address": "::", "last_checked": "2026-10-01T00:00:00Z" } },
+            { "agent_id": "ag1", "hostname": "web-01", "service": {
+                "name": "nginx-alt", "port": 443, "status": "running", "protocol": "tcp",
+                "bind_address": "0.0.0.0", "last_checked": "2026-10-01T00:00:00Z" } },
+            // 隣り合わない重複
+            { "agent_id": "ag1", "hostname": "web-01", "service": {
+                "name": "nginx", "port": 443, "status": "running", "protocol": "TCP",
+                "bind_address": "::", "last_checked": "2026-10-01T00:00:00Z" } },
+            { "agent_id": "ag1", "hostname": "web-01", "service": {
+                "name": "postgres", "port": 5432, "status": "running", "protocol": "tcp",
+                "bind_address": "127.0.0.1", "last_checked": "2026-10-01T00:00:00Z" } },
+            { "agent_id": "ag1", "hostname": "web-01", "service": {
+                "name": "old", "port": 8080, "status": "stopped", "protocol": "tcp",
+                "bind_address": "0.0.0.0", "last_checked": "2026-10-01T00:00:00Z" } },
+            { "agent_id": "ag1", "hostname": "web-01", "service": {
+                "name": "dns", "port": 53, "status": "running", "protocol": "udp",
+                "bind_address": null, "last_checked": "2026-10-01T00:00:00Z" } },
+        ]))
+        .unwrap();
+
+        let exposures = exposures(services);
+        let labels: Vec<String> = exposures
+            .iter()
+            .map(|e| format!("{} {}", e.hostname, e.label()))
+            .collect();
+        assert_eq!(
+            labels,
+            vec![
+                "web-01 443/tcp nginx-alt (0.0.0.0)",
+                "web-01 443/tcp nginx (::)",
+                "web-02 6379/tcp redis (0.0.0.0)"
+            ]
+        );
+    }
+}
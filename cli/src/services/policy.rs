@@ -0,0 +1,157 @@
+//! 公開サービスの許可リスト
+//!
+//! 全インターフェースで待ち受けてよいサービスを `policy.toml` に記述する
+//!
+//! ```toml
+//! # 全ホストで SSH を許可
+//! [[allow]]
+//! ports = [22]
+//!
+//! [[allow]]
+//! hosts = ["web-*"]
+//! ports = [80, 443]
+//! protocol = "tcp"
+//! comment = "公開Webサーバー"
+//! ```
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use super::Exposure;
+
+/// 許可エントリ（指定した条件をすべて満たすサービスを許可）
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Allow {
+    /// 対象ホスト名またはエージェントID（`*` ワイルドカード可、未指定時は全ホスト）
+    pub hosts: Vec<String>,
+    pub ports: Vec<u16>,
+    /// プロトコル（未指定時は問わない）
+    pub protocol: Option<String>,
+    /// サービス名（未指定時は問わない）
+    pub service: Option<String>,
+    pub comment: Option<String>,
+}
+
+impl Allow {
+    pub fn matches(&self, exposure: &Exposure) -> bool {
+        self.ports.contains(&exposure.port)
+            && (self.hosts.is_empty()
+                || self.hosts.iter().any(|pattern| {
+                    glob_match(pattern, &exposure.hostname)
+                        || glob_match(pattern, &exposure.agent_id)
+                }))
+            && self
+                .protocol
+                .as_ref()
+                .is_none_or(|p| p.eq_ignore_ascii_case(&exposure.protocol))
+            && self
+                .service
+                .as_ref()
+                .is_none_or(|s| s.eq_ignore_ascii_case(&exposure.name))
+    }
+}
+
+/// 許可リスト
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Policy {
+    #[serde(default, rename = "allow")]
+    pub entries: Vec<Allow>,
+}
+
+impl Policy {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("許可リストの読み込みに失敗: {}", path.display()))?;
+        let policy: Policy = toml::from_str(&text)
+            .with_context(|| format!("許可リストの形式が不正です: {}", path.display()))?;
+        for (i, entry) in policy.entries.iter().enumerate() {
+            anyhow::ensure!(
+                !entry.ports.is_empty(),
+                "{}番目の [[allow]] に ports がありません",
+                i + 1
+            );
+        }
+        Ok(policy)
+    }
+
+    /// 一致する許可エントリ
+    pub fn find(&self, exposure: &Exposure) -> Option<&Allow> {
+        self.entries.iter().find(|entry| entry.matches(exposure))
+    }
+}
+
+/// `*` のみを解釈する簡易ワイルドカード照合（大文字小文字を区別しない）
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.to_lowercase();
+    let text = text.to_lowercase();
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // ワイルドカードなし
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("web-*", "WEB-01"));
+        assert!(glob_match("*", "db-01"));
+        assert!(glob_match("*-01", "db-01"));
+        assert!(glob_match("w*b*1", "web-01"));
+        assert!(glob_match("db-01", "db-01"));
+        assert!(!glob_match("db-01", "db-011"));
+        assert!(!glob_match("web-*", "db-01"));
+        assert!(!glob_match("a*bc", "abc-c"));
+    }
+
+    #[test]
+    fn test_policy() {
+        let policy: Policy = toml::from_str(
+            r#"
+[[allow]]
+ports = [22]
+
+[[allow]]
+hosts = ["web-*"]
+ports = [80, 443]
+protocol = "tcp"
+"#,
+        )
+        .unwrap();
+
+        let exposure = |hostname: &str, port: u16, protocol: &str| Exposure {
+            agent_id: "ag1".to_string(),
+            hostname: hostname.to_string(),
+            name: "svc".to_string(),
+            port,
+            protocol: protocol.to_string(),
+            bind_address: "0.0.0.0".to_string(),
+        };
+        assert!(policy.find(&exposure("db-01", 22, "tcp")).is_some());
+        assert!(policy.find(&exposure("web-01", 443, "TCP")).is_some());
+        assert!(policy.find(&exposure("web-01", 443, "udp")).is_none());
+        assert!(policy.find(&exposure("db-01", 443, "tcp")).is_none());
+
+        assert!(toml::from_str::<Policy>("[[allow]]\nport = 22\n").is_err());
+    }
+}
@@ -0,0 +1,141 @@
+//! 公開状態のスナップショット
+//!
+//! 監査ごとに公開サービスを `~/.ghost/services_snapshots.json` にサーバーURL別に保存し、
+//! 同じサーバーの前回からの変化を検出する
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use colored::*;
+use serde::{Deserialize, Serialize};
+
+use super::Exposure;
+use crate::config;
+
+/// 前回実行時の公開サービス
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub recorded_at: DateTime<Utc>,
+    pub exposures: Vec<Exposure>,
+}
+
+/// サーバーURLごとのスナップショット
+type Snapshots = BTreeMap<String, Snapshot>;
+
+/// スナップショットのパス
+pub fn snapshot_path() -> Option<PathBuf> {
+    config::ghost_dir().map(|dir| dir.join("services_snapshots.json"))
+}
+
+/// 保存済みのスナップショットをすべて読み込む（存在しない・壊れている場合は空）
+fn load_all() -> Snapshots {
+    snapshot_path()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+/// 指定サーバーの前回のスナップショットを読み込む
+pub fn load(server: &str) -> Option<Snapshot> {
+    load_all().remove(server)
+}
+
+pub fn save(server: &str, exposures: &[Exposure], now: DateTime<Utc>) -> Result<()> {
+    let Some(path) = snapshot_path() else {
+        return Ok(());
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("ディレクトリの作成に失敗: {}", dir.display()))?;
+    }
+    let mut snapshots = load_all();
+    snapshots.insert(
+        server.to_string(),
+        Snapshot {
+            recorded_at: now,
+            exposures: exposures.to_vec(),
+        },
+    );
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_string_pretty(&snapshots)?)
+        .with_context(|| format!("スナップショットの書き込みに失敗: {}", tmp.display()))?;
+    std::fs::rename(&tmp, &path)
+        .with_context(|| format!("スナップショットの書き込みに失敗: {}", path.display()))?;
+    Ok(())
+}
+
+pub fn save_or_warn(server: &str, exposures: &[Exposure], now: DateTime<Utc>) {
+    if let Err(e) = save(server, exposures, now) {
+        eprintln!(
+            "{} 公開状態のスナップショットを保存できませんでした: {:#}",
+            "⚠".yellow(),
+            e
+        );
+    }
+}
+
+/// 前回からの変化
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Drift<'a> {
+    /// 新たに公開された
+    pub opened: Vec<&'a Exposure>,
+    /// 公開されなくなった
+    pub closed: Vec<&'a Exposure>,
+}
+
+impl Drift<'_> {
+    pub fn is_empty(&self) -> bool {
+        self.opened.is_empty() && self.closed.is_empty()
+    }
+}
+
+/// 前回と今回の公開サービスを比較（ホスト・ポート・プロトコル・バインド先で同一視）
+pub fn drift<'a>(previous: &'a [Exposure], current: &'a [Exposure]) -> Drift<'a> {
+    let before: BTreeSet<_> = previous.iter().map(Exposure::key).collect();
+    let after: BTreeSet<_> = current.iter().map(Exposure::key).collect();
+    Drift {
+        opened: current
+            .iter()
+            .filter(|e| !before.contains(&e.key()))
+            .collect(),
+        closed: previous
+            .iter()
+            .filter(|e| !after.contains(&e.key()))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exposure(agent_id: &str, port: u16, bind_address: &str) -> Exposure {
+        Exposure {
+            agent_id: agent_id.to_string(),
+            hostname: format!("host-{}", agent_id),
+            name: "svc".to_string(),
+            port,
+            protocol: "tcp".to_string(),
+            bind_address: bind_address.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_drift() {
+        let previous = vec![exposure("ag1", 22, "0.0.0.0"), exposure("ag1", 8080, "::")];
+        let mut current = vec![
+            exposure("ag1", 22, "0.0.0.0"),
+            exposure("ag2", 8080, "::"),
+            exposure("ag1", 6379, "0.0.0.0"),
+        ];
+        // サービス名の変更は変化とみなさない
+        current[0].name = "sshd".to_string();
+
+        let drift = drift(&previous, &current);
+        assert_eq!(drift.opened, vec![&current[1], &current[2]]);
+        assert_eq!(drift.closed, vec![&previous[1]]);
+        assert!(super::drift(&previous, &previous).is_empty());
+    }
+}
@@ -0,0 +1,192 @@
+//! システムステータスモジュール
+//!
+//! ヘルスチェックに加えて、接続の各段階の所要時間・バージョン差異・認証・ストレージの状態を表示
+
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use chrono::Duration;
+use colored::*;
+
+use crate::probe::{self, format_millis, Endpoint};
+use crate::util::{format_duration, parse_version};
+use crate::{ApiClient, HealthResponse, StorageSettings, UserInfo};
+
+/// CLIのバージョン
+pub const CLI_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// サーバーとCLIのバージョン差異
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionMatch {
+    Same,
+    /// パッチバージョンのみ異なる
+    Patch,
+    /// メジャー/マイナーバージョンが異なる
+    Mismatch,
+    Unknown,
+}
+
+/// `/v1/auth/me` で現在の利用者を確認（認証が必要で未認証の場合はなし）
+pub async fn whoami(client: &ApiClient) -> Result<Option<UserInfo>> {
+    let response = client
+        .client
+        .get(format!("{}/api/v1/auth/me", client.base_url))
+        .send()
+        .await
+        .context("APIリクエスト失敗")?;
+    let status = response.status();
+    if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+        return Ok(None);
+    }
+    ApiClient::parse(response).await.map(Some)
+}
+
+pub fn compare_versions(server: &str, cli: &str) -> VersionMatch {
+    match (parse_version(server), parse_version(cli)) {
+        (Some(s), Some(c)) if s == c => VersionMatch::Same,
+        (Some(s), Some(c)) if (s.0, s.1) == (c.0, c.1) => VersionMatch::Patch,
+        (Some(_), Some(_)) => VersionMatch::Mismatch,
+        _ => VersionMatch::Unknown,
+    }
+}
+
+fn ok_mark(ok: bool) -> ColoredString {
+    if ok {
+        "✓".green()
+    } else {
+        "✗".red()
+    }
+}
+
+pub async fn cmd_status(client: &ApiClient) -> Result<()> {
+    let endpoint = Endpoint::from_url(&client.base_url)?;
+    let timings = probe::probe(&endpoint).await;
+
+    let started = Instant::now();
+    let health: Result<HealthResponse> = client.get("/health").await;
+    let latency = started.elapsed();
+
+    println!("\n{}", "🛡️ Ghost Security Monitor".bold());
+    println!("{}", "=".repeat(40));
+
+    match &health {
+        Ok(health) => {
+            let status = if health.status == "ok" || health.status == "healthy" {
+                health.status.green()
+            } else {
+                health.status.yellow()
+            };
+            println!("ステータス: {}", status);
+
+            let version_note = match compare_versions(&health.version, CLI_VERSION) {
+                VersionMatch::Same => "一致".green(),
+                VersionMatch::Patch => "パッチ差異".yellow(),
+                VersionMatch::Mismatch => "バージョン不一致".red(),
+                VersionMatch::Unknown => "比較不可".dimmed(),
+            };
+            println!(
+                "バージョン: {} (CLI: {}) {}",
+                health.version, CLI_VERSION, version_note
+            );
+            if let Some(uptime) = health.uptime_secs {
+                println!(
+                    "稼働時間:   {}",
+                    format_duration(Duration::seconds(uptime as i64))
+                );
+            }
+        }
+        Err(e) => println!("ステータス: {} {:#}", "接続不可".red(), e),
+    }
+
+    println!("\n--- 接続 ---");
+    println!("サーバー:   {}", client.base_url);
+    match &timings {
+        Ok(t) => {
+            println!("アドレス:   {}", t.addr);
+            println!("DNS解決:    {}", format_millis(t.dns));
+            println!("TCP接続:    {}", format_millis(t.tcp));
+            match &t.tls {
+                Some(tls) => println!(
+                    "TLS:        {} ({}, {})",
+                    format_millis(tls.handshake),
+                    tls.version,
+                    tls.cipher_suite
+                ),
+                None => println!("TLS:        {}", "なし (http)".yellow()),
+            }
+        }
+        Err(e) => println!("{} {:#}", ok_mark(false), e),
+    }
+    if health.is_ok() {
+        println!("API応答:    {}", format_millis(latency));
+    }
+
+    println!("\n--- 認証 ---");
+    match whoami(client).await {
+        Ok(Some(user)) => println!(
+            "{} {} ({})",
+            ok_mark(true),
+            user.username,
+            user.roles.join(", ")
+        ),
+        Ok(None) => println!("{} 未認証 (サーバーが認証を要求しています)", "-".yellow()),
+        Err(e) => println!("{} {:#}", ok_mark(false), e),
+    }
+
+    println!("\n--- ストレージ ---");
+    match client.get::<StorageSettings>("/v1/settings/storage").await {
+        Ok(storage) => {
+            let db = &storage.primary_db_info;
+            println!(
+                "{} プライマリDB: {} {}:{}/{}{}",
+                ok_mark(db.connected),
+                db.db_type,
+                db.host,
+                db.port,
+                db.database,
+                if db.connected {
+                    String::new()
+                } else {
+                    format!(" {}", "未接続".red())
+                }
+            );
+            match &storage.timeseries {
+                Some(ts) if ts.enabled => println!(
+                    "{} 時系列DB:     {} {}:{}",
+                    "•".cyan(),
+                    ts.db_type,
+                    ts.host,
+                    ts.port
+                ),
+                _ => println!("{} 時系列DB:     無効", "-".dimmed()),
+            }
+            match &storage.cache {
+                Some(cache) if cache.enabled => println!(
+                    "{} キャッシュ:   {} {}:{}",
+                    "•".cyan(),
+                    cache.cache_type,
+                    cache.host,
+                    cache.port
+                ),
+                _ => println!("{} キャッシュ:   無効", "-".dimmed()),
+            }
+        }
+        Err(e) => println!("{} 取得できません: {:#}", ok_mark(false), e),
+    }
+    println!();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("0.1.0", "0.1.0"), VersionMatch::Same);
+        assert_eq!(compare_versions("0.1.3", "0.1.0"), VersionMatch::Patch);
+        assert_eq!(compare_versions("1.0.0", "0.1.0"), VersionMatch::Mismatch);
+        assert_eq!(compare_versions("dev", "0.1.0"), VersionMatch::Unknown);
+    }
+}
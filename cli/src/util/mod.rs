@@ -0,0 +1,214 @@
+//! 共通ユーティリティ
+//!
+//! 期間指定のパースと端末向けの簡易チャート描画
+
+use std::io::{IsTerminal, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use chrono::Duration;
+
+/// スパークラインに使用するブロック文字
+const SPARK_CHARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// 指定できる期間の上限（日）
+const MAX_DURATION_DAYS: i64 = 36500;
+
+/// `30s` / `15m` / `1h` / `7d` / `2w` 形式の期間をパース（単位は必須）
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (num, unit) = s.split_at(split);
+
+    let value: i64 = num
+        .parse()
+        .map_err(|_| format!("期間の形式が不正です: {} (例: 30s, 15m, 1h, 7d)", s))?;
+
+    let duration = match unit {
+        "s" => Duration::try_seconds(value),
+        "m" => Duration::try_minutes(value),
+        "h" => Duration::try_hours(value),
+        "d" => Duration::try_days(value),
+        "w" => Duration::try_weeks(value),
+        "" => {
+            return Err(format!(
+                "期間の単位を指定してください: {} (s, m, h, d, w)",
+                s
+            ))
+        }
+        _ => return Err(format!("不明な期間の単位です: {} (s, m, h, d, w)", unit)),
+    }
+    .filter(|d| *d <= Duration::days(MAX_DURATION_DAYS))
+    .ok_or_else(|| format!("期間が長すぎます: {} (最大 {}d)", s, MAX_DURATION_DAYS))?;
+
+    if duration <= Duration::zero() {
+        return Err("期間は0より大きい値を指定してください".to_string());
+    }
+
+    Ok(duration)
+}
+
+/// 期間を「1日2時間」のような表記に変換
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.num_seconds().max(0);
+    let (days, hours, mins) = (secs / 86400, secs % 86400 / 3600, secs % 3600 / 60);
+
+    if days > 0 && hours > 0 {
+        format!("{}日{}時間", days, hours)
+    } else if days > 0 {
+        format!("{}日", days)
+    } else if hours > 0 && mins > 0 {
+        format!("{}時間{}分", hours, mins)
+    } else if hours > 0 {
+        format!("{}時間", hours)
+    } else if mins > 0 {
+        format!("{}分", mins)
+    } else {
+        format!("{}秒", secs)
+    }
+}
+
+/// `1.2.3` / `v1.2.3-beta` 形式のバージョンを (major, minor, patch) に変換
+pub fn parse_version(s: &str) -> Option<(u64, u64, u64)> {
+    let core = s.trim().trim_start_matches('v');
+    let core = core.split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|p| p.parse::<u64>());
+    let major = parts.next()?.ok()?;
+    let minor = parts.next().unwrap_or(Ok(0)).ok()?;
+    let patch = parts.next().unwrap_or(Ok(0)).ok()?;
+    Some((major, minor, patch))
+}
+
+/// 値の列をスパークラインに変換
+pub fn sparkline(values: &[u64]) -> String {
+    let max = values.iter().copied().max().unwrap_or(0);
+    values
+        .iter()
+        .map(|&v| {
+            let level = (v * (SPARK_CHARS.len() as u64 - 1))
+                .checked_div(max)
+                .unwrap_or(0);
+            SPARK_CHARS[level as usize]
+        })
+        .collect()
+}
+
+/// 最大値に対する割合で横棒グラフを描画
+pub fn bar(value: u64, max: u64, width: usize) -> String {
+    if max == 0 {
+        return String::new();
+    }
+    let len = ((value as f64 / max as f64) * width as f64).round() as usize;
+    "█".repeat(len.max(if value > 0 { 1 } else { 0 }))
+}
+
+/// 名前から識別子・ファイル名に使える文字列を生成（英数字以外は `-`、空なら `fallback`）
+pub fn slug(name: &str, fallback: &str) -> String {
+    let slug = name
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() {
+        fallback.to_string()
+    } else {
+        slug
+    }
+}
+
+/// 標準エラー出力に描画する進捗バー（端末でない場合は何も表示しない）
+pub struct Progress {
+    label: String,
+    total: usize,
+    done: AtomicUsize,
+    enabled: bool,
+}
+
+impl Progress {
+    /// 進捗バーの幅
+    const WIDTH: usize = 30;
+
+    pub fn new(label: &str, total: usize) -> Self {
+        let progress = Self {
+            label: label.to_string(),
+            total,
+            done: AtomicUsize::new(0),
+            enabled: std::io::stderr().is_terminal(),
+        };
+        progress.draw(0);
+        progress
+    }
+
+    /// 1件完了
+    pub fn inc(&self) {
+        let done = self.done.fetch_add(1, Ordering::Relaxed) + 1;
+        self.draw(done);
+    }
+
+    /// 進捗表示を消去
+    pub fn finish(&self) {
+        if self.enabled {
+            eprint!("\r\x1b[2K");
+            let _ = std::io::stderr().flush();
+        }
+    }
+
+    fn draw(&self, done: usize) {
+        if !self.enabled {
+            return;
+        }
+        let filled = (done * Self::WIDTH).checked_div(self.total).unwrap_or(0);
+        eprint!(
+            "\r{} [{}{}] {}/{}",
+            self.label,
+            "█".repeat(filled),
+            "░".repeat(Self::WIDTH - filled),
+            done,
+            self.total
+        );
+        let _ = std::io::stderr().flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("30s").unwrap(), Duration::seconds(30));
+        assert_eq!(parse_duration("15m").unwrap(), Duration::minutes(15));
+        assert_eq!(parse_duration("1h").unwrap(), Duration::hours(1));
+        assert_eq!(parse_duration("7d").unwrap(), Duration::days(7));
+        assert!(parse_duration("0d").is_err());
+        assert!(parse_duration("7x").is_err());
+        assert!(parse_duration("d").is_err());
+        // 単位の省略とオーバーフローはエラー
+        assert!(parse_duration("24").is_err());
+        assert!(parse_duration("999999999999999d").is_err());
+        assert!(parse_duration("36501d").is_err());
+    }
+
+    #[test]
+    fn test_slug() {
+        assert_eq!(
+            slug("SSH Brute Force <web-01>", "x"),
+            "ssh-brute-force-web-01"
+        );
+        assert_eq!(slug("ルート ログイン", "rule"), "rule");
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("1.2.3"), Some((1, 2, 3)));
+        assert_eq!(parse_version("v0.4.0-beta.1"), Some((0, 4, 0)));
+        assert_eq!(parse_version("2"), Some((2, 0, 0)));
+        assert_eq!(parse_version("unknown"), None);
+    }
+
+    #[test]
+    fn test_sparkline() {
+        assert_eq!(sparkline(&[0, 0]), "▁▁");
+        assert_eq!(sparkline(&[0, 7, 14]), "▁▄█");
+    }
+}